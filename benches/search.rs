@@ -0,0 +1,59 @@
+//! Compares a full scan with the search index on a history of 10k records.
+//!
+//! Run with `cargo bench --bench search`.
+
+use ropy::repository::ClipboardRepository;
+use ropy::repository::models::SearchScope;
+use std::time::{Duration, Instant};
+
+const RECORDS: usize = 10_000;
+const ITERATIONS: u32 = 20;
+const QUERIES: [&str; 3] = ["invoice 4242", "meeting", "no such text"];
+
+fn main() {
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let repo = ClipboardRepository::init(
+        temp_dir.path().join("bench.db"),
+        temp_dir.path().join("images"),
+    )
+    .expect("Failed to create repository");
+
+    for i in 0..RECORDS {
+        let content = match i % 3 {
+            0 => format!("invoice {i} for order #{}", i * 7),
+            1 => format!("meeting notes {i}: discuss the roadmap"),
+            _ => format!("https://example.com/articles/{i}?ref=clipboard"),
+        };
+        repo.save_text(content).expect("Failed to save");
+    }
+
+    println!("{RECORDS} records, {ITERATIONS} iterations per query");
+    for query in QUERIES {
+        let scan = time(|| {
+            repo.search_scan(query, SearchScope::TextOnly)
+                .unwrap()
+                .len()
+        });
+        let index = time(|| {
+            repo.search_with_scope(query, SearchScope::TextOnly)
+                .unwrap()
+                .len()
+        });
+        println!(
+            "{query:>14}: scan {:>10.2?}  index {:>10.2?}  ({:.1}x)",
+            scan,
+            index,
+            scan.as_secs_f64() / index.as_secs_f64()
+        );
+    }
+}
+
+/// Average duration of one search after a warm-up run
+fn time(search: impl Fn() -> usize) -> Duration {
+    search();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(search());
+    }
+    start.elapsed() / ITERATIONS
+}
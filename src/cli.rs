@@ -0,0 +1,123 @@
+//! Command-line subcommands, run instead of starting the app
+//!
+//! `ropy check [--repair] [--profile <name>]` checks the clipboard history for records
+//! whose image is gone, image files no record uses and unreadable records.
+
+use std::collections::HashSet;
+
+use ropy::config::profile;
+use ropy::repository::ClipboardRepository;
+
+/// Options of the `check` subcommand
+#[derive(Debug, Default, PartialEq)]
+struct CheckArgs {
+    repair: bool,
+    profile: Option<String>,
+}
+
+fn parse_check_args(args: &[String]) -> Result<CheckArgs, String> {
+    let mut parsed = CheckArgs::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--repair" => parsed.repair = true,
+            "--profile" => {
+                let name = iter.next().ok_or("--profile needs a profile name")?;
+                parsed.profile = Some(name.clone());
+            }
+            // Handled by the logger
+            "--log-level" => {
+                iter.next();
+            }
+            other if other.starts_with("--log-level=") => {}
+            other => return Err(format!("Unknown argument: {other}")),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Run the subcommand given in `args`, returning its exit code, or `None` to start the app
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.get(1).map(String::as_str) {
+        Some("check") => Some(check(&args[2..])),
+        _ => None,
+    }
+}
+
+/// Exit code 0 when the history is healthy or was repaired, 1 when problems were left, 2 on errors
+fn check(args: &[String]) -> i32 {
+    let args = match parse_check_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("Usage: ropy check [--repair] [--profile <name>]");
+            return 2;
+        }
+    };
+    let name = args.profile.unwrap_or_else(profile::load_last_profile);
+    if !profile::is_valid_profile_name(&name) {
+        eprintln!("Invalid profile name: {name}");
+        return 2;
+    }
+    profile::set_active_profile(&name);
+
+    // Fails while Ropy is running, as the database is locked by the app
+    let repository = match ClipboardRepository::new(&name) {
+        Ok(repository) => repository,
+        Err(e) => {
+            eprintln!("Failed to open the history of profile {name}: {e}");
+            return 2;
+        }
+    };
+    match repository.verify(args.repair, &HashSet::new()) {
+        Ok(report) => {
+            println!("Profile {name}: {report}");
+            if report.is_clean() || report.repaired {
+                0
+            } else {
+                println!("Run `ropy check --repair` to fix these problems");
+                1
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to check the history: {e}");
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_check_args() {
+        assert_eq!(parse_check_args(&[]), Ok(CheckArgs::default()));
+        assert_eq!(
+            parse_check_args(&args(&["--repair", "--profile", "work"])),
+            Ok(CheckArgs {
+                repair: true,
+                profile: Some("work".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_check_args(&args(&["--log-level", "debug", "--repair"])),
+            Ok(CheckArgs {
+                repair: true,
+                profile: None,
+            })
+        );
+        assert!(parse_check_args(&args(&["--profile"])).is_err());
+        assert!(parse_check_args(&args(&["--fix"])).is_err());
+    }
+
+    #[test]
+    fn test_run_without_subcommand() {
+        assert_eq!(run(&args(&["ropy"])), None);
+        assert_eq!(run(&args(&["ropy", "--silent"])), None);
+    }
+}
@@ -0,0 +1,55 @@
+//! Accumulate several captures into a single record.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Separator placed between texts appended to the same record
+pub const APPEND_SEPARATOR: &str = "\n";
+
+/// Switch shared by the UI and the capture task.
+///
+/// While active, captured text is appended to the latest record instead of creating a new one.
+#[derive(Debug, Default)]
+pub struct AppendMode {
+    active: AtomicBool,
+}
+
+impl AppendMode {
+    /// Whether captured text is currently appended to the latest record
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Turn the mode on or off, returning the new state
+    pub fn toggle(&self) -> bool {
+        !self.active.fetch_xor(true, Ordering::AcqRel)
+    }
+}
+
+/// Content of an accumulating record after appending `text`
+pub fn append_text(existing: &str, text: &str) -> String {
+    if existing.is_empty() {
+        return text.to_string();
+    }
+    format!("{existing}{APPEND_SEPARATOR}{text}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        let mode = AppendMode::default();
+        assert!(!mode.is_active());
+        assert!(mode.toggle());
+        assert!(mode.is_active());
+        assert!(!mode.toggle());
+        assert!(!mode.is_active());
+    }
+
+    #[test]
+    fn test_append_text() {
+        assert_eq!(append_text("first", "second"), "first\nsecond");
+        assert_eq!(append_text("", "only"), "only");
+    }
+}
@@ -0,0 +1,191 @@
+//! Wiping the OS clipboard a while after Ropy copied something to it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_channel::Sender;
+
+use super::{CopyRequest, LastCopyState};
+use crate::repository::models::ContentType;
+
+/// What the clipboard should still hold for a pending clear to go ahead
+#[derive(Debug, Clone, PartialEq)]
+enum Expected {
+    Text(String),
+    /// Hash of the copied image, known once the monitor has seen it land on the clipboard
+    Image(Option<u64>),
+}
+
+/// Clipboard clear scheduled by copies, shared by the UI and the clipboard monitor.
+///
+/// A clear only happens if the clipboard still holds what Ropy copied, as last seen by the
+/// monitor, so something copied in the meantime is never wiped. The clear is announced
+/// through `LastCopyState`, so the empty clipboard is not captured.
+pub struct AutoClear {
+    expected: Mutex<Option<Expected>>,
+    /// Bumped on every copy, so a pending clear knows a newer copy came after it
+    generation: AtomicU64,
+    copy_tx: Sender<CopyRequest>,
+    last_copy: Arc<Mutex<LastCopyState>>,
+}
+
+impl AutoClear {
+    pub fn new(copy_tx: Sender<CopyRequest>, last_copy: Arc<Mutex<LastCopyState>>) -> Self {
+        Self {
+            expected: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            copy_tx,
+            last_copy,
+        }
+    }
+
+    /// Note that `content` is being copied and return the generation to pass to
+    /// [`Self::clear`], or `None` for content that is never cleared
+    pub fn expect(&self, content: &str, content_type: &ContentType) -> Option<u64> {
+        let expected = match content_type {
+            ContentType::Text | ContentType::Svg => Expected::Text(content.to_string()),
+            ContentType::Image => Expected::Image(None),
+            _ => return None,
+        };
+        *self.expected() = Some(expected);
+        Some(self.generation.fetch_add(1, Ordering::AcqRel) + 1)
+    }
+
+    /// Clear the clipboard after `delay`, unless it changed in the meantime
+    pub fn schedule(self: &Arc<Self>, content: &str, content_type: &ContentType, delay: Duration) {
+        let Some(generation) = self.expect(content, content_type) else {
+            return;
+        };
+        let auto_clear = self.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(delay);
+            auto_clear.clear(generation);
+        });
+    }
+
+    /// Called by the monitor after every clipboard change, to learn the hash of a copied image
+    pub fn note_change(&self, state: &LastCopyState) {
+        let mut expected = self.expected();
+        if let (Some(Expected::Image(hash @ None)), LastCopyState::Image(seen)) =
+            (&mut *expected, state)
+        {
+            *hash = Some(*seen);
+        }
+    }
+
+    /// Clear the clipboard if it still holds the copy made at `generation`. Returns whether
+    /// it was cleared.
+    pub fn clear(&self, generation: u64) -> bool {
+        if self.generation.load(Ordering::Acquire) != generation {
+            return false;
+        }
+        let Some(expected) = self.expected().take() else {
+            return false;
+        };
+        let mut last_copy = self.last_copy.lock().unwrap_or_else(|e| e.into_inner());
+        let unchanged = match (&expected, &*last_copy) {
+            (Expected::Text(text), LastCopyState::Text(current)) => text == current,
+            (Expected::Image(Some(hash)), LastCopyState::Image(current)) => hash == current,
+            _ => false,
+        };
+        if !unchanged {
+            return false;
+        }
+        // The empty clipboard matches this, so it isn't captured, while the next copy is
+        *last_copy = LastCopyState::Text(String::new());
+        self.copy_tx.send_blocking(CopyRequest::Clear).is_ok()
+    }
+
+    fn expected(&self) -> std::sync::MutexGuard<'_, Option<Expected>> {
+        self.expected.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auto_clear() -> (
+        AutoClear,
+        async_channel::Receiver<CopyRequest>,
+        Arc<Mutex<LastCopyState>>,
+    ) {
+        let (tx, rx) = async_channel::unbounded();
+        let last_copy = Arc::new(Mutex::new(LastCopyState::Text(String::new())));
+        (AutoClear::new(tx, last_copy.clone()), rx, last_copy)
+    }
+
+    #[test]
+    fn test_clears_unchanged_text() {
+        let (auto_clear, rx, last_copy) = auto_clear();
+        let generation = auto_clear.expect("secret", &ContentType::Text).unwrap();
+        *last_copy.lock().unwrap() = LastCopyState::Text("secret".to_string());
+
+        assert!(auto_clear.clear(generation));
+        assert!(matches!(rx.try_recv(), Ok(CopyRequest::Clear)));
+        // The cleared clipboard is not captured
+        assert!(!last_copy.lock().unwrap().is_new_text(""));
+        // Only once
+        assert!(!auto_clear.clear(generation));
+    }
+
+    #[test]
+    fn test_copy_after_clear_is_captured() {
+        let (auto_clear, _rx, last_copy) = auto_clear();
+        let generation = auto_clear.expect("secret", &ContentType::Text).unwrap();
+        *last_copy.lock().unwrap() = LastCopyState::Text("secret".to_string());
+        assert!(auto_clear.clear(generation));
+
+        // An empty clipboard reaches no capture branch, so nothing resets the state after
+        // the clear; the user's next copy must still be new
+        let state = last_copy.lock().unwrap();
+        assert!(state.is_new_text("next"));
+        assert!(state.is_new_text("secret"));
+        assert!(state.is_new_image(7));
+    }
+
+    #[test]
+    fn test_keeps_newer_copies() {
+        let (auto_clear, rx, last_copy) = auto_clear();
+        let generation = auto_clear.expect("secret", &ContentType::Text).unwrap();
+        // The user copied something else since
+        *last_copy.lock().unwrap() = LastCopyState::Text("other".to_string());
+        assert!(!auto_clear.clear(generation));
+
+        // A newer copy from Ropy replaces the pending clear
+        let first = auto_clear.expect("one", &ContentType::Text).unwrap();
+        let second = auto_clear.expect("two", &ContentType::Text).unwrap();
+        *last_copy.lock().unwrap() = LastCopyState::Text("two".to_string());
+        assert!(!auto_clear.clear(first));
+        assert!(rx.try_recv().is_err());
+        assert!(auto_clear.clear(second));
+    }
+
+    #[test]
+    fn test_clears_image_seen_by_monitor() {
+        let (auto_clear, rx, last_copy) = auto_clear();
+        let generation = auto_clear
+            .expect("/images/1.png", &ContentType::Image)
+            .unwrap();
+        auto_clear.note_change(&LastCopyState::Image(42));
+        // A later change doesn't replace the hash of the copied image
+        auto_clear.note_change(&LastCopyState::Image(7));
+        *last_copy.lock().unwrap() = LastCopyState::Image(7);
+        assert!(!auto_clear.clear(generation));
+
+        let generation = auto_clear
+            .expect("/images/1.png", &ContentType::Image)
+            .unwrap();
+        auto_clear.note_change(&LastCopyState::Image(42));
+        *last_copy.lock().unwrap() = LastCopyState::Image(42);
+        assert!(auto_clear.clear(generation));
+        assert!(matches!(rx.try_recv(), Ok(CopyRequest::Clear)));
+    }
+
+    #[test]
+    fn test_file_paths_are_never_cleared() {
+        let (auto_clear, _, _) = auto_clear();
+        assert_eq!(auto_clear.expect("/tmp/a", &ContentType::FilePath), None);
+    }
+}
@@ -0,0 +1,143 @@
+//! Normalization of raw clipboard text into valid UTF-8.
+
+/// Encoding the platform reports for raw clipboard text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFlavor {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+}
+
+/// Clipboard text converted to UTF-8
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedText {
+    pub text: String,
+    /// Whether some bytes could not be decoded and were replaced
+    pub lossy: bool,
+}
+
+/// Decode raw clipboard bytes into UTF-8 text.
+///
+/// A byte order mark takes precedence over the reported flavor. Bytes reported as UTF-8
+/// are decoded as UTF-16 when they look like it, and as Latin-1 when they are not valid
+/// UTF-8. Trailing NUL terminators, as written by Windows apps, are removed.
+pub fn normalize_clipboard_text(bytes: &[u8], flavor: TextFlavor) -> NormalizedText {
+    let (bytes, flavor) = if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        (rest, TextFlavor::Utf8)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        (rest, TextFlavor::Utf16Le)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        (rest, TextFlavor::Utf16Be)
+    } else {
+        (bytes, flavor)
+    };
+
+    let normalized = match flavor {
+        // ASCII in UTF-16 is also valid UTF-8, so check for UTF-16 first
+        TextFlavor::Utf8 if looks_like_utf16le(bytes) => decode_utf16(bytes, u16::from_le_bytes),
+        TextFlavor::Utf8 => match std::str::from_utf8(bytes) {
+            Ok(text) => NormalizedText {
+                text: text.to_string(),
+                lossy: false,
+            },
+            Err(_) => decode_latin1(bytes),
+        },
+        TextFlavor::Utf16Le => decode_utf16(bytes, u16::from_le_bytes),
+        TextFlavor::Utf16Be => decode_utf16(bytes, u16::from_be_bytes),
+        TextFlavor::Latin1 => decode_latin1(bytes),
+    };
+
+    NormalizedText {
+        text: normalized.text.trim_end_matches('\0').to_string(),
+        lossy: normalized.lossy,
+    }
+}
+
+/// Mostly-ASCII UTF-16LE text has a zero high byte in most code units
+fn looks_like_utf16le(bytes: &[u8]) -> bool {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return false;
+    }
+    let zero_high_bytes = bytes.chunks_exact(2).filter(|unit| unit[1] == 0).count();
+    zero_high_bytes * 2 >= bytes.len() / 2
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> NormalizedText {
+    // A dangling odd byte can't form a code unit
+    let mut lossy = !bytes.len().is_multiple_of(2);
+    let units = bytes
+        .chunks_exact(2)
+        .map(|unit| to_unit([unit[0], unit[1]]));
+    let text = char::decode_utf16(units)
+        .map(|c| {
+            c.unwrap_or_else(|_| {
+                lossy = true;
+                char::REPLACEMENT_CHARACTER
+            })
+        })
+        .collect();
+    NormalizedText { text, lossy }
+}
+
+fn decode_latin1(bytes: &[u8]) -> NormalizedText {
+    NormalizedText {
+        text: bytes.iter().map(|&b| char::from(b)).collect(),
+        lossy: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn test_normalize_utf8() {
+        let normalized = normalize_clipboard_text("héllo 你好".as_bytes(), TextFlavor::Utf8);
+        assert_eq!(normalized.text, "héllo 你好");
+        assert!(!normalized.lossy);
+
+        let with_bom = [&[0xEF, 0xBB, 0xBF], "hi".as_bytes()].concat();
+        assert_eq!(
+            normalize_clipboard_text(&with_bom, TextFlavor::Utf8).text,
+            "hi"
+        );
+    }
+
+    #[test]
+    fn test_normalize_utf16() {
+        let mut bytes = utf16le("héllo 你好");
+        bytes.extend_from_slice(&[0, 0]);
+        let normalized = normalize_clipboard_text(&bytes, TextFlavor::Utf16Le);
+        assert_eq!(normalized.text, "héllo 你好");
+        assert!(!normalized.lossy);
+
+        // UTF-16 reported as UTF-8 is detected
+        let normalized = normalize_clipboard_text(&utf16le("plain text"), TextFlavor::Utf8);
+        assert_eq!(normalized.text, "plain text");
+
+        // BOM overrides the reported flavor
+        let bytes = [0xFE, 0xFF, 0x00, 0x41];
+        assert_eq!(
+            normalize_clipboard_text(&bytes, TextFlavor::Latin1).text,
+            "A"
+        );
+    }
+
+    #[test]
+    fn test_normalize_lossy_and_latin1() {
+        // Unpaired surrogate
+        let normalized = normalize_clipboard_text(&[0x00, 0xD8, 0x41, 0x00], TextFlavor::Utf16Le);
+        assert_eq!(normalized.text, "\u{FFFD}A");
+        assert!(normalized.lossy);
+
+        // Invalid UTF-8 that isn't UTF-16 falls back to Latin-1
+        let normalized = normalize_clipboard_text(&[b'c', b'a', b'f', 0xE9], TextFlavor::Utf8);
+        assert_eq!(normalized.text, "café");
+        assert!(!normalized.lossy);
+    }
+}
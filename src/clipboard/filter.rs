@@ -0,0 +1,65 @@
+/// Whether captured `text` is long enough to be kept in history.
+///
+/// Surrounding whitespace doesn't count towards the length. A `min_chars` of 0 keeps
+/// everything.
+pub fn should_store_text(text: &str, min_chars: usize) -> bool {
+    min_chars == 0 || text.trim().chars().count() >= min_chars
+}
+
+/// Whether the clipboard offers any of the `ignored` formats, compared case-insensitively
+pub fn has_ignored_format(available: &[String], ignored: &[String]) -> bool {
+    available
+        .iter()
+        .any(|format| ignored.iter().any(|i| i.eq_ignore_ascii_case(format)))
+}
+
+/// Parse a comma-separated list of clipboard format identifiers
+pub fn parse_format_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|format| !format.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_store_text() {
+        assert!(should_store_text("a", 0));
+        assert!(should_store_text(" ", 0));
+        assert!(!should_store_text("a", 2));
+        assert!(!should_store_text("  a \n", 2));
+        assert!(should_store_text("ab", 2));
+        // Characters, not bytes
+        assert!(!should_store_text("你", 2));
+        assert!(should_store_text("你好", 2));
+    }
+
+    #[test]
+    fn test_has_ignored_format() {
+        let available = vec!["text/plain".to_string(), "text/uri-list".to_string()];
+        assert!(has_ignored_format(
+            &available,
+            &["TEXT/URI-LIST".to_string()]
+        ));
+        assert!(!has_ignored_format(
+            &available,
+            &["public.html".to_string()]
+        ));
+        assert!(!has_ignored_format(&available, &[]));
+        assert!(!has_ignored_format(&[], &["text/plain".to_string()]));
+    }
+
+    #[test]
+    fn test_parse_format_list() {
+        assert_eq!(
+            parse_format_list(" text/uri-list, ,com.example.private "),
+            vec!["text/uri-list", "com.example.private"]
+        );
+        assert!(parse_format_list("  ").is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Running a user-configured shell command for every capture
+//!
+//! This is a power feature: the command runs with the user's privileges and receives
+//! whatever was copied, including passwords copied from a password manager. The copied
+//! content reaches the command through an environment variable, never as part of the
+//! command line, so the shell doesn't interpret it. The template itself is run as written.
+
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::repository::models::ContentType;
+
+/// Environment variable holding the copied text, image path or file path
+const CONTENT_VAR: &str = "ROPY_CONTENT";
+/// Environment variable holding the content type, as in the `{type}` placeholder
+const TYPE_VAR: &str = "ROPY_TYPE";
+/// Hooks allowed to run at once; captures beyond that skip the hook
+const MAX_RUNNING_HOOKS: usize = 4;
+/// How long a hook may run before it is killed
+const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often a running hook is checked for having exited
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Hooks currently running
+static RUNNING_HOOKS: AtomicUsize = AtomicUsize::new(0);
+
+/// Name of a content type in the `{type}` placeholder
+fn type_name(content_type: &ContentType) -> &'static str {
+    match content_type {
+        ContentType::Text => "text",
+        ContentType::Image => "image",
+        ContentType::FilePath => "file",
+        ContentType::Svg => "svg",
+    }
+}
+
+/// Reference to the content variable that the shell passes on as a single argument,
+/// without interpreting what it holds
+#[cfg(not(target_os = "windows"))]
+fn content_reference() -> String {
+    format!("\"${CONTENT_VAR}\"")
+}
+
+/// Reference to the content variable that the shell passes on as a single argument,
+/// without interpreting what it holds
+///
+/// Delayed expansion substitutes the value after cmd has parsed the line, so `%`, `&` or
+/// quotes in the copied text stay literal.
+#[cfg(target_os = "windows")]
+fn content_reference() -> String {
+    format!("\"!{CONTENT_VAR}!\"")
+}
+
+/// Command line for a capture: `{content}` becomes a quoted reference to the text, image
+/// path or file path, and `{type}` one of `text`, `image`, `file` or `svg`
+pub fn expand_hook_template(template: &str, content_type: &ContentType) -> String {
+    template
+        .replace("{type}", type_name(content_type))
+        .replace("{content}", &content_reference())
+}
+
+/// Take one of the running hook slots, or return `false` when all are taken
+fn try_acquire_slot(running: &AtomicUsize, max: usize) -> bool {
+    running
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+            (count < max).then_some(count + 1)
+        })
+        .is_ok()
+}
+
+#[cfg(target_os = "windows")]
+fn shell_command(command_line: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+
+    let mut command = Command::new("cmd");
+    // Passed as written: the usual argument escaping uses `\"`, which cmd doesn't know
+    command.raw_arg("/V:ON /D /C ").raw_arg(command_line);
+    command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(command_line);
+    command
+}
+
+/// Wait for a hook to exit, killing it once it has run for `timeout`
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if started.elapsed() >= timeout {
+            child.kill()?;
+            child.wait()?;
+            return Ok(None);
+        }
+        std::thread::sleep(HOOK_POLL_INTERVAL);
+    }
+}
+
+/// Run the capture hook on its own thread, discarding its output and logging failures
+///
+/// Skipped while `MAX_RUNNING_HOOKS` hooks are still running, so a slow command can't
+/// pile up processes during a burst of copies.
+pub fn run_capture_hook(template: &str, content: &str, content_type: &ContentType) {
+    if !try_acquire_slot(&RUNNING_HOOKS, MAX_RUNNING_HOOKS) {
+        log::warn!("Skipped the capture hook, {MAX_RUNNING_HOOKS} are still running");
+        return;
+    }
+    let mut command = shell_command(&expand_hook_template(template, content_type));
+    command
+        .env(CONTENT_VAR, content)
+        .env(TYPE_VAR, type_name(content_type))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    std::thread::spawn(move || {
+        let result = command
+            .spawn()
+            .and_then(|mut child| wait_with_timeout(&mut child, HOOK_TIMEOUT));
+        match result {
+            Ok(Some(status)) if status.success() => {}
+            Ok(Some(status)) => log::warn!("Capture hook exited with {status}"),
+            Ok(None) => log::warn!("Capture hook killed after {}s", HOOK_TIMEOUT.as_secs()),
+            Err(e) => log::warn!("Failed to run the capture hook: {e}"),
+        }
+        RUNNING_HOOKS.fetch_sub(1, Ordering::AcqRel);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_hook_template() {
+        assert_eq!(
+            expand_hook_template("notify {type}", &ContentType::Text),
+            "notify text"
+        );
+        assert_eq!(expand_hook_template("echo", &ContentType::Image), "echo");
+        assert_eq!(
+            expand_hook_template("log {content} {type}", &ContentType::FilePath),
+            format!("log {} file", content_reference())
+        );
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_content_is_not_interpreted_by_the_shell() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let template = format!("printf %s {{content}} > '{}'", out.display());
+        let content = "it's $(echo injected) `echo too` & \"quoted\"";
+
+        let status = shell_command(&expand_hook_template(&template, &ContentType::Text))
+            .env(CONTENT_VAR, content)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        assert_eq!(std::fs::read_to_string(out).unwrap(), content);
+    }
+
+    #[test]
+    fn test_running_hooks_are_limited() {
+        let running = AtomicUsize::new(0);
+        assert!(try_acquire_slot(&running, 2));
+        assert!(try_acquire_slot(&running, 2));
+        assert!(!try_acquire_slot(&running, 2));
+        running.fetch_sub(1, Ordering::AcqRel);
+        assert!(try_acquire_slot(&running, 2));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_slow_hooks_are_killed() {
+        let mut child = shell_command("sleep 5").spawn().unwrap();
+        let status = wait_with_timeout(&mut child, Duration::from_millis(200)).unwrap();
+        assert!(status.is_none());
+    }
+}
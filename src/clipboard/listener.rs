@@ -1,143 +1,748 @@
 //! A simple clipboard change listener using event-driven watching.
 
-use super::{ClipboardEvent, LastCopyState};
+use super::{
+    AppendMode, AutoClear, ClipboardEvent, LastCopyState, NormalizedText, OwnWindow, PrivacyMode,
+    StickyClipboard, TextFlavor, UiRefreshGate, append_text, has_ignored_format, is_svg_source,
+    normalize_clipboard_text, run_capture_hook, save_thumbnail, should_replace_previous,
+    should_store_text, svg_file_source,
+};
 use crate::config::Settings;
-use crate::repository::{ClipboardRecord, ClipboardRepository};
+use crate::repository::errors::RepositoryError;
+use crate::repository::ids;
+use crate::repository::models::{ContentType, content_hash};
+use crate::repository::{ActiveRepository, ClipboardRecord, ClipboardRepository};
 use async_channel::Sender;
+use chrono::Local;
 use clipboard_rs::common::RustImage;
 use clipboard_rs::{
     Clipboard, ClipboardContext, ClipboardHandler, ClipboardWatcher, ClipboardWatcherContext,
 };
-use gpui::{AsyncApp, WindowHandle};
+use gpui::{App, AsyncApp, WindowHandle};
 use gpui_component::Root;
 use image::DynamicImage;
 use std::collections::hash_map::DefaultHasher;
+use std::error::Error;
 use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Images captured from a single clipboard change
+enum CapturedImages {
+    /// Image data read directly from the clipboard
+    Data(DynamicImage),
+    /// Image files copied together, e.g. from a file manager
+    Files(Vec<String>),
+}
+
+/// Attempts made to start clipboard monitoring before giving up
+const MONITOR_START_ATTEMPTS: u32 = 8;
+/// Delay before the first retry, doubled after each failed attempt
+const MONITOR_RETRY_BASE: Duration = Duration::from_millis(500);
+/// Upper bound for the delay between retries
+const MONITOR_RETRY_MAX: Duration = Duration::from_secs(30);
+/// Shortest interval allowed for the polling fallback
+const MIN_POLLING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Updates sent from the capture task to the UI
+enum UiUpdate {
+    /// New records were stored
+    Refresh,
+    /// A captured record could not be stored
+    SaveFailed,
+}
 
 /// Clipboard monitor that sends clipboard text changes through a channel.
 struct ClipboardMonitor {
     tx: Sender<ClipboardEvent>,
-    image_tx: Sender<DynamicImage>,
+    image_tx: Sender<CapturedImages>,
     ctx: ClipboardContext,
     last_copy: Arc<Mutex<LastCopyState>>,
+    settings: Arc<RwLock<Settings>>,
+    own_window: Arc<OwnWindow>,
+    auto_clear: Arc<AutoClear>,
 }
 
 impl ClipboardMonitor {
     fn new(
         tx: Sender<ClipboardEvent>,
-        image_tx: Sender<DynamicImage>,
+        image_tx: Sender<CapturedImages>,
         last_copy: Arc<Mutex<LastCopyState>>,
-    ) -> Self {
-        let ctx = ClipboardContext::new().unwrap();
-        Self {
+        settings: Arc<RwLock<Settings>>,
+        own_window: Arc<OwnWindow>,
+        auto_clear: Arc<AutoClear>,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let ctx = ClipboardContext::new()?;
+        Ok(Self {
             tx,
             image_tx,
             last_copy,
+            settings,
+            own_window,
+            auto_clear,
             ctx,
+        })
+    }
+
+    /// Whether new clipboard content was copied inside Ropy's window and should be skipped
+    ///
+    /// Uses up a pending write announcement, so it is called once for every change.
+    fn is_ignored_own_change(&self) -> bool {
+        let own_change = self.own_window.is_own_change();
+        let ignore_self = match self.settings.read() {
+            Ok(settings) => settings.storage.ignore_self,
+            Err(poisoned) => poisoned.into_inner().storage.ignore_self,
+        };
+        own_change && ignore_self
+    }
+
+    /// Whether the clipboard offers a format the user chose to ignore
+    fn offers_ignored_format(&self) -> bool {
+        let ignored = match self.settings.read() {
+            Ok(settings) => settings.storage.ignored_formats.clone(),
+            Err(poisoned) => poisoned.into_inner().storage.ignored_formats.clone(),
+        };
+        if ignored.is_empty() {
+            return false;
         }
+        self.ctx
+            .available_formats()
+            .is_ok_and(|formats| has_ignored_format(&formats, &ignored))
     }
 }
 
 impl ClipboardHandler for ClipboardMonitor {
     // Don't send duplicate clipboard contents
     fn on_clipboard_change(&mut self) {
+        // Before anything can skip the change, so a write announcement never outlives it
+        let own_change = self.is_ignored_own_change();
+        if self.offers_ignored_format() {
+            return;
+        }
         let mut last_copy_guard = match self.last_copy.lock() {
             Ok(g) => g,
             Err(poisoned) => poisoned.into_inner(),
         };
+        // `get_image` only exposes the first image flavor on every platform, so a
+        // clipboard holding several images yields just the first one here. Copied
+        // image files are handled below, where each file becomes its own record.
         if let Ok(image) = self.ctx.get_image()
             && let Ok(dyn_img) = image.get_dynamic_image()
         {
-            // Calculate image hash
+            let hash = content_hash(dyn_img.as_bytes());
+
+            if last_copy_guard.is_new_image(hash) && !own_change {
+                let _ = self.image_tx.send_blocking(CapturedImages::Data(dyn_img));
+            }
+            *last_copy_guard = LastCopyState::Image(hash);
+        } else if let Ok(files) = self.ctx.get_files()
+            && let Some(image_files) = image_file_paths(&files)
+        {
             let mut hasher = DefaultHasher::new();
-            dyn_img.as_bytes().hash(&mut hasher);
+            image_files.hash(&mut hasher);
             let hash: u64 = hasher.finish();
 
-            if !matches!(*last_copy_guard, LastCopyState::Image(h) if h == hash) {
-                let _ = self.image_tx.send_blocking(dyn_img);
-                *last_copy_guard = LastCopyState::Image(hash);
+            if last_copy_guard.is_new_image(hash) && !own_change {
+                let _ = self
+                    .image_tx
+                    .send_blocking(CapturedImages::Files(image_files));
             }
-        } else if let Ok(text) = self.ctx.get_text()
-            && !matches!(*last_copy_guard, LastCopyState::Text(ref last_text) if *last_text == text)
+            *last_copy_guard = LastCopyState::Image(hash);
+        } else if let Ok(files) = self.ctx.get_files()
+            && let Some(source) = svg_file_source(&files)
         {
-            let _ = self.tx.send_blocking(ClipboardEvent::Text(text.clone()));
+            if last_copy_guard.is_new_text(&source) && !own_change {
+                let _ = self.tx.send_blocking(ClipboardEvent::Svg(source.clone()));
+            }
+            // Copying the record back writes the markup as text
+            *last_copy_guard = LastCopyState::Text(source);
+        } else if let Some(NormalizedText { text, lossy }) = read_text(&self.ctx) {
+            if last_copy_guard.is_new_text(&text) && !own_change {
+                let event = if lossy {
+                    ClipboardEvent::LossyText(text.clone())
+                } else if is_svg_source(&text) {
+                    ClipboardEvent::Svg(text.clone())
+                } else {
+                    ClipboardEvent::Text(text.clone())
+                };
+                let _ = self.tx.send_blocking(event);
+            }
             *last_copy_guard = LastCopyState::Text(text);
         }
+        self.auto_clear.note_change(&last_copy_guard);
     }
 }
 
+/// Raw text formats tried, in order, when the platform can't return the text as UTF-8
+#[cfg(target_os = "linux")]
+const RAW_TEXT_FORMATS: &[(&str, TextFlavor)] = &[
+    ("text/plain;charset=utf-16", TextFlavor::Utf16Le),
+    ("text/plain", TextFlavor::Utf8),
+    ("STRING", TextFlavor::Latin1),
+];
+#[cfg(target_os = "macos")]
+const RAW_TEXT_FORMATS: &[(&str, TextFlavor)] = &[
+    ("public.utf16-plain-text", TextFlavor::Utf16Le),
+    ("public.utf8-plain-text", TextFlavor::Utf8),
+];
+// Windows already converts its UTF-16 text to UTF-8
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const RAW_TEXT_FORMATS: &[(&str, TextFlavor)] = &[];
+
+/// Read clipboard text as valid UTF-8, decoding raw text formats when needed
+fn read_text(ctx: &ClipboardContext) -> Option<NormalizedText> {
+    if let Ok(text) = ctx.get_text() {
+        // UTF-16 text passed off as UTF-8 still decodes, but with NULs between characters
+        return Some(normalize_clipboard_text(text.as_bytes(), TextFlavor::Utf8));
+    }
+    RAW_TEXT_FORMATS
+        .iter()
+        .filter_map(|(format, flavor)| {
+            let bytes = ctx.get_buffer(format).ok()?;
+            Some(normalize_clipboard_text(&bytes, *flavor))
+        })
+        .find(|normalized| !normalized.text.is_empty())
+}
+
+/// Turn a copied `file://` URI into a plain path, decoding `%XX` escapes; anything else
+/// is taken as a path already
+fn file_uri_path(file: &str) -> String {
+    let Some(rest) = file.strip_prefix("file://") else {
+        return file.to_string();
+    };
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    // `file:///C:/...` on Windows
+    #[cfg(target_os = "windows")]
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => path,
+    };
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| path.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Keep only the copied files that look like images, as plain paths
+fn image_file_paths(files: &[String]) -> Option<Vec<String>> {
+    let paths: Vec<String> = files
+        .iter()
+        .map(|file| file_uri_path(file))
+        .filter(|path| image::ImageFormat::from_path(path).is_ok())
+        .collect();
+    (!paths.is_empty()).then_some(paths)
+}
+
+/// Delay before retrying to start clipboard monitoring after the given failed attempt
+fn retry_delay(attempt: u32) -> Duration {
+    MONITOR_RETRY_BASE
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MONITOR_RETRY_MAX)
+}
+
+/// Interval of the polling fallback, if enabled, limited to `MIN_POLLING_INTERVAL`
+fn polling_interval(polling_ms: Option<u64>) -> Option<Duration> {
+    polling_ms
+        .filter(|ms| *ms > 0)
+        .map(|ms| Duration::from_millis(ms).max(MIN_POLLING_INTERVAL))
+}
+
 /// Spawn a clipboard listener thread that watches for clipboard changes.
+///
+/// With `storage.polling_ms` set, the clipboard is also read on a timer to catch changes
+/// the watcher misses. Both go through the same `LastCopyState` check, so a change seen
+/// by both is only recorded once.
 pub fn start_clipboard_monitor(
     tx: Sender<ClipboardEvent>,
     async_app: AsyncApp,
     last_copy: Arc<Mutex<LastCopyState>>,
+    settings: Arc<RwLock<Settings>>,
+    own_window: Arc<OwnWindow>,
+    auto_clear: Arc<AutoClear>,
 ) {
-    let (image_tx, image_rx) = async_channel::unbounded::<DynamicImage>();
-    let monitor = ClipboardMonitor::new(tx.clone(), image_tx, last_copy);
+    let (image_tx, image_rx) = async_channel::unbounded::<CapturedImages>();
+    let monitor_tx = tx.clone();
     let executor = async_app.background_executor();
 
     executor
         .spawn(async move {
-            while let Ok(image) = image_rx.recv().await {
-                if let Some(path) = super::save_image(image) {
-                    let _ = tx.send_blocking(ClipboardEvent::Image(path));
+            while let Ok(captured) = image_rx.recv().await {
+                match captured {
+                    CapturedImages::Data(image) => {
+                        if let Some(path) = super::save_image(image) {
+                            let _ = tx.send_blocking(ClipboardEvent::Image(path));
+                        }
+                    }
+                    CapturedImages::Files(files) => {
+                        // Re-encode into the images directory so deleting a record
+                        // never touches the user's original file
+                        let mut paths: Vec<String> = files
+                            .iter()
+                            .filter_map(|file| image::open(file).ok())
+                            .filter_map(super::save_image)
+                            .collect();
+                        let event = match paths.len() {
+                            0 => continue,
+                            1 => ClipboardEvent::Image(paths.remove(0)),
+                            _ => ClipboardEvent::Images(paths),
+                        };
+                        let _ = tx.send_blocking(event);
+                    }
                 }
             }
         })
         .detach();
 
+    let polling = settings
+        .read()
+        .ok()
+        .and_then(|settings| polling_interval(settings.storage.polling_ms));
+    if let Some(interval) = polling {
+        let (tx, image_tx, last_copy, settings, own_window, auto_clear) = (
+            monitor_tx.clone(),
+            image_tx.clone(),
+            last_copy.clone(),
+            settings.clone(),
+            own_window.clone(),
+            auto_clear.clone(),
+        );
+        let poll_executor = executor.clone();
+        executor
+            .spawn(async move {
+                let mut monitor = match ClipboardMonitor::new(
+                    tx, image_tx, last_copy, settings, own_window, auto_clear,
+                ) {
+                    Ok(monitor) => monitor,
+                    Err(e) => {
+                        log::error!("Failed to start clipboard polling: {e}");
+                        return;
+                    }
+                };
+                log::info!("Polling the clipboard every {interval:?}");
+                loop {
+                    poll_executor.timer(interval).await;
+                    monitor.on_clipboard_change();
+                }
+            })
+            .detach();
+    }
+
+    let timer_executor = executor.clone();
     executor
         .spawn(async move {
-            let mut watcher = ClipboardWatcherContext::new().unwrap();
-            watcher.add_handler(monitor);
-            watcher.start_watch();
+            // The clipboard is often unavailable for a moment right after login, so retry
+            // with backoff instead of losing clipboard monitoring for the whole session
+            for attempt in 0..MONITOR_START_ATTEMPTS {
+                let started = ClipboardMonitor::new(
+                    monitor_tx.clone(),
+                    image_tx.clone(),
+                    last_copy.clone(),
+                    settings.clone(),
+                    own_window.clone(),
+                    auto_clear.clone(),
+                )
+                .and_then(|monitor| Ok((monitor, ClipboardWatcherContext::new()?)));
+                match started {
+                    Ok((monitor, mut watcher)) => {
+                        if attempt > 0 {
+                            log::info!("Clipboard monitoring started after {attempt} retries");
+                        }
+                        watcher.add_handler(monitor);
+                        watcher.start_watch();
+                        return;
+                    }
+                    Err(e) => {
+                        let delay = retry_delay(attempt);
+                        log::warn!(
+                            "Failed to start clipboard monitoring (attempt {}/{MONITOR_START_ATTEMPTS}), retrying in {delay:?}: {e}",
+                            attempt + 1
+                        );
+                        timer_executor.timer(delay).await;
+                    }
+                }
+            }
+            log::error!(
+                "Giving up on clipboard monitoring after {MONITOR_START_ATTEMPTS} attempts"
+            );
         })
         .detach();
 }
 
+/// Append captured text to the latest record if it is a text record.
+///
+/// Returns false when there is no text record to append to, so the text is saved as usual.
+fn append_to_latest(
+    repo: &ClipboardRepository,
+    shared_records: &Mutex<Vec<ClipboardRecord>>,
+    text: &str,
+    lossy: bool,
+) -> Result<bool, RepositoryError> {
+    let latest_id = {
+        let guard = match shared_records.lock() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match guard.first() {
+            Some(record) if record.content_type == ContentType::Text => record.id,
+            _ => return Ok(false),
+        }
+    };
+    let Some(latest) = repo.get_by_id(latest_id)? else {
+        return Ok(false);
+    };
+    let Some(mut record) = repo.update_content(latest_id, append_text(&latest.content, text))?
+    else {
+        return Ok(false);
+    };
+    if lossy && !record.lossy_encoding {
+        record.lossy_encoding = true;
+        repo.update(&record)?;
+    }
+
+    let mut guard = match shared_records.lock() {
+        Ok(g) => g,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if let Some(first) = guard.first_mut()
+        && first.id == record.id
+    {
+        *first = record;
+    }
+    Ok(true)
+}
+
+/// Records for a capture that is kept in memory only, shaped like the ones the repository saves
+fn unsaved_records(event: ClipboardEvent) -> Vec<ClipboardRecord> {
+    let count = match &event {
+        ClipboardEvent::Images(paths) => paths.len() as u64,
+        _ => 1,
+    };
+    let id = ids::next_id(Local::now(), count);
+    match event {
+        ClipboardEvent::Text(text) => vec![ClipboardRecord::new(id, text, ContentType::Text)],
+        ClipboardEvent::LossyText(text) => {
+            let mut lossy = ClipboardRecord::new(id, text, ContentType::Text);
+            lossy.lossy_encoding = true;
+            vec![lossy]
+        }
+        ClipboardEvent::Image(path) => vec![ClipboardRecord::new(id, path, ContentType::Image)],
+        ClipboardEvent::Svg(source) => vec![ClipboardRecord::new(id, source, ContentType::Svg)],
+        ClipboardEvent::Images(paths) => paths
+            .into_iter()
+            .enumerate()
+            .map(|(offset, path)| {
+                let mut image = ClipboardRecord::new(id + offset as u64, path, ContentType::Image);
+                image.group_id = Some(id);
+                image
+            })
+            .collect(),
+    }
+}
+
+/// Show a capture made in privacy mode without writing it to the database
+///
+/// In append mode, text is added to the latest record if that one is private too.
+fn keep_private(
+    event: ClipboardEvent,
+    shared_records: &Mutex<Vec<ClipboardRecord>>,
+    privacy_mode: &PrivacyMode,
+    append: bool,
+    max_history_records: usize,
+) {
+    let mut guard = match shared_records.lock() {
+        Ok(g) => g,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    if append
+        && let ClipboardEvent::Text(text) | ClipboardEvent::LossyText(text) = &event
+        && let Some(latest) = guard.first_mut()
+        && latest.content_type == ContentType::Text
+        && privacy_mode.is_private(latest.id)
+    {
+        latest.content = append_text(&latest.content, text);
+        latest.content_hash = Some(content_hash(latest.content.as_bytes()));
+        latest.lossy_encoding |= matches!(event, ClipboardEvent::LossyText(_));
+        return;
+    }
+    for record in unsaved_records(event) {
+        let image_file =
+            (record.content_type == ContentType::Image).then_some(record.content.as_str());
+        privacy_mode.remember(record.id, image_file);
+        guard.insert(0, record);
+    }
+    guard.truncate(max_history_records);
+}
+
+/// Replace an earlier record equivalent to the new capture, keeping the favorite, note, flag and tags set on it
+fn replace_equivalent(
+    repo: &ClipboardRepository,
+    records: &mut Vec<ClipboardRecord>,
+    previous: ClipboardRecord,
+    record: &mut ClipboardRecord,
+) {
+    if previous.favorite
+        || previous.note.is_some()
+        || previous.flag.is_some()
+        || !previous.tags.is_empty()
+    {
+        record.favorite = previous.favorite;
+        record.favorite_order = previous.favorite_order;
+        record.note = previous.note;
+        record.flag = previous.flag;
+        record.tags = previous.tags;
+        if let Err(e) = repo.update(record) {
+            log::error!(
+                "Failed to carry over the replaced record's favorite, note, flag and tags: {e}"
+            );
+        }
+    }
+    match repo.delete(previous.id) {
+        Ok(_) => records.retain(|r| r.id != previous.id),
+        Err(e) => log::error!("Failed to delete the replaced duplicate record: {e}"),
+    }
+}
+
+/// Put saved captures at the top of the list, each replacing the previous record when it
+/// continues it within `merge_window` seconds
+///
+/// A replaced record's favorite, note, flag and tags carry over as for duplicates.
+fn add_captured(
+    repo: &ClipboardRepository,
+    records: &mut Vec<ClipboardRecord>,
+    new_records: Vec<ClipboardRecord>,
+    merge_window: Option<u32>,
+) {
+    for mut record in new_records {
+        if let Some(secs) = merge_window
+            && let Some(prev) = records.first()
+            && should_replace_previous(prev, &record, Duration::from_secs(secs.into()))
+        {
+            let prev = prev.clone();
+            replace_equivalent(repo, records, prev, &mut record);
+        }
+        records.insert(0, record);
+    }
+}
+
+/// Earlier record that a captured text replaces under the enabled duplicate rules
+fn find_equivalent(
+    repo: &ClipboardRepository,
+    text: &str,
+    dedup_whitespace: bool,
+    dedup_urls: bool,
+) -> Option<ClipboardRecord> {
+    let whitespace = dedup_whitespace
+        .then(|| repo.find_equivalent_text(text))
+        .transpose();
+    let found = whitespace.and_then(|found| match found {
+        Some(record) => Ok(Some(record)),
+        None if dedup_urls => repo.find_equivalent_url(text),
+        None => Ok(None),
+    });
+    found.unwrap_or_else(|e| {
+        log::warn!("Failed to look up duplicate records: {e}");
+        None
+    })
+}
+
 pub fn start_clipboard_listener(
     clipboard_rx: async_channel::Receiver<ClipboardEvent>,
     shared_records: Arc<Mutex<Vec<ClipboardRecord>>>,
-    repository: Option<Arc<ClipboardRepository>>,
+    repository: ActiveRepository,
     settings: Arc<RwLock<Settings>>,
+    refresh_gate: Arc<UiRefreshGate>,
+    append_mode: Arc<AppendMode>,
+    privacy_mode: Arc<PrivacyMode>,
+    sticky: Arc<StickyClipboard>,
     async_app: AsyncApp,
     window_handle: WindowHandle<Root>,
+    on_save_failed: impl Fn(&mut App) + 'static,
 ) {
-    let (notify_tx, notify_rx) = async_channel::unbounded::<()>();
+    let (notify_tx, notify_rx) = async_channel::unbounded::<UiUpdate>();
+    let (thumbnail_tx, thumbnail_rx) = async_channel::unbounded::<Vec<String>>();
     let bg_executor = async_app.background_executor().clone();
     let fg_executor = async_app.foreground_executor().clone();
 
+    // Thumbnails of huge images take a while, so they are made after the record is stored
+    // and shown; the list uses the full image until then
+    let (thumbnail_notify_tx, thumbnail_refresh_gate) = (notify_tx.clone(), refresh_gate.clone());
+    bg_executor
+        .spawn(async move {
+            while let Ok(paths) = thumbnail_rx.recv().await {
+                for path in paths {
+                    if let Err(e) = save_thumbnail(Path::new(&path)) {
+                        log::warn!("Failed to save the thumbnail of {path}: {e}");
+                    }
+                }
+                if thumbnail_refresh_gate.request_refresh() {
+                    let _ = thumbnail_notify_tx.send(UiUpdate::Refresh).await;
+                }
+            }
+        })
+        .detach();
+
+    let sticky_executor = bg_executor.clone();
     bg_executor
         .spawn(async move {
             while let Ok(event) = clipboard_rx.recv().await {
+                // Put the record kept on the clipboard back once this copy had time to be pasted
+                if let Some(generation) = sticky.note_capture() {
+                    let delay = settings.read().unwrap().behavior.sticky_restore_secs;
+                    let (sticky, timer) = (sticky.clone(), sticky_executor.clone());
+                    sticky_executor
+                        .spawn(async move {
+                            timer.timer(Duration::from_secs(delay.into())).await;
+                            sticky.restore(generation);
+                        })
+                        .detach();
+                }
+                if let ClipboardEvent::Text(text) | ClipboardEvent::LossyText(text) = &event {
+                    let min_chars = settings.read().unwrap().storage.min_text_chars;
+                    if !should_store_text(text, min_chars) {
+                        continue;
+                    }
+                }
+                if privacy_mode.is_active() {
+                    let max_history_records = settings.read().unwrap().storage.max_history_records;
+                    keep_private(
+                        event,
+                        &shared_records,
+                        &privacy_mode,
+                        append_mode.is_active(),
+                        max_history_records,
+                    );
+                    if refresh_gate.request_refresh() {
+                        let _ = notify_tx.send(UiUpdate::Refresh).await;
+                    }
+                    continue;
+                }
+                // Read per capture so a profile switch takes effect right away
+                let repository = repository.read().unwrap().clone();
                 if let Some(ref repo) = repository {
+                    if append_mode.is_active()
+                        && let ClipboardEvent::Text(text) | ClipboardEvent::LossyText(text) = &event
+                    {
+                        let lossy = matches!(event, ClipboardEvent::LossyText(_));
+                        match append_to_latest(repo, &shared_records, text, lossy) {
+                            Ok(true) => {
+                                if refresh_gate.request_refresh() {
+                                    let _ = notify_tx.send(UiUpdate::Refresh).await;
+                                }
+                                continue;
+                            }
+                            // Nothing to append to yet, so this capture starts a new record
+                            Ok(false) => {}
+                            Err(e) => {
+                                log::error!("Failed to append to the latest record: {e}");
+                                let _ = notify_tx.send(UiUpdate::SaveFailed).await;
+                                continue;
+                            }
+                        }
+                    }
+                    let (dedup_whitespace, dedup_urls) = {
+                        let storage = &settings.read().unwrap().storage;
+                        (storage.dedup_whitespace, storage.dedup_urls)
+                    };
+                    let equivalent = match &event {
+                        ClipboardEvent::Text(text) | ClipboardEvent::LossyText(text) => {
+                            find_equivalent(repo, text, dedup_whitespace, dedup_urls)
+                        }
+                        _ => None,
+                    };
                     let result = match event {
-                        ClipboardEvent::Text(text) => repo.save_text(text),
-                        ClipboardEvent::Image(path) => repo.save_image_from_path(path),
+                        ClipboardEvent::Text(text) => repo.save_text(text).map(|r| vec![r]),
+                        ClipboardEvent::LossyText(text) => {
+                            repo.save_text(text).and_then(|mut record| {
+                                record.lossy_encoding = true;
+                                repo.update(&record).map(|_| vec![record])
+                            })
+                        }
+                        ClipboardEvent::Image(path) => {
+                            repo.save_image_from_path(path).map(|r| vec![r])
+                        }
+                        ClipboardEvent::Images(paths) => repo.save_image_group(paths),
+                        ClipboardEvent::Svg(source) => {
+                            repo.save(source, ContentType::Svg).map(|r| vec![r])
+                        }
                     };
 
                     match result {
-                        Ok(record) => {
+                        Ok(mut new_records) => {
+                            let on_capture = settings.read().unwrap().hooks.on_capture.clone();
+                            if let Some(template) = on_capture.filter(|t| !t.trim().is_empty()) {
+                                for record in &new_records {
+                                    run_capture_hook(
+                                        &template,
+                                        &record.content,
+                                        &record.content_type,
+                                    );
+                                }
+                            }
+                            let images: Vec<String> = new_records
+                                .iter()
+                                .filter(|record| record.content_type == ContentType::Image)
+                                .map(|record| record.content.clone())
+                                .collect();
                             {
                                 let mut guard = match shared_records.lock() {
                                     Ok(g) => g,
                                     Err(poisoned) => poisoned.into_inner(),
                                 };
-                                guard.insert(0, record);
-                                let max_history_records = {
+                                if let Some(previous) = equivalent
+                                    && let Some(record) = new_records.first_mut()
+                                {
+                                    replace_equivalent(repo, &mut guard, previous, record);
+                                }
+                                let (max_history_records, merge_window, size_budget) = {
                                     let settings_guard = settings.read().unwrap();
-                                    settings_guard.storage.max_history_records
+                                    let storage = &settings_guard.storage;
+                                    (
+                                        storage.max_history_records,
+                                        settings_guard.behavior.merge_consecutive_secs,
+                                        storage
+                                            .max_total_bytes
+                                            .map(|max| (max, storage.evict_images_first)),
+                                    )
                                 };
-                                if guard.len() > max_history_records {
-                                    guard.truncate(max_history_records);
-                                    repo.cleanup_old_records(max_history_records).ok();
+                                add_captured(repo, &mut guard, new_records, merge_window);
+                                guard.truncate(max_history_records);
+                                // Checked against the database, since only the newest records
+                                // may be loaded
+                                match repo.trim_history(max_history_records, size_budget) {
+                                    Ok(evicted) if !evicted.is_empty() => {
+                                        guard.retain(|record| !evicted.contains(&record.id));
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => log::error!("Failed to trim the history: {e}"),
                                 }
                             }
-                            let _ = notify_tx.send(()).await;
+                            // Defer the UI refresh until the window is shown again
+                            if refresh_gate.request_refresh() {
+                                let _ = notify_tx.send(UiUpdate::Refresh).await;
+                            }
+                            if !images.is_empty() {
+                                let _ = thumbnail_tx.send(images).await;
+                            }
                         }
                         Err(e) => {
-                            eprintln!("[ropy] Failed to save clipboard record: {e}");
+                            log::error!("Failed to save clipboard record: {e}");
+                            let _ = notify_tx.send(UiUpdate::SaveFailed).await;
                         }
                     }
                 }
@@ -148,15 +753,149 @@ pub fn start_clipboard_listener(
     // Notify GUI to refresh clipboard history
     fg_executor
         .spawn(async move {
-            while (notify_rx.recv().await).is_ok() {
-                let _ = async_app.update(|cx| {
-                    window_handle
-                        .update(cx, |_, _, cx| {
-                            cx.notify();
-                        })
-                        .ok();
+            while let Ok(update) = notify_rx.recv().await {
+                let _ = async_app.update(|cx| match update {
+                    UiUpdate::Refresh => {
+                        window_handle
+                            .update(cx, |_, _, cx| {
+                                cx.notify();
+                            })
+                            .ok();
+                    }
+                    UiUpdate::SaveFailed => on_save_failed(cx),
                 });
             }
         })
         .detach();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_repo(dir: &Path) -> ClipboardRepository {
+        ClipboardRepository::init(dir.join("test.db"), dir.join("images")).unwrap()
+    }
+
+    #[test]
+    fn test_merged_capture_keeps_annotations() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_repo(dir.path());
+        let mut prev = repo.save_text("hello".to_string()).unwrap();
+        prev.favorite = true;
+        prev.note = Some("greeting".to_string());
+        repo.update(&prev).unwrap();
+        let mut records = vec![prev.clone()];
+
+        let new = repo.save_text("hello world".to_string()).unwrap();
+        add_captured(&repo, &mut records, vec![new], Some(3));
+        assert_eq!(records.len(), 1);
+        assert!(records[0].favorite);
+        assert_eq!(records[0].note.as_deref(), Some("greeting"));
+        assert!(repo.get_by_id(prev.id).unwrap().is_none());
+        let stored = repo.get_by_id(records[0].id).unwrap().unwrap();
+        assert!(stored.favorite);
+        assert_eq!(stored.content, "hello world");
+    }
+
+    #[test]
+    fn test_unrelated_superstring_is_not_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = test_repo(dir.path());
+        let prev = repo.save_text("the".to_string()).unwrap();
+        let mut records = vec![prev.clone()];
+
+        let new = repo.save_text("on the other hand".to_string()).unwrap();
+        add_captured(&repo, &mut records, vec![new], Some(3));
+        assert_eq!(records.len(), 2);
+        assert!(repo.get_by_id(prev.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_keep_private() {
+        let records = Mutex::new(Vec::new());
+        let privacy = PrivacyMode::default();
+        keep_private(
+            ClipboardEvent::Text("secret".into()),
+            &records,
+            &privacy,
+            false,
+            10,
+        );
+        keep_private(
+            ClipboardEvent::Images(vec!["/tmp/a.png".into(), "/tmp/b.png".into()]),
+            &records,
+            &privacy,
+            false,
+            10,
+        );
+        {
+            let guard = records.lock().unwrap();
+            assert_eq!(guard.len(), 3);
+            assert!(guard.iter().all(|record| privacy.is_private(record.id)));
+            assert_eq!(guard[2].content, "secret");
+            assert_eq!(guard[0].group_id, guard[1].group_id);
+        }
+        assert_eq!(privacy.take_image_files().len(), 2);
+
+        // Appending only extends a private text record
+        keep_private(
+            ClipboardEvent::Text("more".into()),
+            &records,
+            &privacy,
+            true,
+            2,
+        );
+        keep_private(
+            ClipboardEvent::Text("again".into()),
+            &records,
+            &privacy,
+            true,
+            10,
+        );
+        let guard = records.lock().unwrap();
+        assert_eq!(guard.len(), 2);
+        assert_eq!(guard[0].content, "more\nagain");
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_image_file_paths() {
+        let files = vec![
+            "file:///home/me/My%20Photo.png".to_string(),
+            "file://localhost/tmp/%E5%9B%BE.jpg".to_string(),
+            "/tmp/plain.gif".to_string(),
+            "file:///tmp/notes.txt".to_string(),
+        ];
+        assert_eq!(
+            image_file_paths(&files),
+            Some(vec![
+                "/home/me/My Photo.png".to_string(),
+                "/tmp/图.jpg".to_string(),
+                "/tmp/plain.gif".to_string(),
+            ])
+        );
+        assert_eq!(file_uri_path("file:///tmp/100%.png"), "/tmp/100%.png");
+        assert_eq!(image_file_paths(&files[3..]), None);
+    }
+
+    #[test]
+    fn test_polling_interval() {
+        assert_eq!(polling_interval(None), None);
+        assert_eq!(polling_interval(Some(0)), None);
+        assert_eq!(polling_interval(Some(20)), Some(MIN_POLLING_INTERVAL));
+        assert_eq!(
+            polling_interval(Some(750)),
+            Some(Duration::from_millis(750))
+        );
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_up_to_cap() {
+        assert_eq!(retry_delay(0), Duration::from_millis(500));
+        assert_eq!(retry_delay(1), Duration::from_secs(1));
+        assert_eq!(retry_delay(3), Duration::from_secs(4));
+        assert_eq!(retry_delay(10), MONITOR_RETRY_MAX);
+        assert_eq!(retry_delay(u32::MAX), MONITOR_RETRY_MAX);
+    }
+}
@@ -0,0 +1,86 @@
+//! Decide whether a new capture should replace the previous record.
+
+use crate::repository::ClipboardRecord;
+use crate::repository::models::ContentType;
+use std::time::Duration;
+
+/// Whether `new` continues `prev` and should replace it instead of creating a new record.
+///
+/// This is the case for text captured within `window` that grows the previous text at its
+/// start or end, e.g. an editor updating the clipboard while a selection grows.
+pub fn should_replace_previous(
+    prev: &ClipboardRecord,
+    new: &ClipboardRecord,
+    window: Duration,
+) -> bool {
+    if prev.content_type != ContentType::Text || new.content_type != ContentType::Text {
+        return false;
+    }
+
+    let elapsed = new.created_at.signed_duration_since(prev.created_at);
+    match elapsed.to_std() {
+        Ok(elapsed) if elapsed <= window => {}
+        _ => return false,
+    }
+
+    new.content.len() > prev.content.len()
+        && (new.content.starts_with(&prev.content) || new.content.ends_with(&prev.content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration as ChronoDuration, Local};
+
+    fn record(content: &str, seconds_ago: i64) -> ClipboardRecord {
+        ClipboardRecord {
+            created_at: Local::now() - ChronoDuration::seconds(seconds_ago),
+            ..ClipboardRecord::test_record(0, content)
+        }
+    }
+
+    #[test]
+    fn test_growing_selection_is_merged() {
+        let prev = record("hello", 1);
+        let new = record("hello world", 0);
+        assert!(should_replace_previous(&prev, &new, Duration::from_secs(3)));
+
+        // Selections grow backwards too
+        assert!(should_replace_previous(
+            &prev,
+            &record("say hello", 0),
+            Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn test_not_merged() {
+        let window = Duration::from_secs(3);
+        let prev = record("hello", 10);
+        assert!(!should_replace_previous(
+            &prev,
+            &record("hello world", 0),
+            window
+        ));
+
+        let prev = record("hello", 1);
+        assert!(!should_replace_previous(
+            &prev,
+            &record("goodbye", 0),
+            window
+        ));
+
+        // Shrinking selections and unrelated texts that happen to contain the previous one
+        // are new captures
+        assert!(!should_replace_previous(&prev, &record("hell", 0), window));
+        assert!(!should_replace_previous(
+            &prev,
+            &record("they said hello twice", 0),
+            window
+        ));
+
+        let mut image = record("hello world", 0);
+        image.content_type = ContentType::Image;
+        assert!(!should_replace_previous(&prev, &image, window));
+    }
+}
@@ -1,23 +1,82 @@
+mod append;
+mod auto_clear;
+mod encoding;
+mod filter;
+mod hook;
+#[cfg(feature = "gui")]
 mod listener;
+mod merge;
+mod own_window;
+mod privacy;
+mod refresh;
+mod sticky;
+mod svg;
+mod transform;
 mod utils;
+#[cfg(feature = "gui")]
 mod writer;
 
+pub use append::{APPEND_SEPARATOR, AppendMode, append_text};
+pub use auto_clear::AutoClear;
+pub use encoding::{NormalizedText, TextFlavor, normalize_clipboard_text};
+pub use filter::{has_ignored_format, parse_format_list, should_store_text};
+pub use hook::run_capture_hook;
+#[cfg(feature = "gui")]
 pub use listener::start_clipboard_listener;
+#[cfg(feature = "gui")]
 pub use listener::start_clipboard_monitor;
-pub use utils::save_image;
+pub use merge::should_replace_previous;
+pub use own_window::OwnWindow;
+pub use privacy::PrivacyMode;
+pub use refresh::UiRefreshGate;
+pub use sticky::StickyClipboard;
+pub use svg::{is_svg_source, svg_file_source};
+pub use transform::{Transform, apply_transform};
+pub use utils::{save_image, save_thumbnail, thumbnail_path};
+#[cfg(feature = "gui")]
 pub use writer::start_clipboard_writer;
 
 pub enum ClipboardEvent {
     Text(String),
+    /// Text that could only be decoded with replacement characters
+    LossyText(String),
     Image(String),
+    /// Several images captured from a single clipboard change
+    Images(Vec<String>),
+    /// SVG markup, copied as text or as an `.svg` file
+    Svg(String),
 }
 
 pub enum CopyRequest {
     Text(String),
     Image(String),
+    /// Clear the OS clipboard
+    Clear,
 }
 
 pub enum LastCopyState {
     Text(String),
     Image(u64),
+    /// Don't record the next clipboard change, e.g. a paste that must not touch history
+    IgnoreNext,
+}
+
+impl LastCopyState {
+    /// Whether copied `text` should be recorded, rather than skipped as already seen
+    pub fn is_new_text(&self, text: &str) -> bool {
+        match self {
+            LastCopyState::Text(last) => last != text,
+            LastCopyState::Image(_) => true,
+            LastCopyState::IgnoreNext => false,
+        }
+    }
+
+    /// Whether a copied image with this hash should be recorded, rather than skipped
+    pub fn is_new_image(&self, hash: u64) -> bool {
+        match self {
+            LastCopyState::Image(last) => *last != hash,
+            LastCopyState::Text(_) => true,
+            LastCopyState::IgnoreNext => false,
+        }
+    }
 }
@@ -0,0 +1,69 @@
+//! Tell clipboard changes made inside Ropy's own window apart from everything else.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// State shared by the window, the clipboard writer and the monitor.
+///
+/// Text copied from Ropy's inputs, such as the search box or a note being edited, would
+/// otherwise end up in the history. Copies of records go through the clipboard writer,
+/// which announces them so they are still captured while the window is active.
+#[derive(Debug, Default)]
+pub struct OwnWindow {
+    active: AtomicBool,
+    writing: AtomicBool,
+}
+
+impl OwnWindow {
+    /// Note whether Ropy's window is the active one
+    pub fn set_active(&self, active: bool) {
+        self.active.store(active, Ordering::Release);
+    }
+
+    /// Announce a clipboard write by the writer, so the change it causes is captured
+    pub fn expect_write(&self) {
+        self.writing.store(true, Ordering::Release);
+    }
+
+    /// Whether a clipboard change came from Ropy's own window rather than the writer or
+    /// another app. Consumes a pending write announcement.
+    pub fn is_own_change(&self) -> bool {
+        let announced = self.writing.swap(false, Ordering::AcqRel);
+        self.active.load(Ordering::Acquire) && !announced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_own_change() {
+        let own_window = OwnWindow::default();
+        // Another app is in front
+        assert!(!own_window.is_own_change());
+        own_window.expect_write();
+        assert!(!own_window.is_own_change());
+
+        own_window.set_active(true);
+        assert!(own_window.is_own_change());
+        // A record copied from the list is still captured, once
+        own_window.expect_write();
+        assert!(!own_window.is_own_change());
+        assert!(own_window.is_own_change());
+
+        own_window.set_active(false);
+        assert!(!own_window.is_own_change());
+    }
+
+    #[test]
+    fn test_announcement_used_up_by_skipped_echo() {
+        let own_window = OwnWindow::default();
+        own_window.set_active(true);
+        // A sticky restore rewrites what the clipboard already holds; the monitor skips
+        // that change as an echo but still checks it, using up the announcement
+        own_window.expect_write();
+        assert!(!own_window.is_own_change());
+        // So the next copy from the search box is not taken for the writer's
+        assert!(own_window.is_own_change());
+    }
+}
@@ -0,0 +1,93 @@
+//! Keep captures out of the database for a session.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Switch shared by the UI and the capture task.
+///
+/// While active, captures are only kept in memory: they show up in the list but are never
+/// written to the database, so they are gone after a restart. The IDs of those records are
+/// remembered so reloading the history from disk doesn't drop them, along with the image
+/// files they own so these can be removed on exit.
+#[derive(Debug, Default)]
+pub struct PrivacyMode {
+    active: AtomicBool,
+    session: Mutex<PrivateSession>,
+}
+
+#[derive(Debug, Default)]
+struct PrivateSession {
+    ids: HashSet<u64>,
+    image_files: Vec<String>,
+}
+
+impl PrivacyMode {
+    /// Whether captures are currently kept out of the database
+    pub fn is_active(&self) -> bool {
+        self.active.load(Ordering::Acquire)
+    }
+
+    /// Turn the mode on or off, returning the new state
+    pub fn toggle(&self) -> bool {
+        !self.active.fetch_xor(true, Ordering::AcqRel)
+    }
+
+    /// Remember a record that only exists in memory, and the image file it owns if any
+    pub fn remember(&self, id: u64, image_file: Option<&str>) {
+        let mut session = self.session();
+        session.ids.insert(id);
+        if let Some(path) = image_file {
+            session.image_files.push(path.to_string());
+        }
+    }
+
+    /// Whether the record with this ID was captured in privacy mode
+    pub fn is_private(&self, id: u64) -> bool {
+        self.session().ids.contains(&id)
+    }
+
+    /// Image files saved for private captures so far
+    pub fn image_files(&self) -> Vec<String> {
+        self.session().image_files.clone()
+    }
+
+    /// Image files saved for private captures, emptied so they are only handed out once
+    pub fn take_image_files(&self) -> Vec<String> {
+        std::mem::take(&mut self.session().image_files)
+    }
+
+    fn session(&self) -> std::sync::MutexGuard<'_, PrivateSession> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle() {
+        let mode = PrivacyMode::default();
+        assert!(!mode.is_active());
+        assert!(mode.toggle());
+        assert!(mode.is_active());
+        assert!(!mode.toggle());
+        assert!(!mode.is_active());
+    }
+
+    #[test]
+    fn test_remember() {
+        let mode = PrivacyMode::default();
+        mode.remember(1, None);
+        mode.remember(2, Some("/tmp/ropy-2.png"));
+        assert_eq!(mode.image_files(), vec!["/tmp/ropy-2.png".to_string()]);
+        assert!(mode.is_private(1));
+        assert!(mode.is_private(2));
+        assert!(!mode.is_private(3));
+        assert_eq!(mode.take_image_files(), vec!["/tmp/ropy-2.png".to_string()]);
+        assert!(mode.take_image_files().is_empty());
+        // Records stay known after their files are handed out
+        assert!(mode.is_private(2));
+    }
+}
@@ -0,0 +1,73 @@
+//! Coalesce UI refresh requests while the window is hidden.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Gate that decides whether a clipboard capture should refresh the UI immediately.
+///
+/// While the window is hidden, refresh requests are collapsed into a single pending
+/// flag which is consumed on the next activation.
+#[derive(Debug, Default)]
+pub struct UiRefreshGate {
+    visible: AtomicBool,
+    pending: AtomicBool,
+}
+
+impl UiRefreshGate {
+    pub fn new(visible: bool) -> Self {
+        Self {
+            visible: AtomicBool::new(visible),
+            pending: AtomicBool::new(false),
+        }
+    }
+
+    /// Request a UI refresh. Returns true if the UI should be notified now.
+    pub fn request_refresh(&self) -> bool {
+        if self.visible.load(Ordering::Acquire) {
+            true
+        } else {
+            self.pending.store(true, Ordering::Release);
+            false
+        }
+    }
+
+    /// Whether the window is currently shown
+    pub fn is_visible(&self) -> bool {
+        self.visible.load(Ordering::Acquire)
+    }
+
+    /// Mark the window as hidden
+    pub fn set_hidden(&self) {
+        self.visible.store(false, Ordering::Release);
+    }
+
+    /// Mark the window as visible. Returns true if a refresh was deferred while hidden.
+    pub fn set_visible(&self) -> bool {
+        self.visible.store(true, Ordering::Release);
+        self.pending.swap(false, Ordering::AcqRel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_notifies_every_time() {
+        let gate = UiRefreshGate::new(true);
+        let notified = (0..5).filter(|_| gate.request_refresh()).count();
+        assert_eq!(notified, 5);
+        assert!(!gate.set_visible());
+    }
+
+    #[test]
+    fn test_hidden_coalesces_into_one_refresh() {
+        let gate = UiRefreshGate::new(true);
+        gate.set_hidden();
+        let notified = (0..100).filter(|_| gate.request_refresh()).count();
+        assert_eq!(notified, 0);
+
+        // Exactly one deferred refresh on activation
+        assert!(gate.set_visible());
+        assert!(!gate.set_visible());
+    }
+}
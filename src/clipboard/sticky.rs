@@ -0,0 +1,153 @@
+//! Keep one record on the OS clipboard while other things are copied in between.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use async_channel::Sender;
+
+use super::{CopyRequest, LastCopyState};
+use crate::repository::ClipboardRecord;
+use crate::repository::models::ContentType;
+
+/// Record kept on the clipboard
+#[derive(Debug, Clone, PartialEq)]
+struct StickyItem {
+    id: u64,
+    content: String,
+    content_type: ContentType,
+}
+
+/// Sticky clipboard slot shared by the UI and the capture task.
+///
+/// While a record is pinned, every other capture schedules the record to be written back to
+/// the clipboard. The write goes through the same `LastCopyState` echo suppression as a copy
+/// from the list, so restoring the record is never captured as a new one.
+pub struct StickyClipboard {
+    item: Mutex<Option<StickyItem>>,
+    /// Bumped on every capture, so a pending restore knows a newer copy came after it
+    generation: AtomicU64,
+    copy_tx: Sender<CopyRequest>,
+    last_copy: Arc<Mutex<LastCopyState>>,
+}
+
+impl StickyClipboard {
+    pub fn new(copy_tx: Sender<CopyRequest>, last_copy: Arc<Mutex<LastCopyState>>) -> Self {
+        Self {
+            item: Mutex::new(None),
+            generation: AtomicU64::new(0),
+            copy_tx,
+            last_copy,
+        }
+    }
+
+    /// ID of the pinned record
+    pub fn pinned_id(&self) -> Option<u64> {
+        self.item().as_ref().map(|item| item.id)
+    }
+
+    /// Keep `record` on the clipboard, writing it there right away
+    pub fn pin(&self, record: &ClipboardRecord) {
+        *self.item() = Some(StickyItem {
+            id: record.id,
+            content: record.content.clone(),
+            content_type: record.content_type.clone(),
+        });
+        let generation = self.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        self.restore(generation);
+    }
+
+    /// Stop keeping the pinned record on the clipboard
+    pub fn unpin(&self) {
+        *self.item() = None;
+    }
+
+    /// Note that something else was copied
+    ///
+    /// Returns the generation to pass to [`Self::restore`] once the copy has had time to be
+    /// pasted, or `None` when nothing is pinned.
+    pub fn note_capture(&self) -> Option<u64> {
+        let generation = self.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        self.item().is_some().then_some(generation)
+    }
+
+    /// Write the pinned record back to the clipboard, unless it was unpinned or something
+    /// was copied again since `generation`. Returns whether it was written.
+    pub fn restore(&self, generation: u64) -> bool {
+        if self.generation.load(Ordering::Acquire) != generation {
+            return false;
+        }
+        let Some(item) = self.item().clone() else {
+            return false;
+        };
+        // Keep the listener from capturing the restored content again
+        *self.last_copy.lock().unwrap_or_else(|e| e.into_inner()) = match item.content_type {
+            ContentType::Image => LastCopyState::IgnoreNext,
+            _ => LastCopyState::Text(item.content.clone()),
+        };
+        let request = match item.content_type {
+            ContentType::Image => CopyRequest::Image(item.content),
+            _ => CopyRequest::Text(item.content),
+        };
+        self.copy_tx.send_blocking(request).is_ok()
+    }
+
+    fn item(&self) -> std::sync::MutexGuard<'_, Option<StickyItem>> {
+        self.item.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sticky() -> (
+        StickyClipboard,
+        async_channel::Receiver<CopyRequest>,
+        Arc<Mutex<LastCopyState>>,
+    ) {
+        let (tx, rx) = async_channel::unbounded();
+        let last_copy = Arc::new(Mutex::new(LastCopyState::Text(String::new())));
+        (StickyClipboard::new(tx, last_copy.clone()), rx, last_copy)
+    }
+
+    #[test]
+    fn test_nothing_pinned() {
+        let (sticky, rx, _) = sticky();
+        assert_eq!(sticky.note_capture(), None);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_restores_after_other_copy() {
+        let (sticky, rx, last_copy) = sticky();
+        sticky.pin(&ClipboardRecord::test_record(1, "keep me"));
+        assert_eq!(sticky.pinned_id(), Some(1));
+        assert!(matches!(rx.try_recv(), Ok(CopyRequest::Text(text)) if text == "keep me"));
+
+        *last_copy.lock().unwrap() = LastCopyState::Text("other".to_string());
+        let generation = sticky.note_capture().unwrap();
+        assert!(sticky.restore(generation));
+        assert!(matches!(rx.try_recv(), Ok(CopyRequest::Text(text)) if text == "keep me"));
+        // The restored text is suppressed as an echo
+        assert!(
+            matches!(&*last_copy.lock().unwrap(), LastCopyState::Text(text) if text == "keep me")
+        );
+    }
+
+    #[test]
+    fn test_newer_copy_or_unpin_cancels_restore() {
+        let (sticky, rx, _) = sticky();
+        sticky.pin(&ClipboardRecord::test_record(1, "keep me"));
+        rx.try_recv().unwrap();
+
+        let first = sticky.note_capture().unwrap();
+        let second = sticky.note_capture().unwrap();
+        assert!(!sticky.restore(first));
+        assert!(rx.try_recv().is_err());
+
+        sticky.unpin();
+        assert_eq!(sticky.pinned_id(), None);
+        assert!(!sticky.restore(second));
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,106 @@
+//! Recognizing SVG images copied as markup or as a file.
+
+use std::path::Path;
+
+/// Largest SVG file read from a copied file path, in bytes
+const MAX_SVG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Whether copied text is SVG markup: an `<svg>` root element, optionally preceded by an
+/// XML declaration, comments or a doctype
+pub fn is_svg_source(text: &str) -> bool {
+    let mut rest = text.trim_start_matches('\u{feff}').trim();
+    loop {
+        if let Some(after) = rest.strip_prefix("<?") {
+            let Some((_, tail)) = after.split_once("?>") else {
+                return false;
+            };
+            rest = tail.trim_start();
+        } else if let Some(after) = rest.strip_prefix("<!--") {
+            let Some((_, tail)) = after.split_once("-->") else {
+                return false;
+            };
+            rest = tail.trim_start();
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            let Some((_, tail)) = after.split_once('>') else {
+                return false;
+            };
+            rest = tail.trim_start();
+        } else {
+            break;
+        }
+    }
+    let is_svg_root = rest
+        .strip_prefix("<svg")
+        .and_then(|after| after.chars().next())
+        .is_some_and(|next| next.is_whitespace() || next == '>' || next == '/');
+    if !is_svg_root {
+        return false;
+    }
+    // Either closed at the end, or a lone self-closing root
+    let self_closing = rest.ends_with("/>")
+        && rest
+            .strip_prefix('<')
+            .is_some_and(|after| !after.contains('<'));
+    rest.ends_with("</svg>") || self_closing
+}
+
+/// Markup of a single copied `.svg` file, if that is what was copied
+pub fn svg_file_source(files: &[String]) -> Option<String> {
+    let [file] = files else {
+        return None;
+    };
+    let path = Path::new(file.strip_prefix("file://").unwrap_or(file));
+    let is_svg = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("svg"));
+    if !is_svg || std::fs::metadata(path).ok()?.len() > MAX_SVG_FILE_BYTES {
+        return None;
+    }
+    let source = std::fs::read_to_string(path).ok()?;
+    is_svg_source(&source).then_some(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_svg_source() {
+        assert!(is_svg_source(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><circle r="4"/></svg>"#
+        ));
+        assert!(is_svg_source(
+            "<?xml version=\"1.0\"?>\n<!-- icon -->\n<!DOCTYPE svg>\n<svg viewBox=\"0 0 1 1\"></svg>\n"
+        ));
+        assert!(is_svg_source("<svg/>"));
+        assert!(!is_svg_source("<svgfoo></svgfoo>"));
+        assert!(!is_svg_source("<div><svg></svg></div>"));
+        assert!(!is_svg_source("use <svg> for icons"));
+        assert!(!is_svg_source("<?xml version=\"1.0\"?><html></html>"));
+        // Truncated markup is kept as text
+        assert!(!is_svg_source("<svg><path d=\"M0 0\"/>"));
+        // Text starting with a multi-byte character
+        assert!(!is_svg_source("你好<br/>"));
+    }
+
+    #[test]
+    fn test_svg_file_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let svg = dir.path().join("icon.SVG");
+        std::fs::write(&svg, "<svg></svg>").unwrap();
+        let text = dir.path().join("notes.svg");
+        std::fs::write(&text, "not markup").unwrap();
+
+        let path = svg.to_string_lossy().to_string();
+        assert_eq!(
+            svg_file_source(std::slice::from_ref(&path)),
+            Some("<svg></svg>".to_string())
+        );
+        assert_eq!(
+            svg_file_source(&[format!("file://{path}")]),
+            Some("<svg></svg>".to_string())
+        );
+        assert_eq!(svg_file_source(&[text.to_string_lossy().to_string()]), None);
+        assert_eq!(svg_file_source(&[path.clone(), path]), None);
+    }
+}
@@ -0,0 +1,97 @@
+//! One-off transformations of text applied when pasting, leaving the stored record unchanged
+
+/// Transformation applied to a text record's content before it is copied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Strip leading and trailing whitespace
+    Trim,
+    /// Remove the indentation shared by all non-blank lines
+    Dedent,
+    /// Convert to lowercase
+    Lowercase,
+}
+
+/// Return `content` with `transform` applied
+pub fn apply_transform(content: &str, transform: Transform) -> String {
+    match transform {
+        Transform::Trim => content.trim().to_string(),
+        Transform::Dedent => dedent(content),
+        Transform::Lowercase => content.to_lowercase(),
+    }
+}
+
+/// Remove the longest run of leading spaces and tabs shared by all non-blank lines
+///
+/// Whitespace-only lines don't count towards the shared indentation and are emptied.
+/// Line endings, including a trailing one, are kept.
+fn dedent(content: &str) -> String {
+    let indent = |line: &str| line.len() - line.trim_start_matches([' ', '\t']).len();
+    let common = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..indent(line)])
+        .reduce(|shared, prefix| {
+            let len = shared
+                .bytes()
+                .zip(prefix.bytes())
+                .take_while(|(a, b)| a == b)
+                .count();
+            &shared[..len]
+        })
+        .unwrap_or("");
+
+    content
+        .split_inclusive('\n')
+        .map(|line| {
+            if line.trim().is_empty() {
+                // Keep only the line ending
+                &line[line.trim_end_matches(['\r', '\n']).len()..]
+            } else {
+                &line[common.len()..]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(
+            apply_transform("  hello world \n\t", Transform::Trim),
+            "hello world"
+        );
+        assert_eq!(apply_transform("   ", Transform::Trim), "");
+    }
+
+    #[test]
+    fn test_dedent() {
+        let code = "    fn main() {\n        run();\n    }\n";
+        assert_eq!(
+            apply_transform(code, Transform::Dedent),
+            "fn main() {\n    run();\n}\n"
+        );
+        // Blank lines don't limit the indentation and lose their whitespace
+        assert_eq!(
+            apply_transform("\t\ta\n  \n\t\t\tb", Transform::Dedent),
+            "a\n\n\tb"
+        );
+        // Mixed indentation only shares its common prefix
+        assert_eq!(apply_transform("  a\n\tb", Transform::Dedent), "  a\n\tb");
+        assert_eq!(
+            apply_transform("a\r\n  b\r\n", Transform::Dedent),
+            "a\r\n  b\r\n"
+        );
+        assert_eq!(apply_transform("", Transform::Dedent), "");
+    }
+
+    #[test]
+    fn test_lowercase() {
+        assert_eq!(
+            apply_transform("Hello WORLD Ärger", Transform::Lowercase),
+            "hello world ärger"
+        );
+    }
+}
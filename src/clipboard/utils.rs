@@ -1,14 +1,24 @@
+use crate::repository::ids;
 use chrono::Local;
 use image::DynamicImage;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
 
+/// Largest width and height of a thumbnail
+const THUMBNAIL_SIZE: u32 = 300;
+
+/// Save a captured image into the images directory, returning its path
+///
+/// Only the full image is written, so the record can be stored right away; its thumbnail
+/// is made afterwards with [`save_thumbnail`].
 pub fn save_image(image: DynamicImage) -> Option<String> {
-    let data_dir = dirs::data_local_dir()?.join("ropy").join("images");
+    let data_dir = crate::config::profile::images_dir()?;
     if !data_dir.exists() {
         std::fs::create_dir_all(&data_dir).ok()?;
     }
 
-    let now = Local::now();
-    let id = now.timestamp_nanos_opt().unwrap_or(0) as u64;
+    // Shares the record ID source, so images saved in the same tick get their own files
+    let id = ids::next_id(Local::now(), 1);
     let file_name = format!("{id}.png");
     let file_path = data_dir.join(&file_name);
 
@@ -16,13 +26,42 @@ pub fn save_image(image: DynamicImage) -> Option<String> {
         .save_with_format(&file_path, image::ImageFormat::Png)
         .ok()?;
 
-    // Save thumbnail
-    let thumb_file_name = format!("{id}_thumb.png");
-    let thumb_file_path = data_dir.join(&thumb_file_name);
-    let thumb = image.thumbnail(300, 300);
-    thumb
-        .save_with_format(&thumb_file_path, image::ImageFormat::Png)
-        .ok()?;
-
     Some(file_path.to_string_lossy().to_string())
 }
+
+/// Path of the thumbnail of an image saved by [`save_image`]
+pub fn thumbnail_path(image_path: &Path) -> PathBuf {
+    let file_stem = image_path.file_stem().unwrap_or_default().to_string_lossy();
+    image_path.with_file_name(format!("{file_stem}_thumb.png"))
+}
+
+/// Write the thumbnail of a saved image next to it
+///
+/// The thumbnail appears in one step, so the list never picks up a half-written file; until
+/// then it shows the full image.
+pub fn save_thumbnail(image_path: &Path) -> image::ImageResult<()> {
+    let thumb = image::open(image_path)?.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let mut png = Vec::new();
+    thumb.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+    crate::config::write_atomic(&thumbnail_path(image_path), png)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_thumbnail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("123.png");
+        image::RgbaImage::new(600, 400).save(&path).unwrap();
+
+        save_thumbnail(&path).unwrap();
+        let thumb = image::open(dir.path().join("123_thumb.png")).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (300, 200));
+
+        assert!(save_thumbnail(&dir.path().join("missing.png")).is_err());
+        assert!(!dir.path().join("missing_thumb.png").exists());
+    }
+}
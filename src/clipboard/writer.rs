@@ -1,11 +1,17 @@
-use super::CopyRequest;
+use super::{CopyRequest, OwnWindow};
 use clipboard_rs::{Clipboard, ClipboardContext};
 use gpui::AsyncApp;
 use image::ImageReader;
+use std::sync::Arc;
 
 /// Start a background task to handle clipboard write requests.
 /// This avoids creating a new ClipboardContext and spawning a new task for each write.
-pub fn start_clipboard_writer(async_app: AsyncApp) -> async_channel::Sender<CopyRequest> {
+/// Every write is announced to `own_window`, so copies of records are captured even while
+/// Ropy's window is active.
+pub fn start_clipboard_writer(
+    async_app: AsyncApp,
+    own_window: Arc<OwnWindow>,
+) -> async_channel::Sender<CopyRequest> {
     let (tx, rx) = async_channel::unbounded();
     let executor = async_app.background_executor();
 
@@ -13,6 +19,7 @@ pub fn start_clipboard_writer(async_app: AsyncApp) -> async_channel::Sender<Copy
         .spawn(async move {
             let ctx = ClipboardContext::new().unwrap();
             while let Ok(req) = rx.recv().await {
+                own_window.expect_write();
                 match req {
                     CopyRequest::Text(text) => {
                         set_text(&ctx, text);
@@ -20,6 +27,11 @@ pub fn start_clipboard_writer(async_app: AsyncApp) -> async_channel::Sender<Copy
                     CopyRequest::Image(path) => {
                         set_image(&ctx, path);
                     }
+                    CopyRequest::Clear => {
+                        if let Err(e) = ctx.clear() {
+                            log::error!("Failed to clear clipboard: {e}");
+                        }
+                    }
                 }
             }
         })
@@ -58,7 +70,7 @@ fn set_image(ctx: &ClipboardContext, path: String) {
                 .is_ok()
                 && let Err(e) = ctx.set_buffer("public.png", bytes)
             {
-                eprintln!("Failed to set image to clipboard: {}", e);
+                log::error!("Failed to set image to clipboard: {}", e);
             }
         }
 
@@ -69,7 +81,7 @@ fn set_image(ctx: &ClipboardContext, path: String) {
 
             let rust_image = clipboard_rs::RustImageData::from_dynamic_image(img);
             if let Err(e) = ctx.set_image(rust_image) {
-                eprintln!("Failed to set image to clipboard: {e}");
+                log::error!("Failed to set image to clipboard: {e}");
             }
         }
     }
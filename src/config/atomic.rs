@@ -0,0 +1,67 @@
+//! Crash-safe writes for config files
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Temporary file next to `path` that new contents are written to first
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{name}.tmp"))
+}
+
+/// Replace the file at `path` with `contents`, never leaving it half-written
+///
+/// The contents go to a temporary file in the same directory, which is flushed to disk and
+/// then renamed over the target. A crash before the rename leaves the old file untouched.
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let temp = temp_path(path);
+    let result = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents.as_ref())?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        write_atomic(&path, "a = 1\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a = 1\n");
+
+        write_atomic(&path, "a = 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a = 2\n");
+
+        // Only the target is left behind
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, vec!["config.toml"]);
+    }
+
+    #[test]
+    fn test_failed_write_keeps_old_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "a = 1\n").unwrap();
+
+        // The temporary file can't be created where a directory is in the way
+        std::fs::create_dir(temp_path(&path)).unwrap();
+        assert!(write_atomic(&path, "a = 2\n").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a = 1\n");
+    }
+}
@@ -1,6 +1,16 @@
+mod atomic;
 mod autostart;
+pub mod profile;
 mod settings;
+mod theme;
 
+pub use atomic::write_atomic;
 pub use autostart::{AutoStartError, AutoStartManager};
 pub use settings::AppTheme;
+pub use settings::CopyFeedback;
+pub use settings::Density;
+pub use settings::PasteMode;
+pub use settings::SearchEnter;
 pub use settings::Settings;
+pub use settings::WindowAnchor;
+pub use theme::{ThemeColors, ThemeFile, ThemeFileError, parse_hex_color};
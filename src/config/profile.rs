@@ -0,0 +1,177 @@
+//! Named profiles, each with its own settings, history database and images
+//!
+//! The default profile keeps the paths used before profiles existed. Other profiles live
+//! in a `profiles/<name>` subdirectory of the config and data directories.
+
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Name of the profile using the original, top-level paths
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Subdirectory holding the non-default profiles
+const PROFILES_DIR: &str = "profiles";
+
+/// File in the top-level config directory remembering the last used profile
+const LAST_PROFILE_FILE: &str = "profile";
+
+/// Longest profile name accepted
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// Profile whose settings and history are in use; empty until a profile is chosen
+static ACTIVE_PROFILE: RwLock<String> = RwLock::new(String::new());
+
+/// Name of the profile in use
+pub fn active_profile() -> String {
+    let active = ACTIVE_PROFILE.read().unwrap_or_else(|e| e.into_inner());
+    if active.is_empty() {
+        DEFAULT_PROFILE.to_string()
+    } else {
+        active.clone()
+    }
+}
+
+/// Switch the paths used for settings and images to another profile
+pub fn set_active_profile(name: &str) {
+    *ACTIVE_PROFILE.write().unwrap_or_else(|e| e.into_inner()) = name.to_string();
+}
+
+/// Whether `name` can be used as a profile name, which is also a directory name
+pub fn is_valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LEN
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Directory of a profile below `root`, which is the top-level config or data directory
+pub fn profile_dir(root: &Path, profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        root.to_path_buf()
+    } else {
+        root.join(PROFILES_DIR).join(profile)
+    }
+}
+
+/// Top-level config directory, shared by all profiles
+pub fn config_root() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("ropy"))
+}
+
+/// Top-level data directory, shared by all profiles
+pub fn data_root() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("ropy"))
+}
+
+/// Images directory of the active profile
+pub fn images_dir() -> Option<PathBuf> {
+    Some(profile_dir(&data_root()?, &active_profile()).join("images"))
+}
+
+/// Names of the profiles found below the config directory `root`, default first
+pub fn list_profiles_in(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(root.join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| is_valid_profile_name(name) && name != DEFAULT_PROFILE)
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Names of all profiles, default first
+pub fn list_profiles() -> Vec<String> {
+    match config_root() {
+        Some(root) => list_profiles_in(&root),
+        None => vec![DEFAULT_PROFILE.to_string()],
+    }
+}
+
+/// Profile used last time, falling back to the default one
+pub fn load_last_profile() -> String {
+    config_root()
+        .and_then(|root| std::fs::read_to_string(root.join(LAST_PROFILE_FILE)).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| is_valid_profile_name(name))
+        .unwrap_or_else(|| DEFAULT_PROFILE.to_string())
+}
+
+/// Remember the profile to start with next time
+pub fn save_last_profile(name: &str) -> std::io::Result<()> {
+    let root = config_root().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::NotFound, "Config directory not found")
+    })?;
+    std::fs::create_dir_all(&root)?;
+    super::write_atomic(&root.join(LAST_PROFILE_FILE), name)
+}
+
+/// Profile after `current` in `profiles`, wrapping around
+pub fn next_profile<'a>(profiles: &'a [String], current: &str) -> Option<&'a String> {
+    let index = profiles.iter().position(|name| name == current);
+    match index {
+        Some(index) => profiles.get((index + 1) % profiles.len()),
+        None => profiles.first(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_name_validation() {
+        assert!(is_valid_profile_name("work"));
+        assert!(is_valid_profile_name("side-project_2"));
+        assert!(!is_valid_profile_name(""));
+        assert!(!is_valid_profile_name("../work"));
+        assert!(!is_valid_profile_name("my work"));
+        assert!(!is_valid_profile_name(
+            &"a".repeat(MAX_PROFILE_NAME_LEN + 1)
+        ));
+    }
+
+    #[test]
+    fn test_profile_dir() {
+        let root = Path::new("/data/ropy");
+        assert_eq!(profile_dir(root, DEFAULT_PROFILE), root);
+        assert_eq!(
+            profile_dir(root, "work"),
+            Path::new("/data/ropy/profiles/work")
+        );
+    }
+
+    #[test]
+    fn test_list_profiles_in() {
+        let root = tempfile::tempdir().unwrap();
+        assert_eq!(list_profiles_in(root.path()), vec![DEFAULT_PROFILE]);
+
+        for name in ["work", "home", "bad name"] {
+            std::fs::create_dir_all(root.path().join(PROFILES_DIR).join(name)).unwrap();
+        }
+        std::fs::write(root.path().join(PROFILES_DIR).join("notes.txt"), "").unwrap();
+        assert_eq!(
+            list_profiles_in(root.path()),
+            vec![DEFAULT_PROFILE, "home", "work"]
+        );
+    }
+
+    #[test]
+    fn test_next_profile() {
+        let profiles = vec![
+            DEFAULT_PROFILE.to_string(),
+            "home".to_string(),
+            "work".to_string(),
+        ];
+        assert_eq!(next_profile(&profiles, DEFAULT_PROFILE).unwrap(), "home");
+        assert_eq!(next_profile(&profiles, "work").unwrap(), DEFAULT_PROFILE);
+        assert_eq!(next_profile(&profiles, "gone").unwrap(), DEFAULT_PROFILE);
+        assert_eq!(next_profile(&[], "work"), None);
+    }
+}
@@ -1,8 +1,19 @@
+use super::theme::ThemeColors;
 use crate::i18n::Language;
+use crate::repository::models::SearchScope;
 use config::{Config, ConfigError, File};
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Mutex;
+
+/// Settings as last read from or written to the config file, to tell which ones changed since
+static LAST_SYNCED: Mutex<Option<toml::Table>> = Mutex::new(None);
+
+/// Heights `appearance.list_image_height` is clamped to, in pixels. Thumbnails are at most
+/// 300 pixels, so larger images would only be upscaled.
+pub const LIST_IMAGE_HEIGHT_RANGE: RangeInclusive<f32> = 40.0..=300.0;
 
 /// Application settings structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,9 +28,15 @@ pub struct Settings {
     pub autostart: AutoStartSettings,
     /// Language configuration
     pub language: Language,
+    /// Window behavior configuration
+    pub behavior: BehaviorSettings,
+    /// Appearance configuration
+    pub appearance: AppearanceSettings,
+    /// Commands run on clipboard events
+    pub hooks: HookSettings,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AppTheme {
     Light,
     Dark,
@@ -43,12 +60,37 @@ impl AppTheme {
 pub struct HotkeySettings {
     /// Global hotkey to activate clipboard manager (e.g., "cmd+shift+v")
     pub activation_key: String,
+    /// Global hotkey to clear the OS clipboard (disabled when empty)
+    pub clear_clipboard_key: String,
+    /// Whether clearing the clipboard also deletes the most recent record
+    pub clear_removes_latest: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageSettings {
     /// Maximum number of records to keep in history
     pub max_history_records: usize,
+    /// Hours a deleted record stays in the trash before it is purged
+    pub trash_retention_hours: u32,
+    /// Text shorter than this many characters is not saved (0 saves everything)
+    pub min_text_chars: usize,
+    /// Clipboard changes offering any of these platform format identifiers are not captured
+    #[serde(default)]
+    pub ignored_formats: Vec<String>,
+    /// Total bytes the history may take on disk, including images (unlimited when unset)
+    pub max_total_bytes: Option<u64>,
+    /// Evict image records, largest first, before older text when over `max_total_bytes`
+    pub evict_images_first: bool,
+    /// Replace an earlier text record that only differs in surrounding or repeated whitespace
+    pub dedup_whitespace: bool,
+    /// Replace an earlier record holding the same URL up to tracking parameters, trailing
+    /// slashes and the case of the host; the newly copied URL is stored as is
+    pub dedup_urls: bool,
+    /// Also read the clipboard every this many milliseconds, for systems where change
+    /// events are missed (disabled when unset, as polling costs battery)
+    pub polling_ms: Option<u64>,
+    /// Skip text and images copied inside Ropy's own window, e.g. from the search box
+    pub ignore_self: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +99,141 @@ pub struct AutoStartSettings {
     pub enabled: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BehaviorSettings {
+    /// Hide the window after this many seconds without interaction (disabled when unset)
+    pub auto_hide_secs: Option<u32>,
+    /// Merge consecutive text captures that grow the previous one within this many seconds
+    pub merge_consecutive_secs: Option<u32>,
+    /// Show each record's content hash in the list (hidden debugging option)
+    pub show_content_hash: bool,
+    /// Lifetime in minutes given to a record when marking it to expire
+    pub expiry_mins: u32,
+    /// Start hidden in the tray, as if launched with `--silent`
+    pub start_hidden: bool,
+    /// Record types matched by the search bar
+    pub search_scope: SearchScope,
+    /// Show search results newest first instead of ranking them
+    pub chronological_search: bool,
+    /// Recent search queries, newest first
+    #[serde(default)]
+    pub search_history: Vec<String>,
+    /// Also remember queries made only of `#tag` or `@name` filters
+    pub record_tag_searches: bool,
+    /// Keep the window pinned on top from startup and across hides
+    pub always_pinned: bool,
+    /// Whether pasting a record keeps or removes it
+    pub paste_mode: PasteMode,
+    /// Hide the window when it loses focus, unless it is pinned
+    pub hide_on_focus_loss: bool,
+    /// Ask before the quit shortcut exits the app
+    pub confirm_quit: bool,
+    /// Filter the list only when Enter is pressed in the search box, instead of while typing
+    pub search_on_enter: bool,
+    /// What Enter does while the search box is focused
+    pub search_enter: SearchEnter,
+    /// Records read from disk at startup, with older ones loaded as the list is scrolled to
+    /// its end (0 loads the whole history)
+    pub initial_load_count: usize,
+    /// Seconds after another copy before a record kept on the clipboard is written back,
+    /// leaving time to paste the other copy
+    pub sticky_restore_secs: u32,
+    /// Clear the OS clipboard this many seconds after Ropy copies a record to it, unless
+    /// something else was copied since (disabled when unset)
+    pub clear_clipboard_after_secs: Option<u32>,
+}
+
+/// What happens to a record once it is pasted from the list
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum PasteMode {
+    /// Keep the record, moving it to the top of the history
+    #[default]
+    Copy,
+    /// Remove the record, treating the history as a queue
+    Cut,
+}
+
+/// What Enter does while the search box is focused, once any query waiting for it is applied
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum SearchEnter {
+    /// Copy the top result and close the window, as Enter does in the list
+    #[default]
+    ConfirmTop,
+    /// Move focus from the search box to the list, keeping the window open
+    Blur,
+}
+
+/// Spacing of the records list and header
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum Density {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+/// Confirmation given when a record is copied from the list
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum CopyFeedback {
+    None,
+    /// Briefly highlight the copied row, unless the system asks for reduced motion
+    #[default]
+    Flash,
+    /// Play a short sound
+    Sound,
+}
+
+/// Where the window appears on the screen
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum WindowAnchor {
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppearanceSettings {
+    /// Row density of the records list
+    pub density: Density,
+    /// Maximum width of the expanded preview window, limited by the screen size
+    pub preview_max_width: u32,
+    /// Maximum height of the expanded preview window, limited by the screen size
+    pub preview_max_height: u32,
+    /// Show runs of whitespace as a single space in the list (stored content is unchanged)
+    pub collapse_whitespace: bool,
+    /// Screen position of the window, within the display's work area
+    pub window_anchor: WindowAnchor,
+    /// Colors applied over the Light or Dark theme
+    #[serde(default)]
+    pub custom_colors: ThemeColors,
+    /// How copying a record is confirmed
+    pub copy_feedback: CopyFeedback,
+    /// Maximum height of images in the records list, in pixels; compact rows use 60% of it
+    pub list_image_height: f32,
+}
+
+impl AppearanceSettings {
+    /// `list_image_height` within [`LIST_IMAGE_HEIGHT_RANGE`]
+    pub fn list_image_height(&self) -> f32 {
+        if self.list_image_height.is_nan() {
+            return 100.0;
+        }
+        self.list_image_height.clamp(
+            *LIST_IMAGE_HEIGHT_RANGE.start(),
+            *LIST_IMAGE_HEIGHT_RANGE.end(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HookSettings {
+    /// Shell command run after each capture is saved, with `{content}` and `{type}` replaced
+    /// (disabled when unset). It receives everything copied, passwords included.
+    pub on_capture: Option<String>,
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Self {
@@ -67,22 +244,64 @@ impl Default for Settings {
                 activation_key: "ctrl+shift+d".to_string(),
                 #[cfg(target_os = "linux")]
                 activation_key: "ctrl+shift+d".to_string(),
+                clear_clipboard_key: String::new(),
+                clear_removes_latest: false,
             },
             storage: StorageSettings {
                 max_history_records: 100,
+                trash_retention_hours: 24,
+                min_text_chars: 0,
+                ignored_formats: Vec::new(),
+                max_total_bytes: None,
+                evict_images_first: true,
+                dedup_whitespace: false,
+                dedup_urls: false,
+                polling_ms: None,
+                ignore_self: true,
             },
             theme: AppTheme::System,
             autostart: AutoStartSettings { enabled: false },
             language: Language::default(),
+            behavior: BehaviorSettings {
+                auto_hide_secs: None,
+                merge_consecutive_secs: None,
+                show_content_hash: false,
+                expiry_mins: 60,
+                start_hidden: false,
+                search_scope: SearchScope::default(),
+                chronological_search: false,
+                search_history: Vec::new(),
+                record_tag_searches: true,
+                always_pinned: false,
+                paste_mode: PasteMode::default(),
+                hide_on_focus_loss: true,
+                confirm_quit: false,
+                search_on_enter: false,
+                search_enter: SearchEnter::default(),
+                initial_load_count: 100,
+                sticky_restore_secs: 10,
+                clear_clipboard_after_secs: None,
+            },
+            appearance: AppearanceSettings {
+                density: Density::default(),
+                preview_max_width: 800,
+                preview_max_height: 600,
+                collapse_whitespace: false,
+                window_anchor: WindowAnchor::default(),
+                custom_colors: ThemeColors::default(),
+                copy_feedback: CopyFeedback::default(),
+                list_image_height: 100.0,
+            },
+            hooks: HookSettings::default(),
         }
     }
 }
 
 impl Settings {
-    /// Get the configuration directory path
+    /// Get the configuration directory path of the active profile
     pub fn config_dir() -> Result<PathBuf, ConfigError> {
-        dirs::config_dir()
-            .map(|dir| dir.join("ropy"))
+        super::profile::config_root()
+            .map(|root| super::profile::profile_dir(&root, &super::profile::active_profile()))
             .ok_or_else(|| ConfigError::NotFound("Config directory not found".to_string()))
     }
 
@@ -116,19 +335,111 @@ impl Settings {
         {
             settings.hotkey.activation_key = Settings::default().hotkey.activation_key;
         }
+        // An invalid clear hotkey is disabled rather than replaced
+        if !settings.hotkey.clear_clipboard_key.is_empty()
+            && global_hotkey::hotkey::HotKey::from_str(&settings.hotkey.clear_clipboard_key)
+                .is_err()
+        {
+            settings.hotkey.clear_clipboard_key.clear();
+        }
 
+        settings.mark_synced();
         Ok(settings)
     }
 
+    /// Number of records to read from disk at startup
+    pub fn initial_record_limit(&self) -> usize {
+        let max = self.storage.max_history_records;
+        match self.behavior.initial_load_count {
+            0 => max,
+            count => count.min(max),
+        }
+    }
+
     /// Save settings to configuration file
     pub fn save(&self) -> Result<(), ConfigError> {
         let config_file = Self::config_file()?;
         let toml_string =
             toml::to_string_pretty(self).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
 
-        std::fs::write(&config_file, toml_string).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        if let Some(dir) = config_file.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        }
+        super::write_atomic(&config_file, toml_string)
+            .map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        self.mark_synced();
         Ok(())
     }
+
+    /// Save only the settings changed since the config file was last loaded or saved,
+    /// returning whether anything was written
+    ///
+    /// Other values are taken from the file as it is now, so edits made to it by hand
+    /// are kept.
+    pub fn save_pending(&self) -> Result<bool, ConfigError> {
+        let current = self.to_table()?;
+        let synced = LAST_SYNCED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let Some(synced) = synced else {
+            self.save()?;
+            return Ok(true);
+        };
+        if synced == current {
+            return Ok(false);
+        }
+        let config_file = Self::config_file()?;
+        let on_disk = std::fs::read_to_string(&config_file)
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Table>(&text).ok());
+        // An unreadable file has nothing worth keeping
+        let Some(mut merged) = on_disk else {
+            self.save()?;
+            return Ok(true);
+        };
+        merge_changes(&mut merged, &synced, &current);
+        let toml_string =
+            toml::to_string_pretty(&merged).map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        super::write_atomic(&config_file, toml_string)
+            .map_err(|e| ConfigError::Foreign(Box::new(e)))?;
+        self.mark_synced();
+        Ok(true)
+    }
+
+    fn to_table(&self) -> Result<toml::Table, ConfigError> {
+        toml::Table::try_from(self).map_err(|e| ConfigError::Foreign(Box::new(e)))
+    }
+
+    /// Remember these settings as the config file's content
+    fn mark_synced(&self) {
+        if let Ok(table) = self.to_table() {
+            *LAST_SYNCED.lock().unwrap_or_else(|e| e.into_inner()) = Some(table);
+        }
+    }
+}
+
+/// Apply the values that differ between `base` and `current` to `target`, key by key
+fn merge_changes(target: &mut toml::Table, base: &toml::Table, current: &toml::Table) {
+    for (key, value) in current {
+        match (value, base.get(key)) {
+            (toml::Value::Table(current), Some(toml::Value::Table(base))) => {
+                if let Some(toml::Value::Table(target)) = target.get_mut(key) {
+                    merge_changes(target, base, current);
+                } else if current != base {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+            (value, base) if base != Some(value) => {
+                target.insert(key.clone(), value.clone());
+            }
+            _ => {}
+        }
+    }
+    // Unset optional values are left out of the table
+    for key in base.keys().filter(|key| !current.contains_key(*key)) {
+        target.remove(key);
+    }
 }
 
 #[cfg(test)]
@@ -139,6 +450,56 @@ mod tests {
     fn test_default_settings() {
         let settings = Settings::default();
         assert_eq!(settings.storage.max_history_records, 100);
+        assert_eq!(settings.behavior.auto_hide_secs, None);
+        assert_eq!(settings.behavior.clear_clipboard_after_secs, None);
+        assert!(!settings.behavior.start_hidden);
+        assert!(settings.behavior.hide_on_focus_loss);
+        assert!(!settings.behavior.confirm_quit);
+        assert!(!settings.behavior.search_on_enter);
+        assert_eq!(settings.behavior.search_enter, SearchEnter::ConfirmTop);
+        assert_eq!(settings.hooks.on_capture, None);
+        assert!(settings.storage.ignore_self);
+        assert_eq!(settings.appearance.copy_feedback, CopyFeedback::Flash);
+        assert_eq!(settings.appearance.list_image_height(), 100.0);
+    }
+
+    #[test]
+    fn test_list_image_height() {
+        let mut appearance = Settings::default().appearance;
+        appearance.list_image_height = 180.0;
+        assert_eq!(appearance.list_image_height(), 180.0);
+        appearance.list_image_height = 5.0;
+        assert_eq!(appearance.list_image_height(), 40.0);
+        appearance.list_image_height = 2000.0;
+        assert_eq!(appearance.list_image_height(), 300.0);
+        appearance.list_image_height = f32::NAN;
+        assert_eq!(appearance.list_image_height(), 100.0);
+    }
+
+    #[test]
+    fn test_initial_record_limit() {
+        let mut settings = Settings::default();
+        settings.storage.max_history_records = 5000;
+        assert_eq!(settings.initial_record_limit(), 100);
+        settings.behavior.initial_load_count = 0;
+        assert_eq!(settings.initial_record_limit(), 5000);
+        settings.behavior.initial_load_count = 10_000;
+        assert_eq!(settings.initial_record_limit(), 5000);
+    }
+
+    #[test]
+    fn test_merge_changes() {
+        let table = |text: &str| toml::from_str::<toml::Table>(text).unwrap();
+        let base = table("a = 1\nb = 2\nlimit = 5\n[ui]\ntheme = \"light\"\nsize = 1\n");
+        let current = table("a = 1\nb = 3\n[ui]\ntheme = \"dark\"\nsize = 1\n");
+        // Edited by hand since it was loaded
+        let mut on_disk = table("a = 9\nb = 2\nlimit = 5\n[ui]\ntheme = \"light\"\nsize = 4\n");
+
+        merge_changes(&mut on_disk, &base, &current);
+        assert_eq!(
+            on_disk,
+            table("a = 9\nb = 3\n[ui]\ntheme = \"dark\"\nsize = 4\n")
+        );
     }
 
     #[test]
@@ -0,0 +1,217 @@
+//! Custom theme colors and the shareable theme files built from them
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::settings::{AppTheme, Density, Settings};
+
+/// Range the preview window size of an imported theme is clamped to
+const PREVIEW_SIZE_RANGE: std::ops::RangeInclusive<u32> = 200..=4000;
+
+#[derive(Debug, Error)]
+pub enum ThemeFileError {
+    #[error("Failed to read theme file: {0}")]
+    Parse(String),
+    #[error("Failed to write theme file: {0}")]
+    Serialize(String),
+    #[error("Invalid color for {name}: {value}")]
+    InvalidColor { name: &'static str, value: String },
+}
+
+/// Colors overriding those of the Light or Dark theme, as `#rgb` or `#rrggbb` hex
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeColors {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub foreground: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secondary_foreground: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub border: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub muted_foreground: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+}
+
+impl ThemeColors {
+    /// Every color slot with its name, set or not
+    fn slots(&self) -> [(&'static str, &Option<String>); 8] {
+        [
+            ("background", &self.background),
+            ("foreground", &self.foreground),
+            ("secondary", &self.secondary),
+            ("secondary_foreground", &self.secondary_foreground),
+            ("border", &self.border),
+            ("accent", &self.accent),
+            ("muted_foreground", &self.muted_foreground),
+            ("input", &self.input),
+        ]
+    }
+
+    /// Fail on the first set color that isn't valid hex
+    pub fn validate(&self) -> Result<(), ThemeFileError> {
+        for (name, value) in self.slots() {
+            if let Some(value) = value
+                && parse_hex_color(value).is_none()
+            {
+                return Err(ThemeFileError::InvalidColor {
+                    name,
+                    value: value.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Parsed `0xrrggbb` value of a color slot, if it is set and valid
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.slots()
+            .into_iter()
+            .find(|(slot, _)| *slot == name)
+            .and_then(|(_, value)| parse_hex_color(value.as_deref()?))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots().iter().all(|(_, value)| value.is_none())
+    }
+}
+
+/// Parse `#rgb` or `#rrggbb` into `0xrrggbb`
+pub fn parse_hex_color(value: &str) -> Option<u32> {
+    let hex = value.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    match hex.len() {
+        3 => {
+            let short = u32::from_str_radix(hex, 16).ok()?;
+            let (r, g, b) = ((short >> 8) & 0xf, (short >> 4) & 0xf, short & 0xf);
+            Some(((r * 17) << 16) | ((g * 17) << 8) | (b * 17))
+        }
+        6 => u32::from_str_radix(hex, 16).ok(),
+        _ => None,
+    }
+}
+
+/// The appearance settings that make up a theme, as exported to and imported from TOML
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeFile {
+    /// Built-in theme the colors are applied on top of
+    pub base: AppTheme,
+    pub density: Density,
+    pub preview_max_width: u32,
+    pub preview_max_height: u32,
+    #[serde(default)]
+    pub colors: ThemeColors,
+}
+
+impl ThemeFile {
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            base: settings.theme.clone(),
+            density: settings.appearance.density,
+            preview_max_width: settings.appearance.preview_max_width,
+            preview_max_height: settings.appearance.preview_max_height,
+            colors: settings.appearance.custom_colors.clone(),
+        }
+    }
+
+    pub fn to_toml(&self) -> Result<String, ThemeFileError> {
+        toml::to_string_pretty(self).map_err(|e| ThemeFileError::Serialize(e.to_string()))
+    }
+
+    /// Parse a theme file, rejecting invalid colors and clamping sizes to sane bounds
+    pub fn from_toml(text: &str) -> Result<Self, ThemeFileError> {
+        let mut theme: Self =
+            toml::from_str(text).map_err(|e| ThemeFileError::Parse(e.to_string()))?;
+        theme.colors.validate()?;
+        let (min, max) = (*PREVIEW_SIZE_RANGE.start(), *PREVIEW_SIZE_RANGE.end());
+        theme.preview_max_width = theme.preview_max_width.clamp(min, max);
+        theme.preview_max_height = theme.preview_max_height.clamp(min, max);
+        Ok(theme)
+    }
+
+    pub fn apply_to(self, settings: &mut Settings) {
+        settings.theme = self.base;
+        settings.appearance.density = self.density;
+        settings.appearance.preview_max_width = self.preview_max_width;
+        settings.appearance.preview_max_height = self.preview_max_height;
+        settings.appearance.custom_colors = self.colors;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8800"), Some(0xff8800));
+        assert_eq!(parse_hex_color(" #F80 "), Some(0xff8800));
+        assert_eq!(parse_hex_color("ff8800"), None);
+        assert_eq!(parse_hex_color("#ff880"), None);
+        assert_eq!(parse_hex_color("#gg8800"), None);
+        assert_eq!(parse_hex_color("#+f8800"), None);
+    }
+
+    #[test]
+    fn test_theme_file_round_trip() {
+        let mut settings = Settings {
+            theme: AppTheme::Dark,
+            ..Default::default()
+        };
+        settings.appearance.density = Density::Compact;
+        settings.appearance.custom_colors.accent = Some("#336699".to_string());
+
+        let text = ThemeFile::from_settings(&settings).to_toml().unwrap();
+        let theme = ThemeFile::from_toml(&text).unwrap();
+        assert_eq!(theme, ThemeFile::from_settings(&settings));
+
+        let mut imported = Settings::default();
+        theme.apply_to(&mut imported);
+        assert_eq!(imported.theme, AppTheme::Dark);
+        assert_eq!(imported.appearance.density, Density::Compact);
+        assert_eq!(
+            imported.appearance.custom_colors.get("accent"),
+            Some(0x336699)
+        );
+    }
+
+    #[test]
+    fn test_theme_file_validation() {
+        let text = r##"
+            base = "Light"
+            density = "Comfortable"
+            preview_max_width = 99999
+            preview_max_height = 1
+
+            [colors]
+            background = "#101010"
+        "##;
+        let theme = ThemeFile::from_toml(text).unwrap();
+        assert_eq!(theme.preview_max_width, 4000);
+        assert_eq!(theme.preview_max_height, 200);
+        assert_eq!(theme.colors.get("background"), Some(0x101010));
+        assert_eq!(theme.colors.get("accent"), None);
+
+        let invalid = text.replace("#101010", "red");
+        assert!(matches!(
+            ThemeFile::from_toml(&invalid),
+            Err(ThemeFileError::InvalidColor {
+                name: "background",
+                ..
+            })
+        ));
+        assert!(matches!(
+            ThemeFile::from_toml("base = 3"),
+            Err(ThemeFileError::Parse(_))
+        ));
+    }
+}
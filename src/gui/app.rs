@@ -1,19 +1,28 @@
-use crate::clipboard::{self, ClipboardEvent, LastCopyState};
-use crate::config::{AppTheme, AutoStartManager, Settings};
+use crate::clipboard::{
+    self, AppendMode, AutoClear, ClipboardEvent, LastCopyState, OwnWindow, PrivacyMode,
+    StickyClipboard, UiRefreshGate,
+};
+use crate::config::{AppTheme, AutoStartManager, Settings, ThemeColors, WindowAnchor, profile};
 use crate::gui::board::RopyBoard;
+use crate::gui::hotkey::{
+    HotkeyAction, HotkeyBindings, bindings_from_settings, start_hotkey_listener,
+};
+#[cfg(target_os = "linux")]
+use crate::gui::session::SessionType;
 use crate::gui::tray::start_tray_handler_inner;
+use crate::gui::utils::{
+    anchored_bounds, board_window_size, rebuild_stale_index, set_always_on_top,
+};
 use crate::gui::x11::X11;
-use crate::repository::{ClipboardRecord, ClipboardRepository};
+use crate::repository::{ActiveRepository, ClipboardRecord, ClipboardRepository, ClipboardStore};
 use gpui::{
     App, AppContext, Application, AssetSource, AsyncApp, Bounds, KeyBinding, WindowBounds,
-    WindowHandle, WindowKind, WindowOptions, px, rgb, size,
+    WindowHandle, WindowKind, WindowOptions, rgb,
 };
 use gpui_component::theme::Theme;
 use gpui_component::{Root, ThemeMode};
 use rust_embed::RustEmbed;
 use std::borrow::Cow;
-#[cfg(target_os = "linux")]
-use std::env;
 use std::sync::{Arc, Mutex, OnceLock, RwLock, mpsc};
 use std::time::Duration;
 
@@ -50,13 +59,15 @@ fn set_activation_policy_accessory() {
 }
 
 fn initialize_repository() -> Option<Arc<ClipboardRepository>> {
-    match ClipboardRepository::new() {
+    match ClipboardRepository::new(&profile::active_profile()) {
         Ok(repo) => {
-            println!("[ropy] Clipboard history repository initialized");
-            Some(Arc::new(repo))
+            log::info!("Clipboard history repository initialized");
+            let repo = Arc::new(repo);
+            rebuild_stale_index(&repo);
+            Some(repo)
         }
         Err(e) => {
-            eprintln!("[ropy] Clipboard repository initialization failed: {e}");
+            log::error!("Clipboard repository initialization failed: {e}");
             None
         }
     }
@@ -66,10 +77,11 @@ fn load_initial_records(
     repository: &Option<Arc<ClipboardRepository>>,
     settings: &Arc<RwLock<Settings>>,
 ) -> Vec<ClipboardRecord> {
-    let max_records = settings.read().unwrap().storage.max_history_records;
+    // The rest is loaded as the list is scrolled
+    let limit = settings.read().unwrap().initial_record_limit();
     repository
         .as_ref()
-        .and_then(|repo| repo.get_recent(max_records).ok())
+        .and_then(|repo| repo.get_recent(limit).ok())
         .unwrap_or_default()
 }
 
@@ -80,10 +92,10 @@ fn sync_autostart_on_launch(settings: &Arc<RwLock<Settings>>) {
     match AutoStartManager::new("Ropy") {
         Ok(manager) => {
             if let Err(e) = manager.sync_state(autostart_enabled) {
-                eprintln!("[ropy] Failed to sync auto-start state on launch: {e}");
+                log::error!("Failed to sync auto-start state on launch: {e}");
             } else {
-                println!(
-                    "[ropy] Auto-start state synced: {}",
+                log::info!(
+                    "Auto-start state synced: {}",
                     if autostart_enabled {
                         "enabled"
                     } else {
@@ -93,7 +105,7 @@ fn sync_autostart_on_launch(settings: &Arc<RwLock<Settings>>) {
             }
         }
         Err(e) => {
-            eprintln!("[ropy] Failed to initialize auto-start manager: {e}");
+            log::error!("Failed to initialize auto-start manager: {e}");
         }
     }
 }
@@ -101,9 +113,19 @@ fn sync_autostart_on_launch(settings: &Arc<RwLock<Settings>>) {
 fn start_clipboard_monitor(
     async_app: AsyncApp,
     last_copy: Arc<Mutex<LastCopyState>>,
+    settings: Arc<RwLock<Settings>>,
+    own_window: Arc<OwnWindow>,
+    auto_clear: Arc<AutoClear>,
 ) -> async_channel::Receiver<ClipboardEvent> {
     let (clipboard_tx, clipboard_rx) = async_channel::unbounded::<ClipboardEvent>();
-    clipboard::start_clipboard_monitor(clipboard_tx, async_app, last_copy);
+    clipboard::start_clipboard_monitor(
+        clipboard_tx,
+        async_app,
+        last_copy,
+        settings,
+        own_window,
+        auto_clear,
+    );
     clipboard_rx
 }
 
@@ -111,31 +133,65 @@ fn setup_hotkey_listener(
     window_handle: WindowHandle<Root>,
     async_app: AsyncApp,
     settings: Arc<RwLock<Settings>>,
-) -> async_channel::Sender<String> {
+) -> async_channel::Sender<HotkeyBindings> {
     let fg_executor = async_app.foreground_executor().clone();
     let bg_executor = async_app.background_executor().clone();
-    let hotkey_str = settings.read().unwrap().hotkey.activation_key.clone();
-    crate::gui::hotkey::start_hotkey_listener(hotkey_str, fg_executor, bg_executor, move || {
-        let _ = async_app.update(move |cx| {
-            window_handle
-                .update(cx, |_, window, cx| {
-                    window.dispatch_action(Box::new(crate::gui::board::Active), cx)
-                })
-                .ok();
-        });
-    })
+    let bindings = bindings_from_settings(&settings.read().unwrap());
+    let failure_app = async_app.clone();
+    start_hotkey_listener(
+        bindings,
+        fg_executor,
+        bg_executor,
+        move |action| {
+            let _ = async_app.update(move |cx| {
+                window_handle
+                    .update(cx, |_, window, cx| match action {
+                        HotkeyAction::Activate => {
+                            window.dispatch_action(Box::new(crate::gui::board::Active), cx)
+                        }
+                        HotkeyAction::ClearClipboard => {
+                            window.dispatch_action(Box::new(crate::gui::board::ClearClipboard), cx)
+                        }
+                    })
+                    .ok();
+            });
+        },
+        move |failed| {
+            let _ = failure_app.update(move |cx| {
+                window_handle
+                    .update(cx, |root, _, cx| {
+                        if let Ok(board) = root.view().clone().downcast::<RopyBoard>() {
+                            board.update(cx, |board, cx| board.set_failed_hotkeys(failed, cx));
+                        }
+                    })
+                    .ok();
+            });
+        },
+    )
 }
 
 fn create_window(
     cx: &mut App,
     shared_records: Arc<Mutex<Vec<ClipboardRecord>>>,
-    repository: Option<Arc<ClipboardRepository>>,
+    repository: Option<Arc<dyn ClipboardStore>>,
     settings: Arc<RwLock<Settings>>,
     last_copy: Arc<Mutex<LastCopyState>>,
     copy_tx: async_channel::Sender<crate::clipboard::CopyRequest>,
+    refresh_gate: Arc<UiRefreshGate>,
+    append_mode: Arc<AppendMode>,
+    privacy_mode: Arc<PrivacyMode>,
+    sticky: Arc<StickyClipboard>,
+    own_window: Arc<OwnWindow>,
+    auto_clear: Arc<AutoClear>,
     is_silent: bool,
 ) -> WindowHandle<Root> {
-    let bounds = Bounds::centered(None, size(px(400.), px(600.0)), cx);
+    let anchor = settings.read().unwrap().appearance.window_anchor;
+    let bounds = match cx.primary_display() {
+        Some(display) if anchor != WindowAnchor::Center => {
+            anchored_bounds(anchor, display.visible_bounds(), board_window_size())
+        }
+        _ => Bounds::centered(None, board_window_size(), cx),
+    };
     cx.open_window(
         WindowOptions {
             window_bounds: Some(WindowBounds::Windowed(bounds)),
@@ -146,8 +202,14 @@ fn create_window(
         },
         |window, cx| {
             // Apply the application theme based on settings
-            let app_theme = &settings.read().unwrap().theme.get_theme();
-            set_app_theme(window, cx, app_theme);
+            let (app_theme, colors) = {
+                let settings = settings.read().unwrap();
+                (
+                    settings.theme.get_theme(),
+                    settings.appearance.custom_colors.clone(),
+                )
+            };
+            set_app_theme(window, cx, &app_theme, &colors);
 
             let view = cx.new(|cx| {
                 RopyBoard::new(
@@ -156,6 +218,12 @@ fn create_window(
                     settings,
                     last_copy,
                     copy_tx,
+                    refresh_gate,
+                    append_mode,
+                    privacy_mode,
+                    sticky,
+                    own_window,
+                    auto_clear,
                     window,
                     cx,
                 )
@@ -166,8 +234,13 @@ fn create_window(
     .unwrap()
 }
 
-/// Set the application theme (light or dark)
-pub fn set_app_theme(window: &mut gpui::Window, cx: &mut App, app_theme: &AppTheme) {
+/// Set the application theme (light or dark), then apply the user's custom colors over it
+pub fn set_app_theme(
+    window: &mut gpui::Window,
+    cx: &mut App,
+    app_theme: &AppTheme,
+    colors: &ThemeColors,
+) {
     match app_theme.get_theme() {
         AppTheme::Dark => {
             Theme::change(ThemeMode::Dark, Some(window), cx);
@@ -193,11 +266,57 @@ pub fn set_app_theme(window: &mut gpui::Window, cx: &mut App, app_theme: &AppThe
             theme.muted_foreground = rgb(0x6b6b6b).into();
             theme.input = rgb(0xf0f0f0).into();
         }
-        _ => {}
+        _ => return,
+    }
+
+    let theme = Theme::global_mut(cx);
+    let slots = [
+        ("background", &mut theme.background),
+        ("foreground", &mut theme.foreground),
+        ("secondary", &mut theme.secondary),
+        ("secondary_foreground", &mut theme.secondary_foreground),
+        ("border", &mut theme.border),
+        ("accent", &mut theme.accent),
+        ("muted_foreground", &mut theme.muted_foreground),
+        ("input", &mut theme.input),
+    ];
+    for (name, slot) in slots {
+        if let Some(color) = colors.get(name) {
+            *slot = rgb(color).into();
+        }
     }
 }
 
-pub fn launch_app() {
+/// Persist state before the app exits
+///
+/// Runs on every quit route (quit shortcut, tray menu, closing the window) since they all
+/// end in `cx.quit()`. Image files of captures made in privacy mode are removed, since no
+/// record refers to them after a restart. Only settings changed since the config file was
+/// last loaded or saved are written, so edits made to it by hand are kept.
+fn shutdown(
+    repository: Option<&ClipboardRepository>,
+    settings: &RwLock<Settings>,
+    privacy_mode: &PrivacyMode,
+) {
+    for path in privacy_mode.take_image_files() {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("Failed to remove private image {path}: {e}");
+        }
+    }
+    if let Some(repo) = repository
+        && let Err(e) = repo.flush()
+    {
+        log::error!("Failed to flush clipboard history on exit: {e}");
+    }
+    if let Err(e) = settings.read().unwrap().save_pending() {
+        log::error!("Failed to save settings on exit: {e}");
+    }
+}
+
+/// Run the GUI application until it quits
+///
+/// `on_exit` runs after state has been persisted, e.g. to release a single-instance lock.
+pub fn launch_app(on_exit: impl FnOnce() + 'static) {
     let args: Vec<String> = std::env::args().collect();
     let is_silent = args.iter().any(|arg| arg == "--silent");
 
@@ -212,34 +331,88 @@ pub fn launch_app() {
         // Bind global application keys
         bind_application_keys(cx);
 
+        // Settings and history belong to the profile used last time
+        profile::set_active_profile(&profile::load_last_profile());
         let settings = load_settings();
+        let (is_silent, always_pinned) = {
+            let behavior = &settings.read().unwrap().behavior;
+            (is_silent || behavior.start_hidden, behavior.always_pinned)
+        };
 
         // Sync auto-start state on application launch
         sync_autostart_on_launch(&settings);
 
         let repository = initialize_repository();
+        let privacy_mode = Arc::new(PrivacyMode::default());
+        let active_repository: ActiveRepository = Arc::new(RwLock::new(repository.clone()));
+        let shutdown_repository = active_repository.clone();
+        let shutdown_settings = settings.clone();
+        let shutdown_privacy = privacy_mode.clone();
+        let mut on_exit = Some(on_exit);
+        cx.on_app_quit(move |_| {
+            shutdown(
+                shutdown_repository.read().unwrap().as_deref(),
+                &shutdown_settings,
+                &shutdown_privacy,
+            );
+            if let Some(on_exit) = on_exit.take() {
+                on_exit();
+            }
+            async {}
+        })
+        .detach();
         let initial_records = load_initial_records(&repository, &settings);
         let shared_records = Arc::new(Mutex::new(initial_records));
         let last_copy = Arc::new(Mutex::new(LastCopyState::Text("".to_string())));
         let async_app = cx.to_async();
-        let clipboard_rx = start_clipboard_monitor(async_app.clone(), last_copy.clone());
-        let copy_tx = clipboard::start_clipboard_writer(async_app.clone());
+        let own_window = Arc::new(OwnWindow::default());
+        let copy_tx = clipboard::start_clipboard_writer(async_app.clone(), own_window.clone());
+        let auto_clear = Arc::new(AutoClear::new(copy_tx.clone(), last_copy.clone()));
+        let clipboard_rx = start_clipboard_monitor(
+            async_app.clone(),
+            last_copy.clone(),
+            settings.clone(),
+            own_window.clone(),
+            auto_clear.clone(),
+        );
+        let sticky = Arc::new(StickyClipboard::new(copy_tx.clone(), last_copy.clone()));
+        let refresh_gate = Arc::new(UiRefreshGate::new(!is_silent));
+        let append_mode = Arc::new(AppendMode::default());
         let window_handle = create_window(
             cx,
             shared_records.clone(),
-            repository.clone(),
+            repository
+                .clone()
+                .map(|repo| repo as Arc<dyn ClipboardStore>),
             settings.clone(),
             last_copy.clone(),
             copy_tx,
+            refresh_gate.clone(),
+            append_mode.clone(),
+            privacy_mode.clone(),
+            sticky.clone(),
+            own_window,
+            auto_clear,
             is_silent,
         );
         clipboard::start_clipboard_listener(
             clipboard_rx,
             shared_records,
-            repository.clone(),
+            active_repository.clone(),
             settings.clone(),
+            refresh_gate,
+            append_mode,
+            privacy_mode,
+            sticky,
             async_app.clone(),
             window_handle,
+            move |cx| {
+                let _ = window_handle.update(cx, |root, _, cx| {
+                    if let Ok(board) = root.view().clone().downcast::<RopyBoard>() {
+                        board.update(cx, |board, cx| board.notify_save_failed(cx));
+                    }
+                });
+            },
         );
         let hotkey_tx = setup_hotkey_listener(window_handle, async_app.clone(), settings.clone());
         let _ = window_handle.update(cx, |root, _, cx| {
@@ -249,9 +422,13 @@ pub fn launch_app() {
                 .unwrap()
                 .update(cx, |board, _| {
                     board.set_hotkey_tx(hotkey_tx);
+                    board.set_active_repository(active_repository);
+                    board.purge_trash();
                 });
         });
 
+        start_expiry_timer(async_app.clone(), window_handle);
+        start_system_theme_watcher(settings.clone(), async_app.clone(), window_handle);
         start_tray_handler(settings, async_app, window_handle);
 
         if !is_silent {
@@ -260,13 +437,39 @@ pub fn launch_app() {
 
         // Initialize X11 control
         #[cfg(target_os = "linux")]
-        if env::var("DISPLAY").is_ok() {
-            let x11 = X11.get_or_init(|| X11::new().expect("Failed to connect x11rb"));
-            let _ = x11.active_window();
+        init_linux_window_control();
+
+        // Window control is only available once the platform window exists
+        if always_pinned {
+            let _ = window_handle.update(cx, |_, window, cx| {
+                set_always_on_top(window, cx, true);
+            });
         }
     });
 }
 
+/// Connect X11 window control when available, degrading gracefully otherwise
+#[cfg(target_os = "linux")]
+fn init_linux_window_control() {
+    match SessionType::detect() {
+        SessionType::X11 => match X11::new() {
+            Ok(x11) => {
+                let _ = x11.active_window();
+                let _ = X11.set(x11);
+            }
+            Err(e) => log::warn!(
+                "Failed to connect to X11, always-on-top and hiding the window are unavailable: {e}"
+            ),
+        },
+        SessionType::Wayland => log::warn!(
+            "Wayland session detected: always-on-top is unsupported and hiding the window falls back to minimizing"
+        ),
+        SessionType::Unknown => {
+            log::warn!("Unknown display session, window control is unavailable")
+        }
+    }
+}
+
 fn start_tray_handler(
     settings: Arc<RwLock<Settings>>,
     async_app: AsyncApp,
@@ -300,6 +503,30 @@ fn start_tray_handler(
                                 crate::gui::tray::send_active_action(window_handle, cx);
                             });
                         }
+                        TrayEvent::TogglePrivacy => {
+                            let _ = async_app.update(move |cx| {
+                                window_handle
+                                    .update(cx, |_, window, cx| {
+                                        window.dispatch_action(
+                                            Box::new(crate::gui::board::TogglePrivacyMode),
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            });
+                        }
+                        TrayEvent::NextProfile => {
+                            let _ = async_app.update(move |cx| {
+                                window_handle
+                                    .update(cx, |_, window, cx| {
+                                        window.dispatch_action(
+                                            Box::new(crate::gui::board::NextProfile),
+                                            cx,
+                                        )
+                                    })
+                                    .ok();
+                            });
+                        }
                         TrayEvent::Quit => {
                             let _ = async_app.update(move |cx| {
                                 cx.quit();
@@ -314,6 +541,84 @@ fn start_tray_handler(
         .detach();
 }
 
+/// Follow OS light/dark changes while the System theme is selected
+fn start_system_theme_watcher(
+    settings: Arc<RwLock<Settings>>,
+    async_app: AsyncApp,
+    window_handle: WindowHandle<Root>,
+) {
+    let (tx, rx) = async_channel::unbounded::<AppTheme>();
+    let fg_executor = async_app.foreground_executor().clone();
+    let bg_executor = async_app.background_executor().clone();
+    let bg_executor_clone = bg_executor.clone();
+    let fg_settings = settings.clone();
+
+    // Detection may block on platform APIs, so poll from the background executor
+    bg_executor
+        .spawn(async move {
+            let mut last_resolved: Option<AppTheme> = None;
+            loop {
+                bg_executor_clone.timer(Duration::from_secs(2)).await;
+                if settings.read().unwrap().theme != AppTheme::System {
+                    last_resolved = None;
+                    continue;
+                }
+                let resolved = AppTheme::System.get_theme();
+                if last_resolved.as_ref() != Some(&resolved) {
+                    // The first detection only records the current state
+                    if last_resolved.is_some() && tx.send(resolved.clone()).await.is_err() {
+                        break;
+                    }
+                    last_resolved = Some(resolved);
+                }
+            }
+        })
+        .detach();
+
+    fg_executor
+        .spawn(async move {
+            while let Ok(theme) = rx.recv().await {
+                let _ = async_app.update(|cx| {
+                    window_handle
+                        .update(cx, |_, window, cx| {
+                            let colors =
+                                fg_settings.read().unwrap().appearance.custom_colors.clone();
+                            set_app_theme(window, cx, &theme, &colors);
+                            cx.notify();
+                        })
+                        .ok();
+                });
+            }
+        })
+        .detach();
+}
+
+/// How often the whole history is checked for expired records
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Periodically remove expired records, whether they are loaded or not
+fn start_expiry_timer(async_app: AsyncApp, window_handle: WindowHandle<Root>) {
+    let fg_executor = async_app.foreground_executor().clone();
+    let bg_executor = async_app.background_executor().clone();
+
+    fg_executor
+        .spawn(async move {
+            loop {
+                bg_executor.timer(EXPIRY_CHECK_INTERVAL).await;
+                let _ = async_app.update(|cx| {
+                    window_handle
+                        .update(cx, |root, _, cx| {
+                            if let Ok(board) = root.view().clone().downcast::<RopyBoard>() {
+                                board.update(cx, |board, cx| board.remove_expired_records(cx));
+                            }
+                        })
+                        .ok();
+                });
+            }
+        })
+        .detach();
+}
+
 fn bind_application_keys(cx: &mut App) {
     cx.bind_keys([
         KeyBinding::new("escape", crate::gui::board::Hide, None),
@@ -326,20 +631,43 @@ fn bind_application_keys(cx: &mut App) {
         KeyBinding::new("up", crate::gui::board::SelectPrev, None),
         KeyBinding::new("down", crate::gui::board::SelectNext, None),
         KeyBinding::new("enter", crate::gui::board::ConfirmSelection, None),
+        KeyBinding::new("alt-enter", crate::gui::board::CopyWithMetadata, None),
+        KeyBinding::new("ctrl-enter", crate::gui::board::CopyWithoutHistory, None),
+        KeyBinding::new("delete", crate::gui::board::DeleteSelected, None),
+        KeyBinding::new("backspace", crate::gui::board::DeleteSelected, None),
+        KeyBinding::new("alt-b", crate::gui::board::ToggleBurnAfterPaste, None),
+        KeyBinding::new("alt-e", crate::gui::board::ToggleExpiry, None),
+        KeyBinding::new("alt-o", crate::gui::board::OpenInEditor, None),
+        KeyBinding::new("alt-right", crate::gui::board::NextContentFilter, None),
+        KeyBinding::new("alt-left", crate::gui::board::PrevContentFilter, None),
+        KeyBinding::new("alt-f", crate::gui::board::ToggleFavoritesOnly, None),
+        KeyBinding::new("alt-g", crate::gui::board::ToggleGallery, None),
+        KeyBinding::new("alt-up", crate::gui::board::MoveFavoriteUp, None),
+        KeyBinding::new("alt-down", crate::gui::board::MoveFavoriteDown, None),
+        KeyBinding::new("alt-a", crate::gui::board::ToggleAppendMode, None),
+        KeyBinding::new("ctrl-k", crate::gui::board::ToggleCommandPalette, None),
+        KeyBinding::new("ctrl-s", crate::gui::board::ExportSelected, None),
+        KeyBinding::new("alt-t", crate::gui::board::PasteTrimmed, None),
+        KeyBinding::new("alt-d", crate::gui::board::PasteDedented, None),
+        KeyBinding::new("alt-l", crate::gui::board::PasteLowercase, None),
+        KeyBinding::new("f5", crate::gui::board::RefreshRecords, None),
+        KeyBinding::new("alt-p", crate::gui::board::PromoteToTop, None),
+        KeyBinding::new("alt-i", crate::gui::board::TogglePrivacyMode, None),
+        KeyBinding::new("alt-shift-p", crate::gui::board::NextProfile, None),
     ]);
 }
 
 fn load_settings() -> Arc<RwLock<Settings>> {
     match Settings::load() {
         Ok(s) => {
-            println!("[ropy] Settings loaded successfully");
+            log::info!("Settings loaded successfully");
             Arc::new(RwLock::new(s))
         }
         Err(e) => {
-            eprintln!("[ropy] Failed to load settings, using defaults: {e}");
+            log::warn!("Failed to load settings, using defaults: {e}");
             let default_settings = Settings::default();
             default_settings.save().unwrap_or_else(|err| {
-                eprintln!("[ropy] Failed to save default settings: {err}");
+                log::error!("Failed to save default settings: {err}");
             });
             Arc::new(RwLock::new(default_settings))
         }
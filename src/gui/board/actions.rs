@@ -1,55 +1,609 @@
-use crate::gui::board::RopyBoard;
-use crate::gui::{active_window, hide_window};
-use gpui::{Context, Focusable, Window};
+use crate::clipboard::{CopyRequest, LastCopyState, Transform, apply_transform};
+use crate::gui::active_window;
+use crate::gui::board::render::{accessible_label, format_record_with_metadata};
+use crate::gui::board::{APP_TITLE, RopyBoard, ToastLevel};
+use crate::gui::utils::board_window_size;
+use crate::repository::ClipboardRecord;
+use crate::repository::models::{ContentType, next_content_filter};
+use gpui::{Context, Focusable, PromptLevel, Window};
 
 gpui::actions!(
     board,
-    [Hide, Quit, Active, SelectPrev, SelectNext, ConfirmSelection]
+    [
+        Hide,
+        Quit,
+        Active,
+        SelectPrev,
+        SelectNext,
+        ConfirmSelection,
+        CopyWithMetadata,
+        CopyWithoutHistory,
+        DeleteSelected,
+        ClearClipboard,
+        ToggleBurnAfterPaste,
+        ToggleExpiry,
+        OpenInEditor,
+        NextContentFilter,
+        PrevContentFilter,
+        ToggleFavoritesOnly,
+        MoveFavoriteUp,
+        MoveFavoriteDown,
+        ToggleAppendMode,
+        ToggleCommandPalette,
+        ExportSelected,
+        PasteTrimmed,
+        PasteDedented,
+        PasteLowercase,
+        RefreshRecords,
+        PromoteToTop,
+        TogglePrivacyMode,
+        NextProfile,
+        ToggleGallery,
+        ToggleFavorite,
+        ToggleStickyClipboard,
+        CompareRecords
+    ]
 );
 
+/// Number of leading records that can be copied with the number keys
+pub(super) const QUICK_PASTE_COUNT: usize = 5;
+
+/// Record index selected by a number key, if it is within the quick-paste range
+fn quick_paste_index(key: &str) -> Option<usize> {
+    let number: usize = key.parse().ok()?;
+    (1..=QUICK_PASTE_COUNT)
+        .contains(&number)
+        .then(|| number - 1)
+}
+
+/// Whether copying record `id` moves it to the top, because it isn't the newest record.
+///
+/// Checked against the whole history rather than the rows on screen: with a search or
+/// filter active, the first row shown is not necessarily the newest record.
+pub(super) fn moves_to_top_on_copy(history: &[ClipboardRecord], id: u64) -> bool {
+    history.first().is_none_or(|newest| newest.id != id)
+}
+
+/// Send `content` to the clipboard writer exactly as stored.
+///
+/// Color records are copied as their original text, `#` and case included, not as the
+/// swatch's parsed color.
+pub(super) fn send_copy(
+    copy_tx: &async_channel::Sender<CopyRequest>,
+    content: &str,
+    content_type: &ContentType,
+) {
+    let request = match content_type {
+        // SVG records go back as their original markup
+        ContentType::Text | ContentType::Svg => CopyRequest::Text(content.to_string()),
+        ContentType::Image => CopyRequest::Image(content.to_string()),
+        _ => return,
+    };
+    let _ = copy_tx.send_blocking(request);
+}
+
 impl RopyBoard {
-    pub fn on_select_prev(&mut self, _: &SelectPrev, _: &mut Window, cx: &mut Context<Self>) {
+    pub fn on_select_prev(&mut self, _: &SelectPrev, window: &mut Window, cx: &mut Context<Self>) {
         if self.selected_index > 0 {
             self.selected_index -= 1;
+            self.pending_delete = None;
             self.list_state.scroll_to_reveal_item(self.selected_index);
+            self.reveal_selected_in_gallery();
+            self.refresh_preview(window, cx);
+            self.announce_selection(window);
             cx.notify();
         }
     }
 
-    pub fn on_select_next(&mut self, _: &SelectNext, _: &mut Window, cx: &mut Context<Self>) {
+    pub fn on_select_next(&mut self, _: &SelectNext, window: &mut Window, cx: &mut Context<Self>) {
+        if self.selected_index + 1 >= self.filtered_records.len() {
+            self.load_more_records(cx);
+        }
         let count = self.filtered_records.len();
         if count > 0 && self.selected_index < count - 1 {
             self.selected_index += 1;
+            self.pending_delete = None;
             self.list_state.scroll_to_reveal_item(self.selected_index);
+            self.reveal_selected_in_gallery();
+            self.refresh_preview(window, cx);
+            self.announce_selection(window);
             cx.notify();
         }
     }
 
+    /// Expose the selected record to screen readers through the window title
+    ///
+    /// GPUI has no accessibility tree for elements, but the title is read by assistive
+    /// technology on every platform.
+    pub(super) fn announce_selection(&self, window: &mut Window) {
+        let title = match self.filtered_records.get(self.selected_index) {
+            Some(record) => format!(
+                "{APP_TITLE} – {}",
+                accessible_label(
+                    record,
+                    self.selected_index + 1,
+                    self.filtered_records.len(),
+                    &self.i18n
+                )
+            ),
+            None => APP_TITLE.to_string(),
+        };
+        window.set_window_title(&title);
+    }
+
     pub fn on_confirm_selection(
         &mut self,
         _: &ConfirmSelection,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.submit_search(window, cx) {
+            return;
+        }
         self.confirm_record(window, cx, self.selected_index);
     }
 
+    pub fn on_copy_with_metadata(
+        &mut self,
+        _: &CopyWithMetadata,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let text = format_record_with_metadata(record, &self.i18n);
+        let _ = self.copy_tx.send_blocking(CopyRequest::Text(text));
+        if self.pinned {
+            self.show_toast(self.i18n.t("message_copied"), ToastLevel::Info);
+            cx.notify();
+        } else {
+            self.hide(window, cx);
+        }
+    }
+
+    pub fn on_copy_without_history(
+        &mut self,
+        _: &CopyWithoutHistory,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let (content, content_type) = (record.content.clone(), record.content_type.clone());
+        self.record_use(record);
+        // Unlike confirming, the record stays where it is and no new record is captured
+        *self.last_copy.lock().unwrap_or_else(|e| e.into_inner()) = LastCopyState::IgnoreNext;
+        self.copy_to_clipboard(&content, &content_type);
+        if self.pinned {
+            self.show_toast(self.i18n.t("message_copied"), ToastLevel::Info);
+            cx.notify();
+        } else {
+            self.hide(window, cx);
+        }
+    }
+
+    pub fn on_paste_trimmed(
+        &mut self,
+        _: &PasteTrimmed,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.paste_transformed(Transform::Trim, window, cx);
+    }
+
+    pub fn on_paste_dedented(
+        &mut self,
+        _: &PasteDedented,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.paste_transformed(Transform::Dedent, window, cx);
+    }
+
+    pub fn on_paste_lowercase(
+        &mut self,
+        _: &PasteLowercase,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.paste_transformed(Transform::Lowercase, window, cx);
+    }
+
+    /// Copy the selected text record with `transform` applied, leaving the record unchanged
+    fn paste_transformed(
+        &mut self,
+        transform: Transform,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        if record.content_type != ContentType::Text {
+            return;
+        }
+        let content = apply_transform(&record.content, transform);
+        self.record_use(record);
+        // The transformed text is a one-off, so it isn't captured as a new record
+        *self.last_copy.lock().unwrap_or_else(|e| e.into_inner()) = LastCopyState::IgnoreNext;
+        self.copy_to_clipboard(&content, &ContentType::Text);
+        if self.pinned {
+            self.show_toast(self.i18n.t("message_copied"), ToastLevel::Info);
+            cx.notify();
+        } else {
+            self.hide(window, cx);
+        }
+    }
+
+    pub fn on_delete_selected(
+        &mut self,
+        _: &DeleteSelected,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Let the search input handle its own deletion
+        if let Some(focused_handle) = window.focused(cx)
+            && focused_handle == self.search_input.focus_handle(cx)
+        {
+            cx.propagate();
+            return;
+        }
+
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let id = record.id;
+
+        // Images are the costliest to lose, so require a second press to confirm
+        if record.content_type == ContentType::Image && self.pending_delete != Some(id) {
+            self.pending_delete = Some(id);
+            cx.notify();
+            return;
+        }
+
+        self.pending_delete = None;
+        self.trash_record(id);
+        self.filtered_records.remove(self.selected_index);
+        self.list_state.reset(self.filtered_records.len());
+        // Keep the same position so the next record becomes selected
+        if self.selected_index >= self.filtered_records.len() {
+            self.selected_index = self.filtered_records.len().saturating_sub(1);
+        }
+        self.list_state.scroll_to_reveal_item(self.selected_index);
+        cx.notify();
+    }
+
+    pub fn on_clear_clipboard(
+        &mut self,
+        _: &ClearClipboard,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Suppress the resulting clipboard change so no empty record is captured
+        self.clear_last_copy_state();
+        let _ = self.copy_tx.send_blocking(CopyRequest::Clear);
+
+        if self.settings.read().unwrap().hotkey.clear_removes_latest {
+            let latest_id = self.records.lock().unwrap().first().map(|record| record.id);
+            if let Some(id) = latest_id {
+                self.delete_record(id);
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn on_toggle_burn_after_paste(
+        &mut self,
+        _: &ToggleBurnAfterPaste,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // Image files may still be read by the writer after pasting, so only text can burn
+        let Some(record) = self
+            .filtered_records
+            .get(self.selected_index)
+            .filter(|record| record.content_type == ContentType::Text)
+        else {
+            return;
+        };
+        let id = record.id;
+        self.update_record(id, |record| {
+            record.delete_after_paste = !record.delete_after_paste;
+        });
+        cx.notify();
+    }
+
+    pub fn on_toggle_expiry(
+        &mut self,
+        _: &ToggleExpiry,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let id = record.id;
+        let expiry_mins = self.settings.read().unwrap().behavior.expiry_mins;
+        self.update_record(id, |record| {
+            record.expires_at = match record.expires_at {
+                Some(_) => None,
+                None => Some(chrono::Local::now() + chrono::Duration::minutes(expiry_mins.into())),
+            };
+        });
+        cx.notify();
+    }
+
+    pub fn on_open_in_editor(
+        &mut self,
+        _: &OpenInEditor,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.open_in_editor(self.selected_index);
+        cx.notify();
+    }
+
+    pub fn on_export_selected(
+        &mut self,
+        _: &ExportSelected,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.export_record(self.selected_index, window, cx);
+    }
+
+    pub fn on_refresh_records(
+        &mut self,
+        _: &RefreshRecords,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.refresh_records(cx);
+        self.show_toast(self.i18n.t("toast_records_refreshed"), ToastLevel::Info);
+    }
+
+    pub fn on_promote_to_top(
+        &mut self,
+        _: &PromoteToTop,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(record) = self.filtered_records.get(self.selected_index) {
+            self.promote_to_top(record.id, cx);
+        }
+    }
+
+    pub fn on_toggle_favorite(
+        &mut self,
+        _: &ToggleFavorite,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(record) = self.filtered_records.get(self.selected_index) {
+            self.toggle_favorite(record.id);
+            cx.notify();
+        }
+    }
+
+    /// Keep the selected record on the OS clipboard, or stop keeping the pinned one there
+    pub fn on_toggle_sticky_clipboard(
+        &mut self,
+        _: &ToggleStickyClipboard,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let message = if self.sticky.pinned_id() == Some(record.id) {
+            self.sticky.unpin();
+            self.i18n.t("toast_sticky_off")
+        } else {
+            self.sticky.pin(record);
+            self.i18n.t("toast_sticky_on")
+        };
+        self.show_toast(message, ToastLevel::Info);
+        cx.notify();
+    }
+
+    pub fn on_next_content_filter(
+        &mut self,
+        _: &NextContentFilter,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.content_filter = next_content_filter(self.content_filter, true);
+        self.selected_index = 0;
+        cx.notify();
+    }
+
+    pub fn on_prev_content_filter(
+        &mut self,
+        _: &PrevContentFilter,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.content_filter = next_content_filter(self.content_filter, false);
+        self.selected_index = 0;
+        cx.notify();
+    }
+
+    pub fn on_toggle_favorites_only(
+        &mut self,
+        _: &ToggleFavoritesOnly,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.favorites_only = !self.favorites_only;
+        self.selected_index = 0;
+        cx.notify();
+    }
+
+    pub fn on_toggle_gallery(
+        &mut self,
+        _: &ToggleGallery,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_gallery(cx);
+    }
+
+    pub fn on_toggle_append_mode(
+        &mut self,
+        _: &ToggleAppendMode,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_append_mode(cx);
+    }
+
+    pub fn on_toggle_privacy_mode(
+        &mut self,
+        _: &TogglePrivacyMode,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.toggle_privacy_mode(cx);
+    }
+
+    pub fn on_next_profile(
+        &mut self,
+        _: &NextProfile,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.cycle_profile(window, cx);
+    }
+
+    pub fn on_toggle_command_palette(
+        &mut self,
+        _: &ToggleCommandPalette,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.show_palette {
+            self.close_palette(window, cx);
+        } else {
+            self.open_palette(window, cx);
+        }
+    }
+
+    pub fn on_move_favorite_up(
+        &mut self,
+        _: &MoveFavoriteUp,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_selected_favorite(false, cx);
+    }
+
+    pub fn on_move_favorite_down(
+        &mut self,
+        _: &MoveFavoriteDown,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_selected_favorite(true, cx);
+    }
+
+    /// Swap the selected favorite with its neighbour in the favorites-only list
+    fn move_selected_favorite(&mut self, down: bool, cx: &mut Context<Self>) {
+        // Favorites are only shown in their manual order in the favorites-only list, and
+        // search results are ranked instead, as for the drag handles
+        if !self.favorites_only || !self.active_query(cx).is_empty() {
+            return;
+        }
+        let target_index = if down {
+            self.selected_index + 1
+        } else if let Some(index) = self.selected_index.checked_sub(1) {
+            index
+        } else {
+            return;
+        };
+        let (Some(selected), Some(target)) = (
+            self.filtered_records.get(self.selected_index),
+            self.filtered_records.get(target_index),
+        ) else {
+            return;
+        };
+        let (id, target_id) = (selected.id, target.id);
+        self.move_favorite_to(id, target_id);
+        self.selected_index = target_index;
+        self.list_state.scroll_to_reveal_item(self.selected_index);
+        cx.notify();
+    }
+
     pub fn on_active_action(&mut self, _: &Active, window: &mut Window, cx: &mut Context<Self>) {
         self.selected_index = 0;
+        self.pending_delete = None;
+        self.last_activity = std::time::Instant::now();
         self.show_preview = false;
+        self.close_preview(cx);
         self.list_state.scroll_to_reveal_item(self.selected_index);
         self.show_settings = false;
-        window.resize(gpui::size(gpui::px(400.), gpui::px(600.)));
+        self.show_trash = false;
+        self.show_help = false;
+        self.show_palette = false;
+        self.editing_note = None;
+        self.editing_tags = None;
+        self.diff_lines = None;
+        // Resizing keeps the window's position, so an anchored window stays in its corner
+        window.resize(board_window_size());
+        self.announce_selection(window);
         active_window(window, cx);
+        // Apply any clipboard captures that arrived while hidden
+        if self.refresh_gate.set_visible() {
+            cx.notify();
+        }
     }
 
     pub fn on_hide_action(&mut self, _: &Hide, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_palette {
+            self.close_palette(window, cx);
+            return;
+        }
+        if self.editing_note.is_some() {
+            self.close_note_editor(window, cx);
+            return;
+        }
+        if self.editing_tags.is_some() {
+            self.close_tag_editor(window, cx);
+            return;
+        }
+        if self.diff_lines.is_some() {
+            self.close_diff_view(cx);
+            return;
+        }
+        // Leave the trash and help views before hiding
+        if self.show_trash || self.show_help {
+            self.show_trash = false;
+            self.show_help = false;
+            window.focus(&self.focus_handle);
+            cx.notify();
+            return;
+        }
+
         // If still in settings, exit settings view and refocus main board instead of hiding
         if self.show_settings {
             self.show_settings = false;
             self.settings_max_history_input.update(cx, |input, cx| {
                 input.set_value("", window, cx);
             });
+            self.settings_min_text_chars_input.update(cx, |input, cx| {
+                input.set_value("", window, cx);
+            });
+            let ignored_formats = self
+                .settings
+                .read()
+                .unwrap()
+                .storage
+                .ignored_formats
+                .join(", ");
+            self.settings_ignored_formats_input.update(cx, |input, cx| {
+                input.set_value(ignored_formats, window, cx);
+            });
             self.settings_activation_key_input.update(cx, |input, cx| {
                 input.set_value("", window, cx);
             });
@@ -65,12 +619,34 @@ impl RopyBoard {
             window.focus(&self.focus_handle);
             return;
         }
-        hide_window(window, cx);
-        self.pinned = false;
+        self.hide(window, cx);
+        if !self.settings.read().unwrap().behavior.always_pinned {
+            self.pinned = false;
+        }
     }
 
-    pub fn on_quit_action(&mut self, _: &Quit, _window: &mut Window, cx: &mut Context<Self>) {
-        cx.quit();
+    pub fn on_quit_action(&mut self, _: &Quit, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.settings.read().unwrap().behavior.confirm_quit {
+            cx.quit();
+            return;
+        }
+        let message = self.i18n.t("quit_confirm_prompt");
+        let detail = self.i18n.t("quit_confirm_detail");
+        let quit_label = self.i18n.t("quit_confirm");
+        let cancel_label = self.i18n.t("settings_cancel");
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some(detail.as_str()),
+            &[quit_label.as_str(), cancel_label.as_str()],
+            cx,
+        );
+        cx.spawn(async move |_, cx| {
+            if answer.await == Ok(0) {
+                cx.update(|cx| cx.quit()).ok();
+            }
+        })
+        .detach();
     }
 
     pub fn on_key_down(
@@ -79,36 +655,129 @@ impl RopyBoard {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let search_focused = window
+            .focused(cx)
+            .is_some_and(|handle| handle == self.search_input.focus_handle(cx));
+
+        // "?" arrives as shift-/ on some platforms
+        let is_question_mark = event.keystroke.key == "?"
+            || (event.keystroke.key == "/" && event.keystroke.modifiers.shift);
+        if is_question_mark && !search_focused {
+            self.show_help = !self.show_help;
+            cx.notify();
+            return;
+        }
+
         // If the "/" key is pressed, focus the search input
         if event.keystroke.key.as_str() == "/" {
             window.focus(&self.search_input.focus_handle(cx));
             return;
         }
 
-        // If the search input is focused, ignore key presses
-        if let Some(focused_handle) = window.focused(cx)
-            && focused_handle == self.search_input.focus_handle(cx)
+        // If the search input, the command palette or a record editor is focused, ignore key presses
+        if search_focused
+            || self.show_palette
+            || self.editing_note.is_some()
+            || self.editing_tags.is_some()
+            || self.diff_lines.is_some()
         {
             return;
         }
 
+        // Bare "f" favorites the selected record; not a key binding, which would also fire
+        // while typing in the search input
+        if event.keystroke.key.as_str() == "f" && !event.keystroke.modifiers.modified() {
+            self.on_toggle_favorite(&ToggleFavorite, window, cx);
+            return;
+        }
+
+        // Bare "t" edits the selected record's tags
+        if event.keystroke.key.as_str() == "t" && !event.keystroke.modifiers.modified() {
+            self.open_tag_editor(window, cx);
+            return;
+        }
+
         // If the space key is pressed, toggle preview
         if event.keystroke.key.as_str() == "space" {
             self.show_preview = !self.show_preview;
+            if self.show_preview {
+                self.open_preview(window, cx);
+            } else {
+                self.close_preview(cx);
+            }
             cx.notify();
             return;
         }
 
         // Map number keys to record selection
-        let key = &event.keystroke.key;
-        let index = match key.as_str() {
-            "1" => 0,
-            "2" => 1,
-            "3" => 2,
-            "4" => 3,
-            "5" => 4,
-            _ => return,
-        };
-        self.confirm_record(window, cx, index);
+        if let Some(index) = quick_paste_index(&event.keystroke.key) {
+            self.confirm_record(window, cx, index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::board::query::{ListFilters, displayed_records};
+    use crate::repository::{ClipboardStore, MemStore};
+
+    #[test]
+    fn test_quick_paste_index() {
+        assert_eq!(quick_paste_index("1"), Some(0));
+        assert_eq!(quick_paste_index("5"), Some(4));
+        assert_eq!(quick_paste_index("0"), None);
+        assert_eq!(quick_paste_index("6"), None);
+        assert_eq!(quick_paste_index("a"), None);
+    }
+
+    #[test]
+    fn test_quick_paste_with_active_query() {
+        let repo = MemStore::new();
+        for content in ["banana", "cherry tart", "banana split", "apple pie"] {
+            repo.save(content.to_string(), ContentType::Text).unwrap();
+        }
+        let history = repo.get_recent(usize::MAX).unwrap();
+        let filters = ListFilters::default();
+        let matching = repo.search_with_scope("banana", filters.scope).unwrap();
+        let displayed = displayed_records(matching, "banana", filters);
+
+        // Number keys pick the rows as displayed, ranked, not as stored
+        let first = &displayed[quick_paste_index("1").unwrap()];
+        let second = &displayed[quick_paste_index("2").unwrap()];
+        assert_eq!(first.content, "banana");
+        assert_eq!(second.content, "banana split");
+        assert!(displayed.get(quick_paste_index("3").unwrap()).is_none());
+
+        // The first displayed row is not the newest record, so it is moved to the top
+        assert!(moves_to_top_on_copy(&history, first.id));
+        assert!(moves_to_top_on_copy(&history, second.id));
+    }
+
+    #[test]
+    fn test_quick_paste_without_query() {
+        let history = vec![
+            ClipboardRecord::test_record(2, "newest"),
+            ClipboardRecord::test_record(1, "older"),
+        ];
+        assert!(!moves_to_top_on_copy(
+            &history,
+            history[quick_paste_index("1").unwrap()].id
+        ));
+        assert!(moves_to_top_on_copy(
+            &history,
+            history[quick_paste_index("2").unwrap()].id
+        ));
+        assert!(moves_to_top_on_copy(&[], 1));
+    }
+
+    #[test]
+    fn test_color_record_copied_as_stored() {
+        let (tx, rx) = async_channel::unbounded();
+        for content in ["#FF8800", "#f80", "#aBcDeF"] {
+            let color = ClipboardRecord::test_record(1, content);
+            send_copy(&tx, &color.content, &color.content_type);
+            assert!(matches!(rx.try_recv(), Ok(CopyRequest::Text(text)) if text == content));
+        }
     }
 }
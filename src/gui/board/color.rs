@@ -0,0 +1,180 @@
+//! Conversions between the color syntaxes offered for color records.
+
+use crate::config::parse_hex_color;
+
+/// An opaque sRGB color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Text formats a color can be copied as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+}
+
+impl ColorFormat {
+    pub const ALL: [ColorFormat; 3] = [ColorFormat::Hex, ColorFormat::Rgb, ColorFormat::Hsl];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorFormat::Hex => "HEX",
+            ColorFormat::Rgb => "RGB",
+            ColorFormat::Hsl => "HSL",
+        }
+    }
+}
+
+impl Rgb {
+    /// Parse `#RGB` or `#RRGGBB`
+    pub fn from_hex(content: &str) -> Option<Self> {
+        let value = parse_hex_color(content)?;
+        Some(Self {
+            r: (value >> 16) as u8,
+            g: (value >> 8) as u8,
+            b: value as u8,
+        })
+    }
+
+    /// Packed `0xRRGGBB` value
+    pub fn to_u32(self) -> u32 {
+        (u32::from(self.r) << 16) | (u32::from(self.g) << 8) | u32::from(self.b)
+    }
+
+    /// Hue in degrees, saturation and lightness in percent
+    pub fn to_hsl(self) -> (f64, f64, f64) {
+        let r = f64::from(self.r) / 255.0;
+        let g = f64::from(self.g) / 255.0;
+        let b = f64::from(self.b) / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        let delta = max - min;
+        if delta == 0.0 {
+            return (0.0, 0.0, lightness * 100.0);
+        }
+
+        let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+        let hue = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        (hue, saturation * 100.0, lightness * 100.0)
+    }
+
+    /// Color from hue in degrees, saturation and lightness in percent
+    pub fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Self {
+        let s = (saturation / 100.0).clamp(0.0, 1.0);
+        let l = (lightness / 100.0).clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let h = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (h.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match h as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let m = l - chroma / 2.0;
+        let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+        Self {
+            r: channel(r),
+            g: channel(g),
+            b: channel(b),
+        }
+    }
+
+    /// Text copied for a color record stored as `stored`: HEX is the record exactly as
+    /// stored, the same string confirming the row copies
+    pub fn copy_text(self, stored: &str, format: ColorFormat) -> String {
+        match format {
+            ColorFormat::Hex => stored.to_string(),
+            _ => self.format(format),
+        }
+    }
+
+    /// The color written in the given syntax, e.g. `rgb(255, 136, 0)`
+    pub fn format(self, format: ColorFormat) -> String {
+        match format {
+            ColorFormat::Hex => format!("#{:06x}", self.to_u32()),
+            ColorFormat::Rgb => format!("rgb({}, {}, {})", self.r, self.g, self.b),
+            ColorFormat::Hsl => {
+                let (h, s, l) = self.to_hsl();
+                format!("hsl({h:.0}, {s:.0}%, {l:.0}%)")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_text_keeps_stored_hex() {
+        let rgb = Rgb::from_hex("#FFF").unwrap();
+        assert_eq!(rgb.copy_text("#FFF", ColorFormat::Hex), "#FFF");
+        assert_eq!(rgb.format(ColorFormat::Hex), "#ffffff");
+        assert_eq!(
+            rgb.copy_text("#FFF", ColorFormat::Rgb),
+            "rgb(255, 255, 255)"
+        );
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(
+            Rgb::from_hex("#ff8800"),
+            Some(Rgb {
+                r: 255,
+                g: 136,
+                b: 0
+            })
+        );
+        assert_eq!(Rgb::from_hex("#f80"), Rgb::from_hex("#FF8800"));
+        assert_eq!(Rgb::from_hex("ff8800"), None);
+        assert_eq!(Rgb::from_hex("#ff880"), None);
+        assert_eq!(Rgb::from_hex("#gg8800"), None);
+        assert_eq!(Rgb::from_hex("#ff8800").unwrap().to_u32(), 0xff8800);
+    }
+
+    #[test]
+    fn test_format() {
+        let orange = Rgb::from_hex("#ff8800").unwrap();
+        assert_eq!(orange.format(ColorFormat::Hex), "#ff8800");
+        assert_eq!(orange.format(ColorFormat::Rgb), "rgb(255, 136, 0)");
+        assert_eq!(orange.format(ColorFormat::Hsl), "hsl(32, 100%, 50%)");
+
+        let gray = Rgb::from_hex("#808080").unwrap();
+        assert_eq!(gray.format(ColorFormat::Hsl), "hsl(0, 0%, 50%)");
+        assert_eq!(
+            Rgb::from_hex("#3366cc").unwrap().format(ColorFormat::Hsl),
+            "hsl(220, 60%, 50%)"
+        );
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for hex in [
+            "#000000", "#ffffff", "#ff0000", "#00ff00", "#0000ff", "#3366cc", "#c0ffee",
+        ] {
+            let color = Rgb::from_hex(hex).unwrap();
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(Rgb::from_hsl(h, s, l), color, "{hex}");
+        }
+        assert_eq!(
+            Rgb::from_hsl(360.0, 100.0, 50.0),
+            Rgb::from_hex("#ff0000").unwrap()
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Opening the config file for hand edits, and reloading it without a restart
+
+use gpui::{Context, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::config::Settings;
+use crate::gui::utils::{open_in_default_editor, reveal_in_file_manager};
+
+impl RopyBoard {
+    /// Open `config.toml` in the default editor, writing it out first if it doesn't exist yet
+    pub(super) fn open_config_file(&mut self, cx: &mut Context<Self>) {
+        let path = match Settings::config_file() {
+            Ok(path) => path,
+            Err(e) => {
+                log::error!("Failed to locate the config file: {e}");
+                self.show_toast(self.i18n.t("toast_config_open_failed"), ToastLevel::Error);
+                cx.notify();
+                return;
+            }
+        };
+        if !path.exists()
+            && let Err(e) = self.settings.read().unwrap().save()
+        {
+            log::error!("Failed to save settings: {e}");
+        }
+        if let Err(e) = open_in_default_editor(&path) {
+            log::warn!("Failed to open {} in an editor: {e}", path.display());
+            // Without an editor, at least show where the file is
+            if let Err(e) = reveal_in_file_manager(&path) {
+                log::error!("Failed to reveal {}: {e}", path.display());
+                self.show_toast(self.i18n.t("toast_config_open_failed"), ToastLevel::Error);
+                cx.notify();
+            }
+        }
+    }
+
+    /// Read `config.toml` again and apply it, keeping the current settings if it doesn't parse
+    pub(super) fn reload_config(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        match Settings::load() {
+            Ok(settings) => {
+                *self.settings.write().unwrap() = settings;
+                self.apply_loaded_settings(window, cx);
+                self.show_toast(self.i18n.t("toast_config_reloaded"), ToastLevel::Info);
+            }
+            Err(e) => {
+                log::error!("Failed to reload settings: {e}");
+                let message = self
+                    .i18n
+                    .t("toast_config_reload_failed")
+                    .replace("{error}", &e.to_string());
+                self.show_toast(message, ToastLevel::Error);
+            }
+        }
+        cx.notify();
+    }
+}
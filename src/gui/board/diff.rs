@@ -0,0 +1,231 @@
+//! Line diff between two text records, shown over the board
+
+use gpui::{
+    Context, Window, div,
+    prelude::{InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement, Styled},
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
+
+use super::actions::CompareRecords;
+use super::{RopyBoard, ToastLevel};
+use crate::repository::models::{ClipboardRecord, ContentType};
+
+/// Largest table the LCS diff fills in; bigger inputs are shown as fully replaced
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// One line of a diff
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Line diff turning `old` into `new`, from their longest common subsequence of lines
+pub(super) fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if (old.len() + 1) * (new.len() + 1) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string()))
+            .chain(new.iter().map(|line| DiffLine::Added(line.to_string())))
+            .collect();
+    }
+
+    // lcs[i][j] is the length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    diff.extend(
+        old[i..]
+            .iter()
+            .map(|line| DiffLine::Removed(line.to_string())),
+    );
+    diff.extend(
+        new[j..]
+            .iter()
+            .map(|line| DiffLine::Added(line.to_string())),
+    );
+    diff
+}
+
+impl RopyBoard {
+    /// Mark the selected text record for comparison, or compare it with the marked one
+    pub fn on_compare_records(
+        &mut self,
+        _: &CompareRecords,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(self.selected_index).cloned() else {
+            return;
+        };
+        if record.content_type != ContentType::Text {
+            self.show_toast(self.i18n.t("toast_compare_text_only"), ToastLevel::Info);
+            cx.notify();
+            return;
+        }
+        match self.compare_base.take() {
+            Some(base) if base.id != record.id => self.show_diff(base, record, cx),
+            Some(_) => {
+                self.show_toast(self.i18n.t("toast_compare_cancelled"), ToastLevel::Info);
+            }
+            None => {
+                self.compare_base = Some(record);
+                self.show_toast(self.i18n.t("toast_compare_marked"), ToastLevel::Info);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Diff two records in the background and show the result over the board
+    fn show_diff(&mut self, a: ClipboardRecord, b: ClipboardRecord, cx: &mut Context<Self>) {
+        // Older record first, so additions are what changed since
+        let (old, new) = if a.created_at <= b.created_at {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        // Long texts take a while to diff
+        let task = cx.background_spawn(async move { line_diff(&old.content, &new.content) });
+        cx.spawn(async move |this, cx| {
+            let lines = task.await;
+            this.update(cx, |board, cx| {
+                board.diff_lines = Some(lines);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    pub(super) fn close_diff_view(&mut self, cx: &mut Context<Self>) {
+        self.diff_lines = None;
+        cx.notify();
+    }
+}
+
+/// Render the diff over the board while two records are being compared
+pub(super) fn render_diff_view(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    let lines = board.diff_lines.as_ref()?;
+    let rows: Vec<_> = lines
+        .iter()
+        .map(|line| {
+            let (marker, text, color) = match line {
+                DiffLine::Same(text) => (" ", text, cx.theme().muted_foreground),
+                DiffLine::Added(text) => ("+", text, cx.theme().success),
+                DiffLine::Removed(text) => ("-", text, cx.theme().danger),
+            };
+            h_flex()
+                .gap_2()
+                .text_xs()
+                .font_family("monospace")
+                .text_color(color)
+                .child(div().flex_shrink_0().child(marker))
+                .child(div().child(text.clone()))
+        })
+        .collect();
+
+    Some(
+        v_flex()
+            .absolute()
+            .top(gpui::px(48.))
+            .left_4()
+            .right_4()
+            .bottom_4()
+            .p_2()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .shadow_lg()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(board.i18n.t("diff_hint")),
+                    )
+                    .child(
+                        Button::new("close-diff")
+                            .xsmall()
+                            .ghost()
+                            .label("×")
+                            .on_click(cx.listener(|this, _, _, cx| this.close_diff_view(cx))),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("diff-lines")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(rows),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn same(line: &str) -> DiffLine {
+        DiffLine::Same(line.to_string())
+    }
+
+    fn added(line: &str) -> DiffLine {
+        DiffLine::Added(line.to_string())
+    }
+
+    fn removed(line: &str) -> DiffLine {
+        DiffLine::Removed(line.to_string())
+    }
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!(
+            line_diff("a\nb\nc", "a\nc\nd"),
+            vec![same("a"), removed("b"), same("c"), added("d")]
+        );
+        assert_eq!(line_diff("same", "same"), vec![same("same")]);
+        assert_eq!(line_diff("old", "new"), vec![removed("old"), added("new")]);
+    }
+
+    #[test]
+    fn test_line_diff_empty_side() {
+        assert_eq!(line_diff("", "a\nb"), vec![added("a"), added("b")]);
+        assert_eq!(line_diff("a", ""), vec![removed("a")]);
+        assert!(line_diff("", "").is_empty());
+    }
+}
@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use gpui::{Context, ExternalPaths, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::clipboard::{save_image, save_thumbnail};
+use crate::repository::errors::RepositoryError;
+use crate::repository::ids;
+use crate::repository::models::ContentType;
+use crate::repository::{ClipboardRecord, ClipboardStore};
+
+/// Dropped files, with images already copied into the images directory
+struct DroppedFiles {
+    /// Files kept as a path, including images that couldn't be decoded
+    paths: Vec<String>,
+    /// Copies of the dropped images
+    images: Vec<String>,
+}
+
+/// Copy dropped images into the images directory, keeping anything else as a file path
+fn copy_dropped_paths(paths: Vec<PathBuf>) -> DroppedFiles {
+    let (images, files): (Vec<PathBuf>, Vec<PathBuf>) = paths
+        .into_iter()
+        .partition(|path| image::ImageFormat::from_path(path).is_ok());
+
+    let mut dropped = DroppedFiles {
+        paths: files
+            .iter()
+            .map(|file| file.to_string_lossy().to_string())
+            .collect(),
+        images: Vec::new(),
+    };
+    // Undecodable images are still worth keeping as paths
+    for path in images {
+        match image::open(&path).ok().and_then(save_image) {
+            Some(saved) => dropped.images.push(saved),
+            None => dropped.paths.push(path.to_string_lossy().to_string()),
+        }
+    }
+    for path in &dropped.images {
+        if let Err(e) = save_thumbnail(Path::new(path)) {
+            log::warn!("Failed to save the thumbnail of {path}: {e}");
+        }
+    }
+    dropped
+}
+
+/// Save dropped files as records, returned in save order
+fn save_dropped_paths(
+    repo: &dyn ClipboardStore,
+    mut dropped: DroppedFiles,
+) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+    let mut records = Vec::new();
+    for path in dropped.paths {
+        records.push(repo.save(path, ContentType::FilePath)?);
+    }
+    match dropped.images.len() {
+        0 => {}
+        1 => records.push(repo.save_image_from_path(dropped.images.remove(0))?),
+        _ => records.extend(repo.save_image_group(dropped.images)?),
+    }
+    Ok(records)
+}
+
+/// Records for files dropped in privacy mode, kept in memory only and shaped like the
+/// ones [`save_dropped_paths`] saves
+fn unsaved_dropped_paths(dropped: DroppedFiles) -> Vec<ClipboardRecord> {
+    let count = dropped.paths.len() + dropped.images.len();
+    let first_id = ids::next_id(Local::now(), count as u64);
+    let group_id = (dropped.images.len() > 1).then_some(first_id + dropped.paths.len() as u64);
+    let paths = dropped
+        .paths
+        .into_iter()
+        .map(|path| (path, ContentType::FilePath));
+    let images = dropped
+        .images
+        .into_iter()
+        .map(|path| (path, ContentType::Image));
+    paths
+        .chain(images)
+        .enumerate()
+        .map(|(offset, (content, content_type))| ClipboardRecord {
+            group_id: group_id.filter(|_| content_type == ContentType::Image),
+            ..ClipboardRecord::new(first_id + offset as u64, content, content_type)
+        })
+        .collect()
+}
+
+impl RopyBoard {
+    /// Save files dropped onto the window as records
+    ///
+    /// In privacy mode they are only kept in memory, like captures.
+    pub(super) fn on_drop_paths(
+        &mut self,
+        paths: &ExternalPaths,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(repo) = self.repository.clone() else {
+            return;
+        };
+        let paths = paths.paths().to_vec();
+        if paths.is_empty() {
+            return;
+        }
+        let private = self.privacy_mode.is_active();
+        // Decoding and re-encoding images can take a while
+        let task = cx.background_spawn(async move {
+            let dropped = copy_dropped_paths(paths);
+            if private {
+                Ok(unsaved_dropped_paths(dropped))
+            } else {
+                save_dropped_paths(&repo, dropped)
+            }
+        });
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |board, cx| {
+                board.add_dropped_records(result, private);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn add_dropped_records(
+        &mut self,
+        result: Result<Vec<ClipboardRecord>, RepositoryError>,
+        private: bool,
+    ) {
+        let records = match result {
+            Ok(records) => records,
+            Err(e) => {
+                log::error!("Failed to save dropped files: {e}");
+                self.show_toast(self.i18n.t("toast_save_failed"), ToastLevel::Error);
+                return;
+            }
+        };
+        let (max_history_records, size_budget) = {
+            let storage = &self.settings.read().unwrap().storage;
+            (
+                storage.max_history_records,
+                storage
+                    .max_total_bytes
+                    .map(|max| (max, storage.evict_images_first)),
+            )
+        };
+        let mut guard = self.records.lock().unwrap();
+        for record in records {
+            if private {
+                let image_file =
+                    (record.content_type == ContentType::Image).then_some(record.content.as_str());
+                self.privacy_mode.remember(record.id, image_file);
+            }
+            guard.insert(0, record);
+        }
+        guard.truncate(max_history_records);
+        if private {
+            return;
+        }
+        // Checked against the database, since only the newest records may be loaded
+        if let Some(ref repo) = self.repository {
+            match repo.trim_history(max_history_records, size_budget) {
+                Ok(evicted) => guard.retain(|record| !evicted.contains(&record.id)),
+                Err(e) => log::error!("Failed to trim the history: {e}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::MemStore;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_dropped_paths() {
+        let dir = tempdir().unwrap();
+        let repo = MemStore::new();
+        let notes = dir.path().join("notes.txt");
+        let broken = dir.path().join("broken.png");
+        std::fs::write(&notes, "notes").unwrap();
+        std::fs::write(&broken, "not an image").unwrap();
+
+        let dropped = copy_dropped_paths(vec![notes.clone(), broken.clone()]);
+        let records = save_dropped_paths(&repo, dropped).unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(
+            records
+                .iter()
+                .all(|r| r.content_type == ContentType::FilePath)
+        );
+        assert_eq!(records[0].content, notes.to_string_lossy());
+        assert_eq!(records[1].content, broken.to_string_lossy());
+        assert_eq!(repo.count(), 2);
+    }
+
+    #[test]
+    fn test_unsaved_dropped_paths() {
+        let dropped = DroppedFiles {
+            paths: vec!["/tmp/notes.txt".to_string()],
+            images: vec!["a.png".to_string(), "b.png".to_string()],
+        };
+
+        let records = unsaved_dropped_paths(dropped);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].content_type, ContentType::FilePath);
+        assert_eq!(records[0].group_id, None);
+        assert_eq!(records[1].group_id, Some(records[1].id));
+        assert_eq!(records[2].group_id, Some(records[1].id));
+        assert!(records.windows(2).all(|pair| pair[0].id < pair[1].id));
+    }
+}
@@ -0,0 +1,171 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use gpui::Context;
+use tempfile::TempPath;
+
+use super::{RopyBoard, ToastLevel};
+use crate::gui::utils::open_in_default_editor;
+use crate::repository::models::ContentType;
+
+/// How long changes to a record opened in an external editor are picked up
+const EDIT_WATCH_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// A text record opened in an external editor, whose temp file is watched for changes
+///
+/// The temp file is deleted once the edit is dropped.
+pub(super) struct ExternalEdit {
+    record_id: u64,
+    path: TempPath,
+    modified: Option<SystemTime>,
+    started_at: Instant,
+}
+
+impl ExternalEdit {
+    fn is_stale(&self, now: Instant) -> bool {
+        now.duration_since(self.started_at) > EDIT_WATCH_DURATION
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Write `content` to a new temp file with an unpredictable name, readable only by the user
+fn write_temp_file(content: &str) -> io::Result<TempPath> {
+    let mut builder = tempfile::Builder::new();
+    builder.prefix("ropy-").suffix(".txt");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o600));
+    }
+    let mut file = builder.tempfile()?;
+    file.write_all(content.as_bytes())?;
+    // Closes the file, so editors that lock open files can still save to it
+    Ok(file.into_temp_path())
+}
+
+impl RopyBoard {
+    /// Write the text record at `index` to a temp file and open it in the default editor
+    pub(super) fn open_in_editor(&mut self, index: usize) {
+        let Some(record) = self.filtered_records.get(index) else {
+            return;
+        };
+        if record.content_type != ContentType::Text {
+            return;
+        }
+        let id = record.id;
+        // Open the file already being edited again rather than replacing it under the editor
+        if let Some(edit) = self.external_edits.iter().find(|edit| edit.record_id == id) {
+            if let Err(e) = open_in_default_editor(&edit.path) {
+                log::warn!("Failed to open external editor: {e}");
+                self.show_toast(self.i18n.t("toast_no_editor"), ToastLevel::Error);
+            }
+            return;
+        }
+        let path = match write_temp_file(&record.content) {
+            Ok(path) => path,
+            Err(e) => {
+                log::error!("Failed to write a temp file for the editor: {e}");
+                self.show_toast(self.i18n.t("toast_editor_failed"), ToastLevel::Error);
+                return;
+            }
+        };
+        if let Err(e) = open_in_default_editor(&path) {
+            log::warn!("Failed to open external editor: {e}");
+            self.show_toast(self.i18n.t("toast_no_editor"), ToastLevel::Error);
+            return;
+        }
+
+        self.external_edits.push(ExternalEdit {
+            record_id: id,
+            modified: modified_time(&path),
+            path,
+            started_at: Instant::now(),
+        });
+    }
+
+    /// Keep saving external edits of a record that moved to a new ID, e.g. when pasting it
+    /// moved it to the top
+    pub(super) fn follow_moved_record(&mut self, old_id: u64, new_id: u64) {
+        for edit in self
+            .external_edits
+            .iter_mut()
+            .filter(|edit| edit.record_id == old_id)
+        {
+            edit.record_id = new_id;
+        }
+    }
+
+    /// Save changes made in external editors back to their records
+    pub(super) fn sync_external_edits(&mut self, cx: &mut Context<Self>) {
+        if self.external_edits.is_empty() {
+            return;
+        }
+        let Some(repo) = self.repository.clone() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut changed = false;
+        let mut failed = false;
+        let mut saved_as_new = false;
+        self.external_edits.retain_mut(|edit| {
+            if edit.is_stale(now) {
+                return false;
+            }
+            let modified = modified_time(&edit.path);
+            if modified.is_none() {
+                return false;
+            }
+            if modified == edit.modified {
+                return true;
+            }
+            edit.modified = modified;
+            let Ok(content) = fs::read_to_string(&edit.path) else {
+                return true;
+            };
+            match repo.update_content(edit.record_id, content.clone()) {
+                Ok(Some(record)) => {
+                    let mut guard = self.records.lock().unwrap();
+                    if let Some(existing) = guard.iter_mut().find(|r| r.id == record.id) {
+                        *existing = record;
+                    }
+                    changed = true;
+                    true
+                }
+                // The record is gone, e.g. deleted or replaced by a new capture of the same
+                // text, so the edit is kept as a new record
+                Ok(None) => match repo.save(content, ContentType::Text) {
+                    Ok(record) => {
+                        edit.record_id = record.id;
+                        self.records.lock().unwrap().insert(0, record);
+                        saved_as_new = true;
+                        true
+                    }
+                    Err(e) => {
+                        log::error!("Failed to save the edited text: {e}");
+                        failed = true;
+                        true
+                    }
+                },
+                Err(e) => {
+                    log::error!("Failed to update clipboard record: {e}");
+                    failed = true;
+                    true
+                }
+            }
+        });
+        if failed {
+            self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+        }
+        if saved_as_new {
+            self.show_toast(self.i18n.t("toast_edit_saved_as_new"), ToastLevel::Info);
+        }
+        if changed || failed || saved_as_new {
+            cx.notify();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Saving the selected record to a file chosen by the user
+
+use std::path::Path;
+
+use gpui::{Context, PromptLevel, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::repository::models::ContentType;
+
+/// Suggested file name for a record saved to a file
+fn export_file_name(id: u64, content_type: &ContentType) -> String {
+    match content_type {
+        ContentType::Image => format!("ropy-{id}.png"),
+        ContentType::Text | ContentType::FilePath => format!("ropy-{id}.txt"),
+        ContentType::Svg => format!("ropy-{id}.svg"),
+    }
+}
+
+impl RopyBoard {
+    /// Ask where to save the record at `index` and write it there
+    pub(super) fn export_record(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(record) = self.filtered_records.get(index) else {
+            return;
+        };
+        let id = record.id;
+        let file_name = export_file_name(id, &record.content_type);
+        let directory = dirs::download_dir()
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(std::env::temp_dir);
+        let path_rx = cx.prompt_for_new_path(&directory, Some(file_name.as_str()));
+        let overwrite_message = self.i18n.t("export_overwrite_prompt");
+        let overwrite_label = self.i18n.t("export_overwrite");
+        let cancel_label = self.i18n.t("settings_cancel");
+
+        cx.spawn_in(window, async move |this, cx| {
+            let path = match path_rx.await {
+                Ok(Ok(Some(path))) => path,
+                // Cancelled, or the dialog went away with the window
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(e)) => {
+                    log::error!("Failed to choose an export path: {e}");
+                    return;
+                }
+            };
+            if path.exists() {
+                let Ok(answer) = this.update_in(cx, |_, window, cx| {
+                    let message = overwrite_message.replace("{path}", &path.display().to_string());
+                    window.prompt(
+                        PromptLevel::Warning,
+                        &message,
+                        None,
+                        &[overwrite_label.as_str(), cancel_label.as_str()],
+                        cx,
+                    )
+                }) else {
+                    return;
+                };
+                if answer.await != Ok(0) {
+                    return;
+                }
+            }
+            this.update(cx, |board, cx| {
+                board.finish_export(id, &path);
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn finish_export(&mut self, id: u64, path: &Path) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        match repo.export_single(id, path) {
+            Ok(true) => {
+                let message = self
+                    .i18n
+                    .t("toast_export_done")
+                    .replace("{path}", &path.display().to_string());
+                self.show_toast(message, ToastLevel::Info);
+            }
+            // Deleted while the dialog was open
+            Ok(false) => {}
+            Err(e) => {
+                log::error!("Failed to export record to {}: {e}", path.display());
+                self.show_toast(self.i18n.t("toast_export_failed"), ToastLevel::Error);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_file_name() {
+        assert_eq!(export_file_name(7, &ContentType::Text), "ropy-7.txt");
+        assert_eq!(export_file_name(7, &ContentType::Image), "ropy-7.png");
+        assert_eq!(export_file_name(7, &ContentType::FilePath), "ropy-7.txt");
+        assert_eq!(export_file_name(7, &ContentType::Svg), "ropy-7.svg");
+    }
+}
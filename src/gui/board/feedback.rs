@@ -0,0 +1,183 @@
+//! Confirming a copy with a short flash of the copied row or a sound
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::mpsc::{self, SyncSender};
+use std::time::{Duration, Instant};
+
+use gpui::{Context, Window};
+
+use super::RopyBoard;
+use crate::config::CopyFeedback;
+use crate::config::profile::data_root;
+use crate::gui::app::Assets;
+use crate::gui::utils::{play_sound, prefers_reduced_motion};
+
+/// How long the highlight of a copied row takes to fade out
+pub(super) const COPY_FLASH_DURATION: Duration = Duration::from_millis(450);
+
+/// How long a copied row stays eligible for the flash; the listener may take a moment to
+/// capture the record again at the top of the list
+const COPY_FLASH_LIFETIME: Duration = Duration::from_secs(2);
+
+/// Embedded sound played after a copy
+const COPY_SOUND: &str = "sounds/copy.wav";
+/// Name of the copy sound once written to the data directory for the system player
+const COPY_SOUND_FILE: &str = "copy.wav";
+
+/// Row highlighted after a copy
+///
+/// Matched by content rather than ID, since copying may move the record to the top under a
+/// new ID.
+#[derive(Debug, Clone)]
+pub(super) struct CopyFlash {
+    content: String,
+    /// Distinguishes consecutive flashes, so copying the same row again restarts the animation
+    pub sequence: usize,
+    started_at: Instant,
+}
+
+impl CopyFlash {
+    fn new(content: &str, previous: Option<&CopyFlash>, now: Instant) -> Self {
+        Self {
+            content: content.to_string(),
+            sequence: previous.map_or(0, |flash| flash.sequence + 1),
+            started_at: now,
+        }
+    }
+
+    pub fn matches(&self, content: &str) -> bool {
+        self.content == content
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.started_at) >= COPY_FLASH_LIFETIME
+    }
+}
+
+impl RopyBoard {
+    /// Confirm a copy the way the appearance settings ask for, returning whether a flash
+    /// was started
+    pub(super) fn give_copy_feedback(&mut self, content: &str) -> bool {
+        match self.settings.read().unwrap().appearance.copy_feedback {
+            CopyFeedback::None => false,
+            // Skipped for reduced motion
+            CopyFeedback::Flash => {
+                if prefers_reduced_motion() {
+                    return false;
+                }
+                self.copy_flash = Some(CopyFlash::new(
+                    content,
+                    self.copy_flash.as_ref(),
+                    Instant::now(),
+                ));
+                true
+            }
+            CopyFeedback::Sound => {
+                play_copy_sound();
+                false
+            }
+        }
+    }
+
+    /// Hide the window once the copy flash has played, unless another copy restarted it
+    pub(super) fn hide_after_flash(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let sequence = self.copy_flash.as_ref().map(|flash| flash.sequence);
+        let timer = cx.background_executor().timer(COPY_FLASH_DURATION);
+        cx.spawn_in(window, async move |this, cx| {
+            timer.await;
+            this.update_in(cx, |board, window, cx| {
+                if board.copy_flash.as_ref().map(|flash| flash.sequence) == sequence {
+                    board.hide(window, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Drop the copy flash once it has run its course
+    pub(super) fn expire_copy_flash(&mut self, cx: &mut Context<Self>) {
+        if self
+            .copy_flash
+            .as_ref()
+            .is_some_and(|flash| flash.is_expired(Instant::now()))
+        {
+            self.copy_flash = None;
+            cx.notify();
+        }
+    }
+}
+
+/// Write the sound `data` to `dir`, unless an identical file is already there
+fn extract_sound(dir: &Path, data: &[u8]) -> std::io::Result<PathBuf> {
+    let path = dir.join(COPY_SOUND_FILE);
+    if std::fs::read(&path).is_ok_and(|existing| existing == data) {
+        return Ok(path);
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(&path, data)?;
+    Ok(path)
+}
+
+/// Play the copy sound on a player thread started with the first copy
+///
+/// The sound is extracted to the data directory once. Copies made while it is still
+/// playing are not sounded again, so a burst of copies doesn't pile up player processes.
+fn play_copy_sound() {
+    static PLAYER: OnceLock<Option<SyncSender<()>>> = OnceLock::new();
+    let player = PLAYER.get_or_init(|| {
+        let data = Assets::get(COPY_SOUND)?.data;
+        let dir = data_root()?.join("sounds");
+        let path = extract_sound(&dir, &data)
+            .inspect_err(|e| log::warn!("Failed to extract the copy sound: {e}"))
+            .ok()?;
+        // Without a buffer, a copy only gets through while the player is idle
+        let (tx, rx) = mpsc::sync_channel::<()>(0);
+        std::thread::spawn(move || {
+            for () in rx {
+                if let Err(e) = play_sound(&path) {
+                    log::warn!("Failed to play the copy sound: {e}");
+                }
+            }
+        });
+        Some(tx)
+    });
+    if let Some(tx) = player {
+        tx.try_send(()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_flash() {
+        let now = Instant::now();
+        let first = CopyFlash::new("hello", None, now);
+        assert_eq!(first.sequence, 0);
+        assert!(first.matches("hello"));
+        assert!(!first.matches("hello world"));
+        assert!(!first.is_expired(now + COPY_FLASH_DURATION));
+        assert!(first.is_expired(now + COPY_FLASH_LIFETIME));
+
+        let second = CopyFlash::new("hello", Some(&first), now);
+        assert_eq!(second.sequence, 1);
+    }
+
+    #[test]
+    fn test_extract_sound() {
+        let dir = tempfile::tempdir().unwrap();
+        let sounds = dir.path().join("sounds");
+
+        let path = extract_sound(&sounds, b"RIFF").unwrap();
+        assert_eq!(path, sounds.join(COPY_SOUND_FILE));
+        assert_eq!(std::fs::read(&path).unwrap(), b"RIFF");
+
+        // A damaged or outdated file is replaced
+        std::fs::write(&path, b"RI").unwrap();
+        assert_eq!(extract_sound(&sounds, b"RIFF").unwrap(), path);
+        assert_eq!(std::fs::read(&path).unwrap(), b"RIFF");
+    }
+}
@@ -0,0 +1,128 @@
+//! Color flags on records, set from a row's context menu and shown as a strip on its edge
+
+use gpui::{Action, Context, Hsla, Window, rgb};
+use gpui_component::menu::PopupMenu;
+
+use super::{RopyBoard, ToastLevel};
+use crate::i18n::I18n;
+use crate::repository::models::RecordFlag;
+
+gpui::actions!(
+    board,
+    [
+        FlagRed, FlagOrange, FlagYellow, FlagGreen, FlagBlue, FlagPurple, ClearFlag
+    ]
+);
+
+/// Color of a flag's strip and filter button
+pub(super) fn flag_color(flag: RecordFlag) -> Hsla {
+    let hex = match flag {
+        RecordFlag::Red => 0xef4444,
+        RecordFlag::Orange => 0xf97316,
+        RecordFlag::Yellow => 0xeab308,
+        RecordFlag::Green => 0x22c55e,
+        RecordFlag::Blue => 0x3b82f6,
+        RecordFlag::Purple => 0xa855f7,
+    };
+    rgb(hex).into()
+}
+
+/// Locale key of a flag's name
+pub(super) fn flag_label_key(flag: RecordFlag) -> &'static str {
+    match flag {
+        RecordFlag::Red => "flag_red",
+        RecordFlag::Orange => "flag_orange",
+        RecordFlag::Yellow => "flag_yellow",
+        RecordFlag::Green => "flag_green",
+        RecordFlag::Blue => "flag_blue",
+        RecordFlag::Purple => "flag_purple",
+    }
+}
+
+/// Action setting `flag` on the selected record, or clearing its flag for `None`
+fn flag_action(flag: Option<RecordFlag>) -> Box<dyn Action> {
+    match flag {
+        Some(RecordFlag::Red) => Box::new(FlagRed),
+        Some(RecordFlag::Orange) => Box::new(FlagOrange),
+        Some(RecordFlag::Yellow) => Box::new(FlagYellow),
+        Some(RecordFlag::Green) => Box::new(FlagGreen),
+        Some(RecordFlag::Blue) => Box::new(FlagBlue),
+        Some(RecordFlag::Purple) => Box::new(FlagPurple),
+        None => Box::new(ClearFlag),
+    }
+}
+
+/// Context menu of a record row, with an entry per flag and one to clear it
+pub(super) fn flag_menu(menu: PopupMenu, i18n: &I18n, current: Option<RecordFlag>) -> PopupMenu {
+    let menu = RecordFlag::ALL.into_iter().fold(menu, |menu, flag| {
+        menu.menu_with_check(
+            i18n.t(flag_label_key(flag)),
+            current == Some(flag),
+            flag_action(Some(flag)),
+        )
+    });
+    menu.separator()
+        .menu(i18n.t("flag_clear"), flag_action(None))
+}
+
+impl RopyBoard {
+    /// Flag or unflag the selected record
+    fn flag_selected(&mut self, flag: Option<RecordFlag>, cx: &mut Context<Self>) {
+        let Some(id) = self
+            .filtered_records
+            .get(self.selected_index)
+            .map(|record| record.id)
+        else {
+            return;
+        };
+        if self.refuse_private_edit(id) {
+            cx.notify();
+            return;
+        }
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        match repo.set_flag(id, flag) {
+            Ok(Some(updated)) => {
+                let mut guard = self.records.lock().unwrap();
+                if let Some(existing) = guard.iter_mut().find(|r| r.id == id) {
+                    *existing = updated;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to update clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            }
+        }
+        cx.notify();
+    }
+
+    pub fn on_flag_red(&mut self, _: &FlagRed, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Red), cx);
+    }
+
+    pub fn on_flag_orange(&mut self, _: &FlagOrange, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Orange), cx);
+    }
+
+    pub fn on_flag_yellow(&mut self, _: &FlagYellow, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Yellow), cx);
+    }
+
+    pub fn on_flag_green(&mut self, _: &FlagGreen, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Green), cx);
+    }
+
+    pub fn on_flag_blue(&mut self, _: &FlagBlue, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Blue), cx);
+    }
+
+    pub fn on_flag_purple(&mut self, _: &FlagPurple, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(Some(RecordFlag::Purple), cx);
+    }
+
+    pub fn on_clear_flag(&mut self, _: &ClearFlag, _: &mut Window, cx: &mut Context<Self>) {
+        self.flag_selected(None, cx);
+    }
+}
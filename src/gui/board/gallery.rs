@@ -0,0 +1,148 @@
+//! Images-only gallery, laid out as a grid of thumbnails
+//!
+//! Shows the records of the image tab, so SVG markup is drawn alongside image files.
+//!
+//! Rows are built by a uniform list, so only the rows scrolled into view are rendered and
+//! the images of the others are never loaded.
+
+use std::ops::Range;
+
+use gpui::{
+    Context, Img, IntoElement, ObjectFit, ParentElement, ScrollStrategy,
+    StatefulInteractiveElement, Styled, StyledImage, div, img, prelude::InteractiveElement, px,
+    uniform_list,
+};
+use gpui_component::{ActiveTheme, h_flex};
+
+use super::RopyBoard;
+use super::render::{create_preview, record_image};
+use crate::repository::models::ContentType;
+
+/// Thumbnails per gallery row
+const GALLERY_COLUMNS: usize = 3;
+
+/// Height of a gallery row, including the gap below it
+const GALLERY_ROW_HEIGHT: f32 = 128.0;
+
+/// Number of rows needed for `count` images
+fn gallery_row_count(count: usize) -> usize {
+    count.div_ceil(GALLERY_COLUMNS)
+}
+
+/// Indices of the images shown on `row`
+fn gallery_row_range(row: usize, count: usize) -> Range<usize> {
+    let start = (row * GALLERY_COLUMNS).min(count);
+    start..(start + GALLERY_COLUMNS).min(count)
+}
+
+impl RopyBoard {
+    /// Switch between the records list and the gallery of images
+    pub(super) fn toggle_gallery(&mut self, cx: &mut Context<Self>) {
+        self.gallery_mode = !self.gallery_mode;
+        self.selected_index = 0;
+        self.gallery_scroll.scroll_to_item(0, ScrollStrategy::Top);
+        cx.notify();
+    }
+
+    /// Scroll the gallery so the row of the selected image is visible
+    pub(super) fn reveal_selected_in_gallery(&self) {
+        self.gallery_scroll
+            .scroll_to_item(self.selected_index / GALLERY_COLUMNS, ScrollStrategy::Top);
+    }
+
+    /// Render the filtered image records as a grid
+    pub(super) fn render_gallery(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let row_count = gallery_row_count(self.filtered_records.len());
+        uniform_list(
+            "gallery",
+            row_count,
+            cx.processor(|board, rows: Range<usize>, _window, cx| {
+                rows.map(|row| board.render_gallery_row(row, cx))
+                    .collect::<Vec<_>>()
+            }),
+        )
+        .track_scroll(self.gallery_scroll.clone())
+        .w_full()
+        .flex_1()
+    }
+
+    fn render_gallery_row(&self, row: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let range = gallery_row_range(row, self.filtered_records.len());
+        // Empty cells keep the thumbnails of a partial last row at the same width
+        let padding = GALLERY_COLUMNS - range.len();
+        h_flex()
+            .w_full()
+            .h(px(GALLERY_ROW_HEIGHT))
+            .pb_2()
+            .gap_2()
+            .children(range.map(|index| self.render_gallery_cell(index, cx)))
+            .children((0..padding).map(|_| div().flex_1()))
+    }
+
+    fn render_gallery_cell(&self, index: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let record = &self.filtered_records[index];
+        let broken_label = self.i18n.t("image_broken");
+        let color = cx.theme().muted_foreground;
+        let broken = {
+            let broken_label = broken_label.clone();
+            move || {
+                div()
+                    .p_1()
+                    .text_xs()
+                    .italic()
+                    .text_color(color)
+                    .child(broken_label.clone())
+                    .into_any_element()
+            }
+        };
+        let fit = |image: Img| image.size_full().object_fit(ObjectFit::Contain);
+        let image = match record.content_type {
+            ContentType::Svg => fit(img(self.svg_images.get(record))).with_fallback(broken),
+            _ => record_image(record, fit, broken),
+        };
+        let is_selected = index == self.selected_index;
+        let (content_type, original) = (record.content_type.clone(), record.content.clone());
+
+        div()
+            .id(("gallery-cell", index))
+            .flex_1()
+            .h_full()
+            .flex()
+            .items_center()
+            .justify_center()
+            .overflow_hidden()
+            .rounded_md()
+            .border_1()
+            .bg(cx.theme().secondary)
+            .border_color(if is_selected {
+                cx.theme().accent
+            } else {
+                cx.theme().border
+            })
+            .hover(|style| style.border_color(cx.theme().accent))
+            .cursor_pointer()
+            .child(image)
+            .tooltip(move |window, cx| {
+                create_preview(&content_type, &original, &broken_label, window, cx)
+            })
+            .on_click(cx.listener(move |board, _, window, cx| {
+                board.selected_index = index;
+                board.confirm_record(window, cx, index);
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gallery_rows() {
+        assert_eq!(gallery_row_count(0), 0);
+        assert_eq!(gallery_row_count(3), 1);
+        assert_eq!(gallery_row_count(7), 3);
+        assert_eq!(gallery_row_range(0, 7), 0..3);
+        assert_eq!(gallery_row_range(2, 7), 6..7);
+        assert_eq!(gallery_row_range(3, 7), 7..7);
+    }
+}
@@ -0,0 +1,147 @@
+use gpui::{
+    Context, div,
+    prelude::{InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement, Styled},
+    px,
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
+
+use super::RopyBoard;
+use super::actions::QUICK_PASTE_COUNT;
+#[cfg(target_os = "windows")]
+use crate::gui::utils::start_window_drag;
+
+#[cfg(target_os = "macos")]
+const QUIT_KEY: &str = "Cmd+Q";
+#[cfg(not(target_os = "macos"))]
+const QUIT_KEY: &str = "Alt+F4";
+
+/// Keyboard shortcuts shown in the help panel, as (keys, description locale key)
+fn shortcuts(activation_key: &str) -> Vec<(String, &'static str)> {
+    [
+        (activation_key, "help_activate"),
+        ("/", "help_search"),
+        ("↑ / ↓", "help_navigate"),
+        ("Enter", "help_confirm"),
+        (&format!("1 – {QUICK_PASTE_COUNT}"), "help_quick_paste"),
+        ("Space", "help_preview"),
+        ("Esc", "help_hide"),
+        ("Alt+Enter", "help_copy_metadata"),
+        ("Ctrl+Enter", "help_copy_without_history"),
+        ("Delete / Backspace", "help_delete"),
+        ("Alt+B", "help_burn_after_paste"),
+        ("Alt+E", "help_expiry"),
+        ("Alt+O", "help_open_in_editor"),
+        ("Alt+← / Alt+→", "help_cycle_filter"),
+        ("F", "help_toggle_favorite"),
+        ("T", "help_tags"),
+        ("Alt+F", "help_favorites_only"),
+        ("Alt+G", "help_gallery"),
+        ("Alt+↑ / Alt+↓", "help_move_favorite"),
+        ("Alt+A", "help_append_mode"),
+        ("Alt+I", "help_privacy_mode"),
+        ("Alt+Shift+P", "help_next_profile"),
+        ("Ctrl+K", "help_command_palette"),
+        ("Ctrl+S", "help_export"),
+        ("Alt+T / Alt+D / Alt+L", "help_paste_transformed"),
+        ("F5", "help_refresh"),
+        ("Alt+P", "help_promote"),
+        ("?", "help_toggle"),
+        (QUIT_KEY, "help_quit"),
+    ]
+    .into_iter()
+    .map(|(keys, description)| (keys.to_string(), description))
+    .collect()
+}
+
+/// Render the keyboard shortcuts panel content
+pub(super) fn render_help_content(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let activation_key = board.settings.read().unwrap().hotkey.activation_key.clone();
+
+    let header = h_flex()
+        .justify_between()
+        .items_center()
+        .mb_4()
+        .pt_4()
+        .child(
+            Button::new("help-back-button")
+                .small()
+                .ghost()
+                .label(board.i18n.t("about_back"))
+                .on_click(cx.listener(|board, _, window, cx| {
+                    board.show_help = false;
+                    window.focus(&board.focus_handle);
+                    cx.notify();
+                }))
+                .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+        )
+        .child(
+            div()
+                .text_lg()
+                .text_color(cx.theme().foreground)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(board.i18n.t("help_title")),
+        )
+        .child(div().w(px(55.)));
+
+    #[cfg(target_os = "windows")]
+    let header = header.on_mouse_down(gpui::MouseButton::Left, |_, window, cx| {
+        start_window_drag(window, cx);
+    });
+
+    let rows = shortcuts(&activation_key)
+        .into_iter()
+        .map(|(keys, description)| {
+            h_flex()
+                .justify_between()
+                .items_center()
+                .gap_4()
+                .py_1()
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t(description)),
+                )
+                .child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .font_family("monospace")
+                        .text_color(cx.theme().muted_foreground)
+                        .bg(cx.theme().secondary)
+                        .px_2()
+                        .rounded_sm()
+                        .child(keys),
+                )
+        });
+
+    v_flex().size_full().child(header).child(
+        div()
+            .id("help-list")
+            .flex_1()
+            .overflow_y_scroll()
+            .child(v_flex().children(rows)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::{I18n, Language};
+
+    #[test]
+    fn test_shortcuts_are_localized() {
+        let list = shortcuts("ctrl+shift+d");
+        assert_eq!(list[0].0, "ctrl+shift+d");
+        for language in Language::all() {
+            let i18n = I18n::new(language).unwrap();
+            for (_, key) in &list {
+                assert!(!i18n.t(key).starts_with("[Missing"), "{key}");
+            }
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Recording the activation hotkey by pressing it instead of typing it
+
+use gpui::{Context, KeyDownEvent, Modifiers, Window};
+
+use super::RopyBoard;
+use crate::gui::hotkey::normalize_hotkey;
+
+/// Canonical hotkey string for a key pressed with `modifiers`, if it can be registered
+///
+/// Apart from function keys, a chord needs a modifier, since a bare key would be swallowed
+/// everywhere else once registered globally.
+fn hotkey_from_chord(modifiers: &Modifiers, key: &str) -> Option<String> {
+    let is_function_key = key
+        .strip_prefix('f')
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()));
+    let has_modifier = modifiers.control || modifiers.alt || modifiers.shift || modifiers.platform;
+    if !has_modifier && !is_function_key {
+        return None;
+    }
+    let chord = [
+        (modifiers.control, "control"),
+        (modifiers.alt, "alt"),
+        (modifiers.shift, "shift"),
+        (modifiers.platform, "cmd"),
+    ]
+    .into_iter()
+    .filter(|(held, _)| *held)
+    .map(|(_, name)| name)
+    .chain(std::iter::once(key))
+    .collect::<Vec<_>>()
+    .join("+");
+    normalize_hotkey(&chord).ok()
+}
+
+impl RopyBoard {
+    /// Start or stop waiting for the activation hotkey to be pressed
+    pub(super) fn toggle_hotkey_capture(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.capturing_hotkey = !self.capturing_hotkey;
+        if self.capturing_hotkey {
+            // Keys only reach the board while it has focus, not the input
+            window.focus(&self.focus_handle);
+        }
+        cx.notify();
+    }
+
+    /// Turn the next valid chord into the activation key input's value; Escape cancels
+    pub(super) fn on_hotkey_capture_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.capturing_hotkey {
+            return;
+        }
+        cx.stop_propagation();
+        let keystroke = &event.keystroke;
+        if keystroke.key == "escape" && !keystroke.modifiers.modified() {
+            self.capturing_hotkey = false;
+            cx.notify();
+            return;
+        }
+        // Keep waiting on a lone key or a chord the hotkey library can't register
+        let Some(hotkey) = hotkey_from_chord(&keystroke.modifiers, &keystroke.key) else {
+            return;
+        };
+        self.capturing_hotkey = false;
+        self.settings_activation_key_input.update(cx, |input, cx| {
+            input.set_value(hotkey, window, cx);
+        });
+        cx.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hotkey_from_chord() {
+        let ctrl_shift = Modifiers {
+            control: true,
+            shift: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            hotkey_from_chord(&ctrl_shift, "d").as_deref(),
+            Some("control+shift+d")
+        );
+        let cmd_alt = Modifiers {
+            platform: true,
+            alt: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            hotkey_from_chord(&cmd_alt, "space").as_deref(),
+            Some("alt+cmd+space")
+        );
+
+        let none = Modifiers::default();
+        assert_eq!(hotkey_from_chord(&none, "f9").as_deref(), Some("f9"));
+        assert_eq!(hotkey_from_chord(&none, "d"), None);
+        assert_eq!(hotkey_from_chord(&none, "f"), None);
+    }
+}
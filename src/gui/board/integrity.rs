@@ -0,0 +1,149 @@
+//! Checking and repairing the history from the settings
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use gpui::{Context, PromptLevel, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::repository::IntegrityReport;
+use crate::repository::errors::RepositoryError;
+
+impl RopyBoard {
+    /// Check the active profile's history in the background, and repair it once the user
+    /// confirms what will be removed
+    pub(super) fn check_integrity(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(repository) = self
+            .active_repository
+            .as_ref()
+            .and_then(|slot| slot.read().unwrap().clone())
+        else {
+            return;
+        };
+        // Images of private records only exist in memory, so no record on disk refers to them
+        let keep: HashSet<PathBuf> = self
+            .privacy_mode
+            .image_files()
+            .into_iter()
+            .map(PathBuf::from)
+            .collect();
+        let check = {
+            let (repository, keep) = (repository.clone(), keep.clone());
+            cx.background_spawn(async move { repository.verify(false, &keep) })
+        };
+        let repair_label = self.i18n.t("integrity_repair");
+        let cancel_label = self.i18n.t("settings_cancel");
+
+        cx.spawn_in(window, async move |this, cx| {
+            let report = match check.await {
+                Ok(report) if report.is_clean() => {
+                    this.update(cx, |board, cx| board.report_integrity(Ok(report), cx))
+                        .ok();
+                    return;
+                }
+                Ok(report) => report,
+                Err(e) => {
+                    this.update(cx, |board, cx| board.report_integrity(Err(e), cx))
+                        .ok();
+                    return;
+                }
+            };
+            // Stray files are deleted for good, so ask before touching anything
+            let Ok(answer) = this.update_in(cx, |board, window, cx| {
+                let message = board.integrity_counts(&report, "integrity_repair_prompt");
+                window.prompt(
+                    PromptLevel::Warning,
+                    &message,
+                    None,
+                    &[repair_label.as_str(), cancel_label.as_str()],
+                    cx,
+                )
+            }) else {
+                return;
+            };
+            if answer.await != Ok(0) {
+                return;
+            }
+            let result = cx
+                .background_executor()
+                .spawn(async move { repository.verify(true, &keep) })
+                .await;
+            this.update(cx, |board, cx| board.report_integrity(result, cx))
+                .ok();
+        })
+        .detach();
+    }
+
+    /// Toast the outcome of a check, reloading the list when records were removed
+    fn report_integrity(
+        &mut self,
+        result: Result<IntegrityReport, RepositoryError>,
+        cx: &mut Context<Self>,
+    ) {
+        match result {
+            Ok(report) => {
+                log::info!("History check: {report}");
+                self.show_toast(self.integrity_message(&report), ToastLevel::Info);
+                if report.repaired && (report.missing_images > 0 || report.bad_records > 0) {
+                    self.reload_records();
+                }
+            }
+            Err(e) => {
+                log::error!("Failed to check the history: {e}");
+                self.show_toast(self.i18n.t("toast_integrity_failed"), ToastLevel::Error);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Write missing thumbnails in the background and report how many were made
+    pub(super) fn repair_thumbnails(&mut self, cx: &mut Context<Self>) {
+        let Some(repository) = self
+            .active_repository
+            .as_ref()
+            .and_then(|slot| slot.read().unwrap().clone())
+        else {
+            return;
+        };
+        let task = cx.background_spawn(async move { repository.regenerate_thumbnails() });
+
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |board, cx| {
+                match result {
+                    Ok(count) => {
+                        let message = board
+                            .i18n
+                            .t("toast_thumbnails_repaired")
+                            .replace("{count}", &count.to_string());
+                        board.show_toast(message, ToastLevel::Info);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to repair thumbnails: {e}");
+                        board
+                            .show_toast(board.i18n.t("toast_thumbnails_failed"), ToastLevel::Error);
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn integrity_message(&self, report: &IntegrityReport) -> String {
+        if report.is_clean() {
+            return self.i18n.t("toast_integrity_clean");
+        }
+        self.integrity_counts(report, "toast_integrity_repaired")
+    }
+
+    /// Message `key` with the problems of `report` filled in
+    fn integrity_counts(&self, report: &IntegrityReport, key: &str) -> String {
+        self.i18n
+            .t(key)
+            .replace("{missing}", &report.missing_images.to_string())
+            .replace("{orphans}", &report.orphan_files.to_string())
+            .replace("{unreadable}", &report.bad_records.to_string())
+    }
+}
@@ -1,70 +1,209 @@
 mod about;
 mod actions;
+mod color;
+mod config_file;
+mod diff;
+mod drop;
+mod editor;
+mod export;
+mod feedback;
+mod flag;
+mod gallery;
+mod help;
+mod hotkey_capture;
+mod integrity;
+mod note;
+mod paging;
+mod palette;
 mod preview;
+mod profile;
+mod query;
 mod render;
+mod search_history;
 mod settings;
+mod status;
+mod tags;
+mod theme;
+mod toast;
+mod trash;
 
-use crate::clipboard::LastCopyState;
-use crate::config::Settings;
+use crate::clipboard::{
+    AppendMode, AutoClear, LastCopyState, OwnWindow, PrivacyMode, StickyClipboard, UiRefreshGate,
+    parse_format_list,
+};
+use crate::config::{Density, PasteMode, Settings};
 use crate::gui::hide_window;
+use crate::gui::hotkey::{HotkeyBindings, bindings_from_settings, normalize_hotkey};
+use crate::gui::utils::reveal_in_file_manager;
 use crate::i18n::{I18n, Language};
-use crate::repository::models::ContentType;
-use crate::repository::{ClipboardRecord, ClipboardRepository};
+use crate::repository::models::{
+    ContentFilter, ContentType, RecordFlag, move_favorite, sort_favorites,
+};
+use crate::repository::{
+    ActiveRepository, ClipboardRecord, ClipboardStore, TrashedRecord, UsageStats, ids,
+};
 use gpui::{
-    AppContext, Context, Entity, FocusHandle, ListAlignment, ListState, Render, Subscription,
-    Window,
+    AppContext, Context, Entity, ExternalPaths, FocusHandle, ListAlignment, ListState, Render,
+    Subscription, UniformListScrollHandle, Window, WindowHandle,
     prelude::{InteractiveElement, IntoElement, ParentElement, Styled},
 };
 use gpui_component::input::InputState;
 use gpui_component::{ActiveTheme, v_flex};
-use std::str::FromStr;
+use std::collections::HashSet;
+use std::path::Path;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 // Re-export utilities for external use
 use about::render_about_content;
-pub use actions::{Active, ConfirmSelection, Hide, Quit, SelectNext, SelectPrev};
-use render::{render_header, render_search_input};
+pub use actions::{
+    Active, ClearClipboard, CompareRecords, ConfirmSelection, CopyWithMetadata, CopyWithoutHistory,
+    DeleteSelected, ExportSelected, Hide, MoveFavoriteDown, MoveFavoriteUp, NextContentFilter,
+    NextProfile, OpenInEditor, PasteDedented, PasteLowercase, PasteTrimmed, PrevContentFilter,
+    PromoteToTop, Quit, RefreshRecords, SelectNext, SelectPrev, ToggleAppendMode,
+    ToggleBurnAfterPaste, ToggleCommandPalette, ToggleExpiry, ToggleFavorite, ToggleFavoritesOnly,
+    ToggleGallery, TogglePrivacyMode, ToggleStickyClipboard,
+};
+use diff::{DiffLine, render_diff_view};
+use editor::ExternalEdit;
+use feedback::CopyFlash;
+use help::render_help_content;
+use note::render_note_editor;
+use palette::render_palette;
+use preview::{PreviewWindow, open_preview_window};
+use query::ListKey;
+use render::{
+    SvgImages, render_filter_tabs, render_header, render_hotkey_warning, render_search_input,
+};
+use search_history::render_search_history;
 use settings::render_settings_content;
+use status::render_status_bar;
+use tags::render_tag_editor;
+pub use toast::ToastLevel;
+use toast::{ToastQueue, render_toasts};
+use trash::render_trash_content;
+
+/// Window title while no record is announced in it
+const APP_TITLE: &str = "Ropy";
+/// How often the auto-hide countdown, toasts, the copy flash and external edits are checked
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(1);
 
 /// RopyBoard Main Window Component
 pub struct RopyBoard {
     records: Arc<Mutex<Vec<ClipboardRecord>>>,
     filtered_records: Vec<ClipboardRecord>, // The final shown records
-    repository: Option<Arc<ClipboardRepository>>,
+    /// What `filtered_records` was last computed from, so renders can skip recomputing it
+    filtered_for: Option<ListKey>,
+    repository: Option<Arc<dyn ClipboardStore>>,
+    /// Repository slot shared with the capture task, replaced when switching profiles
+    active_repository: Option<ActiveRepository>,
     focus_handle: FocusHandle,
     _focus_out_subscription: Subscription,
+    _activation_subscription: Subscription,
     search_input: Entity<InputState>,
+    /// Query last submitted with Enter, used when searching waits for Enter
+    submitted_query: String,
     list_state: ListState,
+    /// Most records read from disk into the history, raised as the list is scrolled
+    record_limit: usize,
+    /// Whether image records are shown alone, as a grid of thumbnails
+    gallery_mode: bool,
+    gallery_scroll: UniformListScrollHandle,
     selected_index: usize,
+    pending_delete: Option<u64>, // Image record awaiting delete confirmation
+    content_filter: ContentFilter,
+    favorites_only: bool,
+    flag_filter: Option<RecordFlag>,
     copy_tx: async_channel::Sender<crate::clipboard::CopyRequest>,
     last_copy: Arc<Mutex<LastCopyState>>,
+    refresh_gate: Arc<UiRefreshGate>,
+    /// Whether captured text is appended to the latest record
+    append_mode: Arc<AppendMode>,
+    /// Whether captures are kept out of the database for this session
+    privacy_mode: Arc<PrivacyMode>,
+    /// Record kept on the OS clipboard while other things are copied
+    sticky: Arc<StickyClipboard>,
+    /// Whether the window is active, so copies made inside it aren't captured
+    own_window: Arc<OwnWindow>,
+    /// Clears the OS clipboard a while after a copy, when configured
+    auto_clear: Arc<AutoClear>,
     // Settings
     settings: Arc<RwLock<Settings>>,
     show_settings: bool,
     show_about: bool,
+    show_help: bool,
+    show_trash: bool,
+    /// Records in the trash, read when the trash view opens or its content changes
+    trash_entries: Vec<TrashedRecord>,
     show_preview: bool,
+    preview_window: Option<WindowHandle<PreviewWindow>>,
     settings_activation_key_input: Entity<InputState>,
+    /// Whether the next key chord pressed becomes the activation key
+    capturing_hotkey: bool,
     settings_max_history_input: Entity<InputState>,
+    settings_min_text_chars_input: Entity<InputState>,
+    settings_ignored_formats_input: Entity<InputState>,
+    settings_profile_input: Entity<InputState>,
     selected_theme: usize, // 0: Light, 1: Dark, 2: System
     autostart_enabled: bool,
     pinned: bool,
-    hotkey_tx: Option<async_channel::Sender<String>>,
+    last_activity: Instant,
+    /// Whether the maintenance tick is running, see [`Self::ensure_maintenance_tick`]
+    maintenance_running: bool,
+    /// When the toast last announced through the window title was shown
+    announced_toast: Option<Instant>,
+    hotkey_tx: Option<async_channel::Sender<HotkeyBindings>>,
+    /// Hotkeys that could not be registered, usually because another app uses them
+    failed_hotkeys: Vec<String>,
+    toasts: ToastQueue,
+    external_edits: Vec<ExternalEdit>,
+    /// Large or binary-looking text records the user chose to show anyway
+    revealed_texts: HashSet<u64>,
+    /// Color record whose copy-as-format buttons are shown
+    color_formats_open: Option<u64>,
+    /// Past query highlighted in the search suggestions
+    history_selected: Option<usize>,
+    show_palette: bool,
+    palette_input: Entity<InputState>,
+    /// Position of the highlighted command among the palette's matches
+    palette_selected: usize,
+    /// Record whose note is being edited
+    editing_note: Option<u64>,
+    /// Record whose tags are being edited
+    editing_tags: Option<u64>,
+    tag_input: Entity<InputState>,
+    /// Tags used anywhere in the history, offered while editing tags
+    known_tags: Vec<String>,
+    /// Text record marked to be compared with the next one chosen
+    compare_base: Option<ClipboardRecord>,
+    /// Diff shown over the board between two compared records
+    diff_lines: Option<Vec<DiffLine>>,
+    /// Row highlighted after a copy
+    copy_flash: Option<CopyFlash>,
+    svg_images: SvgImages,
+    note_input: Entity<InputState>,
     // I18n
     i18n: I18n,
     selected_language: usize, // Index into Language::all()
 }
 
 impl RopyBoard {
-    pub fn set_hotkey_tx(&mut self, tx: async_channel::Sender<String>) {
+    pub fn set_hotkey_tx(&mut self, tx: async_channel::Sender<HotkeyBindings>) {
         self.hotkey_tx = Some(tx);
     }
 
     pub fn new(
         records: Arc<Mutex<Vec<ClipboardRecord>>>,
-        repository: Option<Arc<ClipboardRepository>>,
+        repository: Option<Arc<dyn ClipboardStore>>,
         settings: Arc<RwLock<Settings>>,
         last_copy: Arc<Mutex<LastCopyState>>,
         copy_tx: async_channel::Sender<crate::clipboard::CopyRequest>,
+        refresh_gate: Arc<UiRefreshGate>,
+        append_mode: Arc<AppendMode>,
+        privacy_mode: Arc<PrivacyMode>,
+        sticky: Arc<StickyClipboard>,
+        own_window: Arc<OwnWindow>,
+        auto_clear: Arc<AutoClear>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -75,16 +214,25 @@ impl RopyBoard {
         let _focus_out_subscription =
             cx.on_focus_out(&focus_handle, window, move |this, _event, window, cx| {
                 // When the window loses focus, hide the window
-                if !this.pinned {
-                    hide_window(window, cx);
+                let hide_on_focus_loss = this.settings.read().unwrap().behavior.hide_on_focus_loss;
+                if hide_on_focus_loss && !this.pinned {
+                    this.hide(window, cx);
                 }
             });
 
-        let search_input =
-            cx.new(|cx| InputState::new(window, cx).placeholder("Use / to search ... "));
+        // Pick up changes made by other tools while the window was in the background
+        let _activation_subscription = cx.observe_window_activation(window, |this, window, cx| {
+            this.own_window.set_active(window.is_window_active());
+            if window.is_window_active() {
+                this.refresh_records(cx);
+            }
+        });
+
         let list_state = ListState::new(0, ListAlignment::Top, gpui::px(100.));
+        list_state.set_scroll_handler(cx.listener(Self::on_list_scroll));
+        let record_limit = settings.read().unwrap().initial_record_limit();
 
-        let (max_history_records, activation_key, theme_index, language) = {
+        let (max_history_records, min_text_chars, activation_key, theme_index, language) = {
             let settings_guard = settings.read().unwrap();
             let theme_idx = match settings_guard.theme {
                 crate::config::AppTheme::Light => 0,
@@ -93,19 +241,41 @@ impl RopyBoard {
             };
             (
                 settings_guard.storage.max_history_records,
+                settings_guard.storage.min_text_chars,
                 settings_guard.hotkey.activation_key.clone(),
                 theme_idx,
                 settings_guard.language,
             )
         };
         let autostart_enabled = settings.read().unwrap().autostart.enabled;
+        let pinned = settings.read().unwrap().behavior.always_pinned;
         let settings_activation_key_input =
             cx.new(|cx| InputState::new(window, cx).placeholder(activation_key.to_string()));
         let settings_max_history_input =
             cx.new(|cx| InputState::new(window, cx).placeholder(max_history_records.to_string()));
+        let settings_min_text_chars_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(min_text_chars.to_string()));
+        let ignored_formats = settings.read().unwrap().storage.ignored_formats.join(", ");
+        let settings_ignored_formats_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("text/uri-list")
+                .default_value(ignored_formats)
+        });
 
         // Initialize I18n with the language from settings
         let i18n = I18n::new(language).unwrap_or_default();
+        let search_placeholder = i18n.t("search_placeholder");
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder(search_placeholder));
+        let palette_placeholder = i18n.t("palette_placeholder");
+        let palette_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(palette_placeholder));
+        let note_placeholder = i18n.t("note_placeholder");
+        let note_input = cx.new(|cx| InputState::new(window, cx).placeholder(note_placeholder));
+        let tag_placeholder = i18n.t("tag_placeholder");
+        let tag_input = cx.new(|cx| InputState::new(window, cx).placeholder(tag_placeholder));
+        let profile_placeholder = i18n.t("settings_profile_placeholder");
+        let settings_profile_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(profile_placeholder));
         let selected_language = Language::all()
             .iter()
             .position(|&lang| lang == language)
@@ -114,39 +284,277 @@ impl RopyBoard {
         Self {
             records,
             repository,
+            active_repository: None,
             settings,
             focus_handle,
             _focus_out_subscription,
+            _activation_subscription,
             search_input,
+            submitted_query: String::new(),
             selected_index: 0,
+            pending_delete: None,
+            content_filter: ContentFilter::default(),
+            favorites_only: false,
+            flag_filter: None,
             last_copy,
+            refresh_gate,
+            append_mode,
+            privacy_mode,
+            sticky,
+            own_window,
+            auto_clear,
             list_state,
+            record_limit,
+            gallery_mode: false,
+            gallery_scroll: UniformListScrollHandle::new(),
             filtered_records: Vec::new(),
+            filtered_for: None,
             copy_tx,
             show_settings: false,
             show_about: false,
+            show_help: false,
+            show_trash: false,
+            trash_entries: Vec::new(),
             show_preview: false,
+            preview_window: None,
             settings_activation_key_input,
+            capturing_hotkey: false,
             settings_max_history_input,
+            settings_min_text_chars_input,
+            settings_ignored_formats_input,
+            settings_profile_input,
             selected_theme: theme_index,
             autostart_enabled,
-            pinned: false,
+            pinned,
+            last_activity: Instant::now(),
+            maintenance_running: false,
+            announced_toast: None,
             hotkey_tx: None,
+            failed_hotkeys: Vec::new(),
+            toasts: ToastQueue::default(),
+            external_edits: Vec::new(),
+            revealed_texts: HashSet::new(),
+            color_formats_open: None,
+            history_selected: None,
+            show_palette: false,
+            palette_input,
+            palette_selected: 0,
+            editing_note: None,
+            note_input,
+            editing_tags: None,
+            tag_input,
+            known_tags: Vec::new(),
+            compare_base: None,
+            diff_lines: None,
+            copy_flash: None,
+            svg_images: SvgImages::default(),
             i18n,
             selected_language,
         }
     }
 
-    /// Copy content to clipboard
-    fn copy_to_clipboard(&mut self, content: &str, content_type: &ContentType) {
-        let request = match content_type {
-            ContentType::Text => Some(crate::clipboard::CopyRequest::Text(content.to_string())),
-            ContentType::Image => Some(crate::clipboard::CopyRequest::Image(content.to_string())),
-            _ => None,
+    /// Show a transient message over the board
+    pub fn show_toast(&mut self, message: impl Into<String>, level: ToastLevel) {
+        self.toasts.push(message.into(), level, Instant::now());
+    }
+
+    /// Tell the user a captured clipboard change could not be stored
+    pub fn notify_save_failed(&mut self, cx: &mut Context<Self>) {
+        self.show_toast(self.i18n.t("toast_save_failed"), ToastLevel::Error);
+        cx.notify();
+    }
+
+    /// Record the hotkeys that failed to register, warning about newly failed ones
+    pub fn set_failed_hotkeys(&mut self, failed: Vec<String>, cx: &mut Context<Self>) {
+        for hotkey in failed.iter().filter(|h| !self.failed_hotkeys.contains(h)) {
+            let message = self
+                .i18n
+                .t("toast_hotkey_in_use")
+                .replace("{hotkey}", hotkey);
+            self.show_toast(message, ToastLevel::Error);
+        }
+        self.failed_hotkeys = failed;
+        cx.notify();
+    }
+
+    /// Switch between appending captured text to the latest record and normal capture
+    fn toggle_append_mode(&mut self, cx: &mut Context<Self>) {
+        let message = if self.append_mode.toggle() {
+            self.i18n.t("toast_append_on")
+        } else {
+            self.i18n.t("toast_append_off")
+        };
+        self.show_toast(message, ToastLevel::Info);
+        cx.notify();
+    }
+
+    /// Switch between keeping captures in memory only and saving them to the history
+    fn toggle_privacy_mode(&mut self, cx: &mut Context<Self>) {
+        let message = if self.privacy_mode.toggle() {
+            self.i18n.t("toast_privacy_on")
+        } else {
+            self.i18n.t("toast_privacy_off")
+        };
+        self.show_toast(message, ToastLevel::Info);
+        cx.notify();
+    }
+
+    /// Announce the newest toast through the window title, like the selection, and go back
+    /// to the selection once the toasts are dismissed
+    fn announce_toast(&mut self, window: &mut Window) {
+        let latest = self.toasts.latest();
+        let shown_at = latest.map(|(_, _, shown_at)| shown_at);
+        if shown_at == self.announced_toast || !self.refresh_gate.is_visible() {
+            return;
+        }
+        self.announced_toast = shown_at;
+        match latest {
+            Some((message, level, _)) => {
+                let label = match level {
+                    ToastLevel::Info => message.to_string(),
+                    ToastLevel::Error => self.i18n.t("a11y_error").replace("{message}", message),
+                };
+                window.set_window_title(&format!("{APP_TITLE} – {label}"));
+            }
+            None => self.announce_selection(window),
+        }
+    }
+
+    /// Dismiss toasts that have been shown long enough
+    fn expire_toasts(&mut self, cx: &mut Context<Self>) {
+        if self.toasts.expire(Instant::now()) {
+            cx.notify();
+        }
+    }
+
+    /// Whether the compact list density is selected
+    fn is_compact(&self) -> bool {
+        self.settings.read().unwrap().appearance.density == Density::Compact
+    }
+
+    /// Hide the window and defer UI refreshes until it is shown again
+    fn hide(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_preview = false;
+        self.close_preview(cx);
+        self.refresh_gate.set_hidden();
+        window.set_window_title(APP_TITLE);
+        hide_window(window, cx);
+    }
+
+    /// Open the expanded preview window for the selected record
+    fn open_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.close_preview(cx);
+        let Some(record) = self.filtered_records.get(self.selected_index) else {
+            return;
+        };
+        let anchor = window.bounds();
+        let Some(display) = window.display(cx).map(|display| display.bounds()) else {
+            return;
+        };
+        let max_size = {
+            let appearance = &self.settings.read().unwrap().appearance;
+            gpui::size(
+                gpui::px(appearance.preview_max_width as f32),
+                gpui::px(appearance.preview_max_height as f32),
+            )
         };
+        self.preview_window = open_preview_window(
+            record.content_type.clone(),
+            record.content.clone(),
+            anchor,
+            display,
+            max_size,
+            cx,
+        );
+    }
+
+    /// Close the expanded preview window if it is open
+    fn close_preview(&mut self, cx: &mut Context<Self>) {
+        if let Some(handle) = self.preview_window.take() {
+            let _ = handle.update(cx, |_, window, _| window.remove_window());
+        }
+    }
 
-        if let Some(req) = request {
-            let _ = self.copy_tx.send_blocking(req);
+    /// Show the newly selected record in the preview window if it is open
+    fn refresh_preview(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_preview {
+            self.open_preview(window, cx);
+        }
+    }
+
+    /// Hide the window if it has been idle longer than the configured auto-hide timeout
+    fn auto_hide_if_idle(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(secs) = self.settings.read().unwrap().behavior.auto_hide_secs else {
+            return;
+        };
+        if self.pinned || !self.refresh_gate.is_visible() {
+            return;
+        }
+        if self.last_activity.elapsed() >= Duration::from_secs(secs.into()) {
+            self.hide(window, cx);
+        }
+    }
+
+    /// Whether the maintenance tick has work: an auto-hide countdown on the visible, unpinned
+    /// window, toasts or a copy flash to dismiss, or external edits to save
+    fn needs_maintenance(&self) -> bool {
+        let auto_hide = self
+            .settings
+            .read()
+            .unwrap()
+            .behavior
+            .auto_hide_secs
+            .is_some()
+            && !self.pinned
+            && self.refresh_gate.is_visible();
+        auto_hide
+            || !self.toasts.is_empty()
+            || self.copy_flash.is_some()
+            || !self.external_edits.is_empty()
+    }
+
+    /// Start the maintenance tick when it has work, it stops once there is none left
+    ///
+    /// Checked on every render, so anything that gives the tick work is picked up as soon
+    /// as it is shown.
+    fn ensure_maintenance_tick(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.maintenance_running || !self.needs_maintenance() {
+            return;
+        }
+        self.maintenance_running = true;
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(MAINTENANCE_INTERVAL).await;
+                let running = this
+                    .update_in(cx, |board, window, cx| {
+                        board.auto_hide_if_idle(window, cx);
+                        board.expire_toasts(cx);
+                        board.expire_copy_flash(cx);
+                        board.sync_external_edits(cx);
+                        board.maintenance_running = board.needs_maintenance();
+                        board.maintenance_running
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Copy content to clipboard
+    fn copy_to_clipboard(&mut self, content: &str, content_type: &ContentType) {
+        actions::send_copy(&self.copy_tx, content, content_type);
+        let clear_after = self
+            .settings
+            .read()
+            .unwrap()
+            .behavior
+            .clear_clipboard_after_secs;
+        if let Some(secs) = clear_after {
+            self.auto_clear
+                .schedule(content, content_type, Duration::from_secs(secs.into()));
         }
     }
 
@@ -154,10 +562,11 @@ impl RopyBoard {
     fn clear_history(&mut self) {
         if let Some(ref repo) = self.repository {
             if let Err(e) = repo.clear() {
-                eprintln!("[ropy] Failed to clear clipboard history: {e}");
+                log::error!("Failed to clear clipboard history: {e}");
+                self.show_toast(self.i18n.t("toast_clear_failed"), ToastLevel::Error);
             } else {
-                let mut guard = self.records.lock().unwrap();
-                guard.clear();
+                self.records.lock().unwrap().clear();
+                self.show_toast(self.i18n.t("message_cleared"), ToastLevel::Info);
             }
         }
     }
@@ -178,7 +587,21 @@ impl RopyBoard {
     pub fn delete_record(&mut self, id: u64) {
         if let Some(ref repo) = self.repository {
             if let Err(e) = repo.delete(id) {
-                eprintln!("[ropy] Failed to delete clipboard record: {e}");
+                log::error!("Failed to delete clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_delete_failed"), ToastLevel::Error);
+            } else {
+                let mut guard = self.records.lock().unwrap();
+                guard.retain(|record| record.id != id);
+            }
+        }
+    }
+
+    /// Move a record to the trash so it can still be restored
+    pub fn trash_record(&mut self, id: u64) {
+        if let Some(ref repo) = self.repository {
+            if let Err(e) = repo.trash(id) {
+                log::error!("Failed to move clipboard record to trash: {e}");
+                self.show_toast(self.i18n.t("toast_delete_failed"), ToastLevel::Error);
             } else {
                 let mut guard = self.records.lock().unwrap();
                 guard.retain(|record| record.id != id);
@@ -186,41 +609,480 @@ impl RopyBoard {
         }
     }
 
+    /// Number of records in the whole history, not just the loaded page, counting the
+    /// captures kept in memory in privacy mode
+    fn history_len(&self) -> usize {
+        let records = self.records.lock().unwrap();
+        match self.repository {
+            Some(ref repo) => {
+                let private = records
+                    .iter()
+                    .filter(|record| self.privacy_mode.is_private(record.id))
+                    .count();
+                repo.count() + private
+            }
+            None => records.len(),
+        }
+    }
+
+    /// Show the trash, purging expired records first
+    fn open_trash(&mut self) {
+        self.purge_trash();
+        self.reload_trash();
+        self.show_trash = true;
+    }
+
+    /// Read the records in the trash, most recently deleted first
+    fn reload_trash(&mut self) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        self.trash_entries = repo.list_trash().unwrap_or_else(|e| {
+            log::error!("Failed to list trashed records: {e}");
+            Vec::new()
+        });
+    }
+
+    /// Permanently delete trashed records older than the retention period
+    pub fn purge_trash(&self) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let retention_hours = self.settings.read().unwrap().storage.trash_retention_hours;
+        let cutoff = chrono::Local::now() - chrono::Duration::hours(retention_hours.into());
+        if let Err(e) = repo.purge_trash(cutoff) {
+            log::error!("Failed to purge trash: {e}");
+        }
+    }
+
+    /// Restore a trashed record into the history
+    fn restore_record(&mut self, id: u64) {
+        self.restore_records(&[id]);
+    }
+
+    /// Restore every trashed record into the history
+    fn restore_all_records(&mut self) {
+        let ids: Vec<u64> = self
+            .trash_entries
+            .iter()
+            .map(|entry| entry.record.id)
+            .collect();
+        self.restore_records(&ids);
+    }
+
+    /// Restore trashed records into the history, then read both lists again
+    fn restore_records(&mut self, ids: &[u64]) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let mut failed = false;
+        for &id in ids {
+            if let Err(e) = repo.restore(id) {
+                log::error!("Failed to restore clipboard record: {e}");
+                failed = true;
+            }
+        }
+        if failed {
+            self.show_toast(self.i18n.t("toast_restore_failed"), ToastLevel::Error);
+        }
+        self.reload_records();
+        self.reload_trash();
+    }
+
+    /// Reload the in-memory history from storage, e.g. after restoring older records
+    fn reload_records(&mut self) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let max_history_records = self.settings.read().unwrap().storage.max_history_records;
+        match repo.get_recent(self.record_limit) {
+            Ok(records) => {
+                let mut records = self.with_private_records(records);
+                records.truncate(max_history_records);
+                *self.records.lock().unwrap() = records;
+            }
+            Err(e) => log::error!("Failed to reload clipboard records: {e}"),
+        }
+        self.filtered_for = None;
+    }
+
+    /// Reload the history from disk to pick up changes made outside this window
+    ///
+    /// Edits pending in external editors are saved first so the reload can't discard them.
+    fn refresh_records(&mut self, cx: &mut Context<Self>) {
+        self.sync_external_edits(cx);
+        self.reload_records();
+        cx.notify();
+    }
+
     /// Get filtered records based on search query
     fn get_filtered_records(&self, query: &str) -> Vec<ClipboardRecord> {
-        if query.is_empty() {
+        query::displayed_records(self.get_matching_records(query), query, self.list_filters())
+    }
+
+    /// Recompute the displayed rows when the query, the filters or the history changed since
+    /// they were last computed, returning whether the rows changed
+    ///
+    /// Runs on every render, and with a search or filter active the rows are read from the
+    /// whole history.
+    fn refresh_filtered_records(&mut self, query: &str) -> bool {
+        let filters = self.list_filters();
+        let generation = self.repository.as_ref().map_or(0, |repo| repo.generation());
+        let unchanged = self.filtered_for.as_ref().is_some_and(|key| {
+            key.query == query
+                && key.filters == filters
+                && key.generation == generation
+                && *self.records.lock().unwrap() == key.page
+        });
+        if unchanged {
+            return false;
+        }
+        let page = self.records.lock().unwrap().clone();
+        let rows = self.get_filtered_records(query);
+        self.filtered_for = Some(ListKey {
+            query: query.to_string(),
+            filters,
+            generation,
+            page,
+        });
+        if rows == self.filtered_records {
+            return false;
+        }
+        self.filtered_records = rows;
+        true
+    }
+
+    /// Records matching the search query, before the filter tabs are applied
+    ///
+    /// Without a query or filter, these are the loaded records. Searches and filters read
+    /// the repository, so they cover the whole history rather than the loaded page.
+    fn get_matching_records(&self, query: &str) -> Vec<ClipboardRecord> {
+        if query.is_empty() && !self.is_filtered() {
             let guard = self.records.lock().unwrap();
             guard.clone()
         } else if let Some(ref repo) = self.repository {
-            repo.search(query).unwrap_or_default()
+            if query.is_empty() {
+                let records = repo.get_recent(usize::MAX).unwrap_or_default();
+                self.with_private_records(records)
+            } else {
+                let scope = self.settings.read().unwrap().behavior.search_scope;
+                repo.search_with_scope(query, scope).unwrap_or_default()
+            }
         } else {
             Vec::new()
         }
     }
 
-    /// Confirm, hide and delete.
+    /// Whether a content tab, favorites-only, a flag filter or the gallery narrows the list
+    fn is_filtered(&self) -> bool {
+        self.content_filter != ContentFilter::All
+            || self.favorites_only
+            || self.flag_filter.is_some()
+            || self.gallery_mode
+    }
+
+    /// Add the in-memory captures made in privacy mode to records read from disk, newest
+    /// first
+    fn with_private_records(&self, mut records: Vec<ClipboardRecord>) -> Vec<ClipboardRecord> {
+        let guard = self.records.lock().unwrap();
+        records.extend(
+            guard
+                .iter()
+                .filter(|record| self.privacy_mode.is_private(record.id))
+                .cloned(),
+        );
+        records.sort_by(|a, b| b.id.cmp(&a.id));
+        records
+    }
+
+    /// Copy the record at `index` of the rows as displayed, then hide and move it to the top
+    /// or remove it.
+    ///
+    /// Used by Enter, clicks and the number keys alike, so all of them honor the paste mode.
     fn confirm_record(&mut self, window: &mut Window, cx: &mut Context<Self>, index: usize) {
-        let (id, content, content_type) = {
-            if let Some(record) = self.filtered_records.get(index) {
-                (
-                    record.id,
-                    record.content.clone(),
-                    record.content_type.clone(),
-                )
-            } else {
-                return;
-            }
+        let Some(record) = self.filtered_records.get(index).cloned() else {
+            return;
         };
+        let (id, content, content_type, delete_after_paste) = (
+            record.id,
+            record.content.clone(),
+            record.content_type.clone(),
+            record.delete_after_paste,
+        );
+        let moves_to_top = actions::moves_to_top_on_copy(&self.records.lock().unwrap(), id);
+        // Nothing about a private capture is written to disk
+        if !self.privacy_mode.is_private(id) {
+            self.record_use(&record);
+            self.remember_search(cx);
+        }
+        let paste_mode = self.settings.read().unwrap().behavior.paste_mode;
+        let consume = delete_after_paste || paste_mode == PasteMode::Cut;
+        if consume || moves_to_top {
+            // Keep the listener from capturing the pasted content again
+            *self.last_copy.lock().unwrap_or_else(|e| e.into_inner()) = match content_type {
+                ContentType::Image => LastCopyState::IgnoreNext,
+                _ => LastCopyState::Text(content.clone()),
+            };
+        }
         self.copy_to_clipboard(&content, &content_type);
-        if !self.pinned {
-            hide_window(window, cx);
+        let flashed = self.give_copy_feedback(&content);
+        if self.pinned {
+            self.show_toast(self.i18n.t("message_copied"), ToastLevel::Info);
+        } else if flashed {
+            self.hide_after_flash(window, cx);
+        } else {
+            self.hide(window, cx);
         }
-        if index != 0 {
+        if delete_after_paste {
             self.delete_record(id);
+        } else if consume {
+            // Cut records stay restorable, and an image file outlives the clipboard write
+            self.trash_record(id);
+        } else if moves_to_top {
+            // Moved rather than captured again, so its favorite, note, flag and tags stay
+            self.move_to_top(id);
+        }
+    }
+
+    /// Count a copy of `record` in the usage statistics
+    fn record_use(&self, record: &ClipboardRecord) {
+        if let Some(ref repo) = self.repository
+            && let Err(e) = repo.record_use(record, chrono::Local::now())
+        {
+            log::warn!("Failed to record clipboard usage: {e}");
+        }
+    }
+
+    /// Copies today and this week, and the most-copied items
+    fn usage_stats(&self) -> UsageStats {
+        let Some(ref repo) = self.repository else {
+            return UsageStats::default();
+        };
+        repo.usage_stats(chrono::Local::now()).unwrap_or_else(|e| {
+            log::error!("Failed to read usage statistics: {e}");
+            UsageStats::default()
+        })
+    }
+
+    /// Forget all usage statistics
+    fn reset_usage_stats(&mut self) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        if let Err(e) = repo.reset_usage() {
+            log::error!("Failed to reset usage statistics: {e}");
+            self.show_toast(self.i18n.t("toast_stats_reset_failed"), ToastLevel::Error);
+        }
+    }
+
+    /// Whether record `id` was captured in privacy mode, telling the user it can't be changed
+    ///
+    /// Private records are never written to the database, so favorites, notes, flags and
+    /// tags can't be stored for them.
+    fn refuse_private_edit(&mut self, id: u64) -> bool {
+        let private = self.privacy_mode.is_private(id);
+        if private {
+            self.show_toast(self.i18n.t("toast_private_record"), ToastLevel::Info);
+        }
+        private
+    }
+
+    /// Flip the favorite state of a record
+    fn toggle_favorite(&mut self, id: u64) {
+        if self.refuse_private_edit(id) {
+            return;
+        }
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let Ok(Some(record)) = repo.get_by_id(id) else {
+            return;
+        };
+        match repo.set_favorite(id, !record.favorite) {
+            Ok(Some(updated)) => {
+                let mut guard = self.records.lock().unwrap();
+                if let Some(existing) = guard.iter_mut().find(|r| r.id == id) {
+                    *existing = updated;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to update clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            }
+        }
+    }
+
+    /// Move a record to the top of the history under a fresh ID, returning that ID
+    ///
+    /// Records captured in privacy mode only exist in memory, so they are moved there.
+    fn move_to_top(&mut self, id: u64) -> Option<u64> {
+        if self.privacy_mode.is_private(id) {
+            let mut guard = self.records.lock().unwrap();
+            let index = guard.iter().position(|r| r.id == id)?;
+            let mut record = guard.remove(index);
+            let now = chrono::Local::now();
+            record.id = ids::next_id(now, 1);
+            record.created_at = now;
+            self.privacy_mode.remember(record.id, None);
+            let new_id = record.id;
+            guard.insert(0, record);
+            drop(guard);
+            self.follow_moved_record(id, new_id);
+            return Some(new_id);
+        }
+        let repo = self.repository.as_ref()?;
+        let promoted = match repo.touch(id) {
+            Ok(Some(promoted)) => promoted,
+            Ok(None) => return None,
+            Err(e) => {
+                log::error!("Failed to move record to the top: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+                return None;
+            }
+        };
+        let new_id = promoted.id;
+        {
+            let mut guard = self.records.lock().unwrap();
+            guard.retain(|r| r.id != id);
+            guard.insert(0, promoted);
+        }
+        self.follow_moved_record(id, new_id);
+        Some(new_id)
+    }
+
+    /// Move a record to the top of the history without copying it, keeping it selected
+    fn promote_to_top(&mut self, id: u64, cx: &mut Context<Self>) {
+        let Some(new_id) = self.move_to_top(id) else {
+            return;
+        };
+        // Follow the record, which may not be first when search results are ranked
+        let query = self.active_query(cx);
+        self.filtered_records = self.get_filtered_records(&query);
+        self.list_state.reset(self.filtered_records.len());
+        self.selected_index = self
+            .filtered_records
+            .iter()
+            .position(|r| r.id == new_id)
+            .unwrap_or(0);
+        self.list_state.scroll_to_reveal_item(self.selected_index);
+        cx.notify();
+    }
+
+    /// Move favorite `id` to the position of favorite `target`, persisting the new order
+    fn move_favorite_to(&mut self, id: u64, target: u64) {
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let ids: Vec<u64> = {
+            let guard = self.records.lock().unwrap();
+            let mut favorites: Vec<ClipboardRecord> =
+                guard.iter().filter(|r| r.favorite).cloned().collect();
+            sort_favorites(&mut favorites);
+            favorites.iter().map(|r| r.id).collect()
+        };
+        match repo.set_favorite_order(&move_favorite(&ids, id, target)) {
+            Ok(updated) => {
+                let mut guard = self.records.lock().unwrap();
+                for record in updated {
+                    if let Some(existing) = guard.iter_mut().find(|r| r.id == record.id) {
+                        *existing = record;
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("Failed to update clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            }
+        }
+    }
+
+    /// Apply a change to a record and persist it
+    fn update_record(&mut self, id: u64, update: impl FnOnce(&mut ClipboardRecord)) {
+        if self.refuse_private_edit(id) {
+            return;
+        }
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let Ok(Some(mut record)) = repo.get_by_id(id) else {
+            return;
+        };
+        update(&mut record);
+        if let Err(e) = repo.update(&record) {
+            log::error!("Failed to update clipboard record: {e}");
+            self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            return;
+        }
+        let mut guard = self.records.lock().unwrap();
+        if let Some(existing) = guard.iter_mut().find(|r| r.id == id) {
+            *existing = record;
+        }
+    }
+
+    /// Remove expired records from storage and the list
+    ///
+    /// Scans the whole history in the background, since records beyond the loaded page
+    /// expire too.
+    pub fn remove_expired_records(&mut self, cx: &mut Context<Self>) {
+        let Some(repo) = self.repository.clone() else {
+            return;
+        };
+        let task = cx.background_spawn(async move { repo.remove_expired(chrono::Local::now()) });
+        cx.spawn(async move |this, cx| {
+            let result = task.await;
+            this.update(cx, |board, cx| match result {
+                Ok(removed) if !removed.is_empty() => {
+                    board
+                        .records
+                        .lock()
+                        .unwrap()
+                        .retain(|record| !removed.contains(&record.id));
+                    cx.notify();
+                }
+                Ok(_) => {}
+                Err(e) => log::error!("Failed to remove expired records: {e}"),
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Copy the on-disk path of an image record as text
+    fn copy_image_path(&mut self, window: &mut Window, cx: &mut Context<Self>, index: usize) {
+        let Some(record) = self.filtered_records.get(index) else {
+            return;
+        };
+        if record.content_type != ContentType::Image {
+            return;
+        }
+        self.copy_to_clipboard(&record.content.clone(), &ContentType::Text);
+        if self.pinned {
+            self.show_toast(self.i18n.t("message_copied"), ToastLevel::Info);
+        } else {
+            self.hide(window, cx);
+        }
+    }
+
+    /// Show an image or file record in the OS file manager
+    fn reveal_record(&mut self, index: usize) {
+        let Some(record) = self.filtered_records.get(index) else {
+            return;
+        };
+        if !matches!(
+            record.content_type,
+            ContentType::Image | ContentType::FilePath
+        ) {
+            return;
+        }
+        if let Err(e) = reveal_in_file_manager(Path::new(&record.content)) {
+            log::error!("Failed to reveal file in file manager: {e}");
+            self.show_toast(self.i18n.t("toast_reveal_failed"), ToastLevel::Error);
         }
     }
 
     fn save_settings(&mut self, cx: &mut Context<Self>, window: &mut Window) {
+        self.capturing_hotkey = false;
         let mut activation_key = self
             .settings_activation_key_input
             .read(cx)
@@ -234,9 +1096,14 @@ impl RopyBoard {
             if activation_key.is_empty() {
                 activation_key = Settings::default().hotkey.activation_key;
             }
-        } else if global_hotkey::hotkey::HotKey::from_str(&activation_key).is_err() {
-            is_hotkey_invalid = true;
-            activation_key = Settings::default().hotkey.activation_key;
+        } else {
+            match normalize_hotkey(&activation_key) {
+                Ok(normalized) => activation_key = normalized,
+                Err(_) => {
+                    is_hotkey_invalid = true;
+                    activation_key = Settings::default().hotkey.activation_key;
+                }
+            }
         }
 
         // Get current max_history_records from settings as fallback
@@ -250,6 +1117,18 @@ impl RopyBoard {
             .parse::<usize>()
             .unwrap_or(current_max_history);
 
+        let current_min_text_chars = self.settings.read().unwrap().storage.min_text_chars;
+        let min_text_chars = self
+            .settings_min_text_chars_input
+            .read(cx)
+            .value()
+            .to_string()
+            .parse::<usize>()
+            .unwrap_or(current_min_text_chars);
+
+        let ignored_formats =
+            parse_format_list(&self.settings_ignored_formats_input.read(cx).value());
+
         let theme = match self.selected_theme {
             0 => crate::config::AppTheme::Light,
             1 => crate::config::AppTheme::Dark,
@@ -261,26 +1140,35 @@ impl RopyBoard {
             .copied()
             .unwrap_or_default();
 
-        {
+        let mut save_failed = false;
+        let hotkey_bindings = {
             let mut settings = self.settings.write().unwrap();
             settings.hotkey.activation_key = activation_key.clone();
             settings.storage.max_history_records = max_history;
+            settings.storage.min_text_chars = min_text_chars;
+            settings.storage.ignored_formats = ignored_formats.clone();
             settings.theme = theme.clone();
             settings.autostart.enabled = self.autostart_enabled;
             settings.language = language;
             if let Err(e) = settings.save() {
-                eprintln!("[ropy] Failed to save settings: {e}");
+                log::error!("Failed to save settings: {e}");
+                save_failed = true;
             }
+            bindings_from_settings(&settings)
+        };
+
+        if save_failed {
+            self.show_toast(self.i18n.t("toast_settings_save_failed"), ToastLevel::Error);
         }
 
-        // Update hotkey if sender is available
+        // Update hotkeys if sender is available
         if let Some(tx) = &self.hotkey_tx {
-            let _ = tx.try_send(activation_key.clone());
+            let _ = tx.try_send(hotkey_bindings);
         }
 
         // Apply the new language
         if let Err(e) = self.i18n.set_language(language) {
-            eprintln!("[ropy] Failed to set language: {e}");
+            log::error!("Failed to set language: {e}");
         }
 
         // Update search placeholder with new language
@@ -291,17 +1179,32 @@ impl RopyBoard {
 
         // Sync auto-start state with system
         if let Err(e) = self.sync_autostart_state() {
-            eprintln!("[ropy] Failed to sync auto-start state: {e}");
+            log::error!("Failed to sync auto-start state: {e}");
+            self.show_toast(self.i18n.t("toast_autostart_failed"), ToastLevel::Error);
         }
 
         // Apply the new theme
         let app_theme = &theme.get_theme();
-        crate::gui::app::set_app_theme(window, cx, app_theme);
+        let colors = self
+            .settings
+            .read()
+            .unwrap()
+            .appearance
+            .custom_colors
+            .clone();
+        crate::gui::app::set_app_theme(window, cx, app_theme, &colors);
 
         self.settings_max_history_input.update(cx, |input, cx| {
             input.set_placeholder(max_history.to_string(), window, cx);
             input.set_value("", window, cx);
         });
+        self.settings_min_text_chars_input.update(cx, |input, cx| {
+            input.set_placeholder(min_text_chars.to_string(), window, cx);
+            input.set_value("", window, cx);
+        });
+        self.settings_ignored_formats_input.update(cx, |input, cx| {
+            input.set_value(ignored_formats.join(", "), window, cx);
+        });
 
         let hotkey_invalid_msg = self.i18n.t("settings_hotkey_invalid");
         self.settings_activation_key_input.update(cx, |input, cx| {
@@ -328,33 +1231,71 @@ impl RopyBoard {
 }
 
 impl Render for RopyBoard {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.ensure_maintenance_tick(window, cx);
+        self.announce_toast(window);
         let base = v_flex()
             .id("ropy-board")
             .track_focus(&self.focus_handle)
             .on_action(cx.listener(Self::on_hide_action))
             .on_action(cx.listener(Self::on_quit_action))
             .on_action(cx.listener(Self::on_active_action))
+            .on_action(cx.listener(Self::on_clear_clipboard))
+            .on_action(cx.listener(Self::on_next_content_filter))
+            .on_action(cx.listener(Self::on_prev_content_filter))
+            .on_action(cx.listener(Self::on_toggle_favorites_only))
+            .on_action(cx.listener(Self::on_flag_red))
+            .on_action(cx.listener(Self::on_flag_orange))
+            .on_action(cx.listener(Self::on_flag_yellow))
+            .on_action(cx.listener(Self::on_flag_green))
+            .on_action(cx.listener(Self::on_flag_blue))
+            .on_action(cx.listener(Self::on_flag_purple))
+            .on_action(cx.listener(Self::on_clear_flag))
+            .on_action(cx.listener(Self::on_toggle_gallery))
+            .on_drop(cx.listener(Self::on_drop_paths))
+            .drag_over::<ExternalPaths>(|style, _, _, cx| style.bg(cx.theme().drop_target))
+            .capture_key_down(cx.listener(|this, _, _, _| {
+                this.last_activity = Instant::now();
+            }))
+            .capture_key_down(cx.listener(Self::on_hotkey_capture_key))
+            .on_mouse_move(cx.listener(|this, _, _, _| {
+                this.last_activity = Instant::now();
+            }))
             .bg(cx.theme().background)
+            .relative()
             .size_full()
             .px_4()
             .pb_4();
 
         if self.show_settings {
-            return base.child(render_settings_content(self, cx));
+            return base
+                .child(render_settings_content(self, cx))
+                .child(render_toasts(&self.toasts, cx));
+        }
+
+        if self.show_help {
+            return base
+                .child(render_help_content(self, cx))
+                .child(render_toasts(&self.toasts, cx));
         }
 
         if self.show_about {
-            return base.child(render_about_content(self, cx));
+            return base
+                .child(render_about_content(self, cx))
+                .child(render_toasts(&self.toasts, cx));
         }
 
-        // Render main clipboard view
-        let query = self.search_input.read(cx).value().to_string();
-        let new_filtered_records = self.get_filtered_records(&query);
+        if self.show_trash {
+            return base
+                .child(render_trash_content(self, cx))
+                .child(render_toasts(&self.toasts, cx));
+        }
 
-        if new_filtered_records != self.filtered_records {
-            self.filtered_records = new_filtered_records;
+        // Render main clipboard view
+        let query = self.active_query(cx);
+        if self.refresh_filtered_records(&query) {
             self.list_state.reset(self.filtered_records.len());
+            self.svg_images.retain_shown(&self.filtered_records);
         }
 
         if self.selected_index >= self.filtered_records.len() && !self.filtered_records.is_empty() {
@@ -363,12 +1304,57 @@ impl Render for RopyBoard {
             self.selected_index = 0;
         }
 
+        let records_view = if self.gallery_mode {
+            self.render_gallery(cx).into_any_element()
+        } else {
+            self.render_records_list(cx).into_any_element()
+        };
+
         base.on_action(cx.listener(Self::on_select_prev))
             .on_action(cx.listener(Self::on_select_next))
             .on_action(cx.listener(Self::on_confirm_selection))
+            .on_action(cx.listener(Self::on_copy_with_metadata))
+            .on_action(cx.listener(Self::on_copy_without_history))
+            .on_action(cx.listener(Self::on_delete_selected))
+            .on_action(cx.listener(Self::on_toggle_burn_after_paste))
+            .on_action(cx.listener(Self::on_toggle_expiry))
+            .on_action(cx.listener(Self::on_open_in_editor))
+            .on_action(cx.listener(Self::on_toggle_append_mode))
+            .on_action(cx.listener(Self::on_toggle_privacy_mode))
+            .on_action(cx.listener(Self::on_next_profile))
+            .on_action(cx.listener(Self::on_toggle_command_palette))
+            .on_action(cx.listener(Self::on_export_selected))
+            .on_action(cx.listener(Self::on_refresh_records))
+            .on_action(cx.listener(Self::on_promote_to_top))
+            .on_action(cx.listener(Self::on_toggle_favorite))
+            .on_action(cx.listener(Self::on_toggle_sticky_clipboard))
+            .on_action(cx.listener(Self::on_compare_records))
+            .on_action(cx.listener(Self::on_paste_trimmed))
+            .on_action(cx.listener(Self::on_paste_dedented))
+            .on_action(cx.listener(Self::on_paste_lowercase))
+            .on_action(cx.listener(Self::on_move_favorite_up))
+            .on_action(cx.listener(Self::on_move_favorite_down))
+            .capture_key_down(cx.listener(Self::on_search_history_key))
+            .capture_key_down(cx.listener(Self::on_palette_key))
+            .capture_key_down(cx.listener(Self::on_note_key))
+            .capture_key_down(cx.listener(Self::on_tag_key))
+            .capture_key_down(cx.listener(Self::on_search_enter_key))
             .on_key_down(cx.listener(Self::on_key_down))
             .child(render_header(self, cx))
-            .child(render_search_input(&self.search_input, cx))
-            .child(self.render_records_list(cx))
+            .children(render_hotkey_warning(self, cx))
+            .child(render_status_bar(self, &query, cx))
+            .child(render_search_input(
+                &self.search_input,
+                self.is_compact(),
+                cx,
+            ))
+            .children(render_search_history(self, window, cx))
+            .child(render_filter_tabs(self, cx))
+            .child(records_view)
+            .children(render_palette(self, cx))
+            .children(render_note_editor(self, cx))
+            .children(render_tag_editor(self, cx))
+            .children(render_diff_view(self, cx))
+            .child(render_toasts(&self.toasts, cx))
     }
 }
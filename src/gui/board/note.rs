@@ -0,0 +1,125 @@
+//! Editor for the short note attached to a record
+
+use gpui::{
+    Context, Focusable, KeyDownEvent, Window, div,
+    prelude::{InteractiveElement, IntoElement, ParentElement, Styled},
+};
+use gpui_component::input::Input;
+use gpui_component::{ActiveTheme, v_flex};
+
+use super::{RopyBoard, ToastLevel};
+
+impl RopyBoard {
+    /// Open the note editor for a record, filled with its current note
+    pub(super) fn open_note_editor(
+        &mut self,
+        id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.refuse_private_edit(id) {
+            cx.notify();
+            return;
+        }
+        let note = {
+            let guard = self.records.lock().unwrap();
+            let Some(record) = guard.iter().find(|r| r.id == id) else {
+                return;
+            };
+            record.note.clone().unwrap_or_default()
+        };
+        self.editing_note = Some(id);
+        self.note_input.update(cx, |input, cx| {
+            input.set_value(note, window, cx);
+        });
+        window.focus(&self.note_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    pub(super) fn close_note_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing_note = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Save the edited note, clearing it when the text is blank
+    fn save_note(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(id) = self.editing_note else {
+            return;
+        };
+        let note = self.note_input.read(cx).value().to_string();
+        self.close_note_editor(window, cx);
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        match repo.set_note(id, Some(note)) {
+            Ok(Some(updated)) => {
+                let mut guard = self.records.lock().unwrap();
+                if let Some(existing) = guard.iter_mut().find(|r| r.id == id) {
+                    *existing = updated;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to update clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            }
+        }
+    }
+
+    /// Save on Enter and cancel on Escape, before the input or the board sees the keys
+    pub(super) fn on_note_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing_note.is_none() {
+            return;
+        }
+        match event.keystroke.key.as_str() {
+            "enter" => self.save_note(window, cx),
+            "escape" => self.close_note_editor(window, cx),
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+}
+
+/// Render the note editor over the board while a note is being edited
+pub(super) fn render_note_editor(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    board.editing_note?;
+    Some(
+        v_flex()
+            .id("note-editor")
+            .absolute()
+            .top(gpui::px(48.))
+            .left_4()
+            .right_4()
+            .p_2()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .shadow_lg()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(board.i18n.t("note_editor_hint")),
+            )
+            .child(
+                Input::new(&board.note_input)
+                    .appearance(false)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded_md()
+                    .px_2()
+                    .py_1(),
+            ),
+    )
+}
@@ -0,0 +1,74 @@
+//! Loading older records as the list is scrolled, so startup only reads the newest ones
+//!
+//! Searches and filters query the repository directly and always cover the whole history.
+
+use gpui::{Context, ListScrollEvent, Window};
+
+use super::RopyBoard;
+
+/// Load more records once the last visible row is this close to the end of the list
+const LOAD_MORE_MARGIN: usize = 10;
+
+/// Record limit after loading another page, never beyond the history cap
+fn next_record_limit(current: usize, page: usize, max: usize) -> usize {
+    current.saturating_add(page.max(1)).min(max)
+}
+
+impl RopyBoard {
+    /// Read the next page of older records from disk, keeping the scroll position
+    pub(super) fn load_more_records(&mut self, cx: &mut Context<Self>) {
+        let (page, max) = {
+            let settings = self.settings.read().unwrap();
+            (
+                settings.initial_record_limit(),
+                settings.storage.max_history_records,
+            )
+        };
+        // Fewer records than the limit means the whole history is already loaded
+        let loaded = self.records.lock().unwrap().len();
+        if self.record_limit >= max
+            || loaded < self.record_limit
+            || !self.active_query(cx).is_empty()
+            || self.is_filtered()
+        {
+            return;
+        }
+        self.record_limit = next_record_limit(self.record_limit, page, max);
+        self.reload_records();
+
+        let previous = self.filtered_records.len();
+        let filtered = self.get_filtered_records("");
+        // Older records only extend the end of the list, so the rows on screen stay put
+        if filtered.starts_with(&self.filtered_records) {
+            self.list_state
+                .splice(previous..previous, filtered.len() - previous);
+        } else {
+            self.list_state.reset(filtered.len());
+        }
+        self.filtered_records = filtered;
+        cx.notify();
+    }
+
+    pub(super) fn on_list_scroll(
+        &mut self,
+        event: &ListScrollEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if event.visible_range.end + LOAD_MORE_MARGIN >= event.count {
+            self.load_more_records(cx);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_record_limit() {
+        assert_eq!(next_record_limit(100, 100, 1000), 200);
+        assert_eq!(next_record_limit(950, 100, 1000), 1000);
+        assert_eq!(next_record_limit(100, 0, 1000), 101);
+    }
+}
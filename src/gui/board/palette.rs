@@ -0,0 +1,246 @@
+//! Command palette listing the board's actions, filtered by a fuzzy query
+
+use gpui::{
+    Action, Context, Focusable, KeyDownEvent, Window, div,
+    prelude::{
+        FluentBuilder, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement,
+        Styled,
+    },
+};
+use gpui_component::input::Input;
+use gpui_component::{ActiveTheme, v_flex};
+
+use super::RopyBoard;
+use super::actions::{
+    ClearClipboard, CompareRecords, ConfirmSelection, CopyWithMetadata, CopyWithoutHistory,
+    DeleteSelected, ExportSelected, Hide, MoveFavoriteDown, MoveFavoriteUp, NextContentFilter,
+    NextProfile, OpenInEditor, PasteDedented, PasteLowercase, PasteTrimmed, PrevContentFilter,
+    PromoteToTop, Quit, RefreshRecords, ToggleAppendMode, ToggleBurnAfterPaste, ToggleExpiry,
+    ToggleFavorite, ToggleFavoritesOnly, ToggleGallery, TogglePrivacyMode, ToggleStickyClipboard,
+};
+use crate::repository::fuzzy::fuzzy_score;
+
+/// Actions offered in the palette, as (name locale key, action)
+fn palette_commands() -> Vec<(&'static str, Box<dyn Action>)> {
+    vec![
+        ("palette_copy", Box::new(ConfirmSelection)),
+        ("palette_copy_metadata", Box::new(CopyWithMetadata)),
+        ("palette_copy_without_history", Box::new(CopyWithoutHistory)),
+        ("palette_paste_trimmed", Box::new(PasteTrimmed)),
+        ("palette_paste_dedented", Box::new(PasteDedented)),
+        ("palette_paste_lowercase", Box::new(PasteLowercase)),
+        ("palette_promote", Box::new(PromoteToTop)),
+        ("palette_delete", Box::new(DeleteSelected)),
+        ("palette_burn_after_paste", Box::new(ToggleBurnAfterPaste)),
+        ("palette_expiry", Box::new(ToggleExpiry)),
+        ("palette_open_in_editor", Box::new(OpenInEditor)),
+        ("palette_export", Box::new(ExportSelected)),
+        ("palette_next_filter", Box::new(NextContentFilter)),
+        ("palette_prev_filter", Box::new(PrevContentFilter)),
+        ("palette_toggle_favorite", Box::new(ToggleFavorite)),
+        ("palette_toggle_sticky", Box::new(ToggleStickyClipboard)),
+        ("palette_compare", Box::new(CompareRecords)),
+        ("palette_favorites_only", Box::new(ToggleFavoritesOnly)),
+        ("palette_gallery", Box::new(ToggleGallery)),
+        ("palette_move_favorite_up", Box::new(MoveFavoriteUp)),
+        ("palette_move_favorite_down", Box::new(MoveFavoriteDown)),
+        ("palette_append_mode", Box::new(ToggleAppendMode)),
+        ("palette_privacy_mode", Box::new(TogglePrivacyMode)),
+        ("palette_next_profile", Box::new(NextProfile)),
+        ("palette_refresh", Box::new(RefreshRecords)),
+        ("palette_clear_clipboard", Box::new(ClearClipboard)),
+        ("palette_hide", Box::new(Hide)),
+        ("palette_quit", Box::new(Quit)),
+    ]
+}
+
+/// Indices of the names matching `query`, best match first. An empty query matches all.
+fn matching_commands(query: &str, names: &[String]) -> Vec<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return (0..names.len()).collect();
+    }
+    let mut scored: Vec<(i64, usize)> = names
+        .iter()
+        .enumerate()
+        .filter_map(|(index, name)| fuzzy_score(query, name).map(|score| (score, index)))
+        .collect();
+    // Stable, so equally good matches keep the palette's order
+    scored.sort_by(|a, b| b.0.cmp(&a.0));
+    scored.into_iter().map(|(_, index)| index).collect()
+}
+
+impl RopyBoard {
+    /// Localized names of the palette's commands, in palette order
+    fn palette_names(&self) -> Vec<String> {
+        palette_commands()
+            .into_iter()
+            .map(|(key, _)| self.i18n.t(key))
+            .collect()
+    }
+
+    /// Commands matching the palette query, as (index into the palette, name)
+    fn palette_matches(&self, cx: &Context<Self>) -> Vec<(usize, String)> {
+        let names = self.palette_names();
+        let query = self.palette_input.read(cx).value().to_string();
+        matching_commands(&query, &names)
+            .into_iter()
+            .map(|index| (index, names[index].clone()))
+            .collect()
+    }
+
+    pub(super) fn open_palette(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_palette = true;
+        self.palette_selected = 0;
+        self.palette_input.update(cx, |input, cx| {
+            input.set_value("", window, cx);
+        });
+        window.focus(&self.palette_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    pub(super) fn close_palette(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_palette = false;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Close the palette and dispatch the command at `index` of the palette
+    fn run_palette_command(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((_, action)) = palette_commands().into_iter().nth(index) else {
+            return;
+        };
+        self.close_palette(window, cx);
+        window.dispatch_action(action, cx);
+    }
+
+    /// Move through and run the matching commands, before the input sees the keys
+    pub(super) fn on_palette_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.show_palette {
+            return;
+        }
+        let matches = self.palette_matches(cx);
+        match event.keystroke.key.as_str() {
+            "down" if !matches.is_empty() => {
+                self.palette_selected = (self.palette_selected + 1) % matches.len();
+            }
+            "up" if !matches.is_empty() => {
+                self.palette_selected = (self.palette_selected + matches.len() - 1) % matches.len();
+            }
+            "enter" => {
+                if let Some((index, _)) = matches.get(self.palette_selected) {
+                    self.run_palette_command(*index, window, cx);
+                }
+            }
+            "escape" => self.close_palette(window, cx),
+            _ => {
+                // Typing changes the matches, so start again from the best one
+                self.palette_selected = 0;
+                return;
+            }
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+}
+
+/// Render the command palette over the board while it is open
+pub(super) fn render_palette(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    if !board.show_palette {
+        return None;
+    }
+    let matches = board.palette_matches(cx);
+    let no_matches = matches.is_empty();
+    let selected_index = board.palette_selected.min(matches.len().saturating_sub(1));
+    let items = matches
+        .into_iter()
+        .enumerate()
+        .map(|(position, (index, name))| {
+            div()
+                .id(("palette-command", index))
+                .px_2()
+                .py_1()
+                .rounded_sm()
+                .text_sm()
+                .cursor_pointer()
+                .text_color(cx.theme().foreground)
+                .when(position == selected_index, |this| {
+                    this.bg(cx.theme().accent)
+                })
+                .hover(|style| style.bg(cx.theme().accent))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.run_palette_command(index, window, cx);
+                }))
+                .child(name)
+        });
+
+    Some(
+        v_flex()
+            .id("command-palette")
+            .absolute()
+            .top(gpui::px(48.))
+            .left_4()
+            .right_4()
+            .p_2()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .shadow_lg()
+            .child(
+                Input::new(&board.palette_input)
+                    .appearance(false)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded_md()
+                    .px_2()
+                    .py_1(),
+            )
+            .child(
+                v_flex()
+                    .id("command-palette-list")
+                    .max_h(gpui::px(320.))
+                    .overflow_y_scroll()
+                    .children(items)
+                    .when(no_matches, |this| {
+                        this.child(
+                            div()
+                                .px_2()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(board.i18n.t("palette_no_matches")),
+                        )
+                    }),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_commands() {
+        let names: Vec<String> = [
+            "Copy selected record",
+            "Delete selected record",
+            "Quit Ropy",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        assert_eq!(matching_commands("", &names), vec![0, 1, 2]);
+        assert_eq!(matching_commands("quit", &names), vec![2]);
+        assert_eq!(matching_commands("dlt", &names), vec![1]);
+        assert!(matching_commands("zzz", &names).is_empty());
+    }
+}
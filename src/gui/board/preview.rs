@@ -1,10 +1,23 @@
 /// Custom tooltip preview implementation that supports automatic line wrapping
+use crate::repository::models::ContentType;
 use gpui::{
-    AnyView, App, AppContext, IntoElement, ParentElement, Render, Styled, Window, div, img, px,
+    AnyElement, AnyView, App, AppContext, Bounds, IntoElement, ParentElement, Pixels, Point,
+    Render, Size, Styled, Window, WindowBounds, WindowHandle, WindowKind, WindowOptions, div, img,
+    prelude::{InteractiveElement, StatefulInteractiveElement},
+    px, size,
 };
 use gpui_component::ActiveTheme;
-use image::ImageReader;
-use std::path::PathBuf;
+use image::codecs::gif::GifDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, ImageFormat, ImageReader};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// Gap between the board and the preview window
+const PREVIEW_WINDOW_GAP: f32 = 8.0;
+/// Padding around the previewed content
+const PREVIEW_WINDOW_PADDING: f32 = 16.0;
 
 /// Create a tooltip preview that supports automatic line wrapping
 ///
@@ -74,15 +87,62 @@ pub fn image_tooltip(image_path: impl Into<String>, window: &mut Window, cx: &mu
     let max_height = (window_height * 0.9).min(px(400.0));
 
     let (width, height) = calculate_image_size(&image_path, max_width, max_height);
+    let animated = is_animated(Path::new(&image_path));
 
     cx.new(move |_cx| ImageTooltipView {
         image_path,
         width,
         height,
+        animated,
     })
     .into()
 }
 
+/// Whether an image file has more than one frame to play
+///
+/// Only GIF and WebP count, the animated formats GPUI decodes frame by frame.
+fn is_animated(path: &Path) -> bool {
+    let format = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .ok()
+        .and_then(|reader| reader.format());
+    let Ok(file) = File::open(path).map(BufReader::new) else {
+        return false;
+    };
+    match format {
+        Some(ImageFormat::Gif) => {
+            GifDecoder::new(file).is_ok_and(|decoder| decoder.into_frames().take(2).count() > 1)
+        }
+        Some(ImageFormat::WebP) => {
+            WebPDecoder::new(file).is_ok_and(|decoder| decoder.has_animation())
+        }
+        _ => false,
+    }
+}
+
+/// Image element of a preview
+///
+/// GPUI only plays an animation for an image with an element ID, where it keeps the current
+/// frame; static images skip that and are drawn once.
+fn preview_image(path: &str, animated: bool, width: Pixels, height: Pixels) -> AnyElement {
+    let image = img(PathBuf::from(path)).w(width).h(height);
+    if animated {
+        image.id("animated-preview").into_any_element()
+    } else {
+        image.into_any_element()
+    }
+}
+
+/// Whether an image file exists and starts with a readable image header
+///
+/// Only the header is read, so this is cheap enough to call before showing a preview. A
+/// file cut off further in passes, and then fails when GPUI decodes it in the background.
+pub(super) fn image_header_reads(path: &Path) -> bool {
+    ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .is_ok_and(|reader| reader.into_dimensions().is_ok())
+}
+
 fn calculate_image_size(
     path: &str,
     max_w: gpui::Pixels,
@@ -107,6 +167,7 @@ struct ImageTooltipView {
     image_path: String,
     width: gpui::Pixels,
     height: gpui::Pixels,
+    animated: bool,
 }
 
 impl Render for ImageTooltipView {
@@ -119,11 +180,190 @@ impl Render for ImageTooltipView {
                 .rounded_md()
                 .shadow_lg()
                 .p_2()
-                .child(
-                    img(PathBuf::from(&self.image_path))
-                        .w(self.width)
-                        .h(self.height),
-                ),
+                .child(preview_image(
+                    &self.image_path,
+                    self.animated,
+                    self.width,
+                    self.height,
+                )),
         )
     }
 }
+
+/// Larger preview rendered in its own window, so it is not limited by the board size
+pub struct PreviewWindow {
+    content_type: ContentType,
+    content: String,
+    content_size: Size<Pixels>,
+    /// Whether the previewed image is animated
+    animated: bool,
+}
+
+impl Render for PreviewWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut gpui::Context<Self>) -> impl IntoElement {
+        let container = div()
+            .id("preview-window")
+            .size_full()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .p(px(PREVIEW_WINDOW_PADDING / 2.0));
+
+        match self.content_type {
+            ContentType::Image => container.child(preview_image(
+                &self.content,
+                self.animated,
+                self.content_size.width,
+                self.content_size.height,
+            )),
+            _ => container
+                .overflow_y_scroll()
+                .text_sm()
+                .text_color(cx.theme().popover_foreground)
+                .line_height(gpui::relative(1.5))
+                .child(self.content.clone()),
+        }
+    }
+}
+
+/// Open a preview window next to `anchor`, sized up to `max_size` and kept within `display`
+pub fn open_preview_window(
+    content_type: ContentType,
+    content: String,
+    anchor: Bounds<Pixels>,
+    display: Bounds<Pixels>,
+    max_size: Size<Pixels>,
+    cx: &mut App,
+) -> Option<WindowHandle<PreviewWindow>> {
+    // A corrupt image has nothing to show, its row already says so
+    if content_type == ContentType::Image && !image_header_reads(Path::new(&content)) {
+        return None;
+    }
+    // The screen is the ceiling, whatever the configured maximum
+    let padding = px(PREVIEW_WINDOW_PADDING);
+    let max_size = size(
+        max_size.width.min(display.size.width * 0.9),
+        max_size.height.min(display.size.height * 0.9),
+    );
+    let content_size = match content_type {
+        ContentType::Image => calculate_image_size(
+            &content,
+            max_size.width - padding,
+            max_size.height - padding,
+        ),
+        _ => size(max_size.width - padding, max_size.height - padding),
+    };
+    let window_size = size(content_size.width + padding, content_size.height + padding);
+    let origin = preview_origin(anchor, display, window_size);
+    let animated = content_type == ContentType::Image && is_animated(Path::new(&content));
+
+    cx.open_window(
+        WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::new(origin, window_size))),
+            kind: WindowKind::PopUp,
+            titlebar: None,
+            // Keep focus on the board so it doesn't hide itself
+            focus: false,
+            is_movable: false,
+            ..Default::default()
+        },
+        |_, cx| {
+            cx.new(|_| PreviewWindow {
+                content_type,
+                content,
+                content_size,
+                animated,
+            })
+        },
+    )
+    .ok()
+}
+
+/// Place the preview to the right of the anchor, or to its left when it would leave the display
+fn preview_origin(
+    anchor: Bounds<Pixels>,
+    display: Bounds<Pixels>,
+    window_size: Size<Pixels>,
+) -> Point<Pixels> {
+    let gap = px(PREVIEW_WINDOW_GAP);
+    let right = anchor.origin.x + anchor.size.width + gap;
+    let x = if right + window_size.width <= display.origin.x + display.size.width {
+        right
+    } else {
+        (anchor.origin.x - gap - window_size.width).max(display.origin.x)
+    };
+    let max_y = (display.origin.y + display.size.height - window_size.height).max(display.origin.y);
+    let y = anchor.origin.y.clamp(display.origin.y, max_y);
+    Point::new(x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_header_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        let valid = dir.path().join("valid.png");
+        image::RgbaImage::new(4, 4).save(&valid).unwrap();
+        assert!(image_header_reads(&valid));
+
+        // A PNG cut off within its header
+        let bytes = std::fs::read(&valid).unwrap();
+        let truncated = dir.path().join("truncated.png");
+        std::fs::write(&truncated, &bytes[..12]).unwrap();
+        assert!(!image_header_reads(&truncated));
+
+        let garbage = dir.path().join("garbage.png");
+        std::fs::write(&garbage, b"not an image").unwrap();
+        assert!(!image_header_reads(&garbage));
+
+        assert!(!image_header_reads(&dir.path().join("missing.png")));
+    }
+
+    #[test]
+    fn test_is_animated() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, Frame, RgbaImage};
+
+        let dir = tempfile::tempdir().unwrap();
+        let write_gif = |name: &str, frames: usize| {
+            let path = dir.path().join(name);
+            let mut encoder = GifEncoder::new(File::create(&path).unwrap());
+            encoder
+                .encode_frames((0..frames).map(|i| {
+                    let image =
+                        RgbaImage::from_pixel(2, 2, image::Rgba([i as u8 * 100, 0, 0, 255]));
+                    Frame::from_parts(image, 0, 0, Delay::from_numer_denom_ms(100, 1))
+                }))
+                .unwrap();
+            path
+        };
+
+        assert!(is_animated(&write_gif("animated.gif", 3)));
+        assert!(!is_animated(&write_gif("still.gif", 1)));
+
+        let png = dir.path().join("still.png");
+        RgbaImage::new(2, 2).save(&png).unwrap();
+        assert!(!is_animated(&png));
+        assert!(!is_animated(&dir.path().join("missing.gif")));
+    }
+
+    fn bounds(x: f32, y: f32, width: f32, height: f32) -> Bounds<Pixels> {
+        Bounds::new(Point::new(px(x), px(y)), size(px(width), px(height)))
+    }
+
+    #[test]
+    fn test_preview_origin() {
+        let display = bounds(0., 0., 1920., 1080.);
+        let preview = size(px(800.), px(600.));
+
+        // Enough room on the right
+        let origin = preview_origin(bounds(100., 100., 400., 600.), display, preview);
+        assert_eq!(origin, Point::new(px(508.), px(100.)));
+
+        // Flipped to the left and clamped vertically
+        let origin = preview_origin(bounds(1400., 700., 400., 600.), display, preview);
+        assert_eq!(origin, Point::new(px(592.), px(480.)));
+    }
+}
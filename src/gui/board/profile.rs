@@ -0,0 +1,214 @@
+//! Switching between named profiles, each with its own settings and history
+
+use std::sync::Arc;
+
+use gpui::{Context, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::config::{AppTheme, Settings, profile};
+use crate::gui::hotkey::bindings_from_settings;
+use crate::gui::utils::{
+    anchored_bounds, board_window_size, move_window, rebuild_stale_index, set_always_on_top,
+};
+use crate::i18n::Language;
+use crate::repository::{ActiveRepository, ClipboardRepository, ClipboardStore};
+
+impl RopyBoard {
+    /// Share the repository slot also read by the capture task, so profile switches reach it
+    pub fn set_active_repository(&mut self, active_repository: ActiveRepository) {
+        self.active_repository = Some(active_repository);
+    }
+
+    /// Switch to the next profile, in the order shown in settings
+    pub(super) fn cycle_profile(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let profiles = profile::list_profiles();
+        if let Some(next) = profile::next_profile(&profiles, &profile::active_profile()).cloned() {
+            self.switch_profile(&next, window, cx);
+        }
+    }
+
+    /// Switch to the profile named in the settings input, creating it if needed
+    pub(super) fn create_profile(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self
+            .settings_profile_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+        if !profile::is_valid_profile_name(&name) {
+            self.show_toast(self.i18n.t("toast_profile_invalid"), ToastLevel::Error);
+            cx.notify();
+            return;
+        }
+        self.settings_profile_input.update(cx, |input, cx| {
+            input.set_value("", window, cx);
+        });
+        self.switch_profile(&name, window, cx);
+    }
+
+    /// Load another profile's settings and history, and apply them right away
+    pub(super) fn switch_profile(
+        &mut self,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if name == profile::active_profile() {
+            return;
+        }
+        // Write out the current profile before its paths are replaced
+        if let Some(ref slot) = self.active_repository
+            && let Some(repo) = slot.read().unwrap().as_ref()
+            && let Err(e) = repo.flush()
+        {
+            log::error!("Failed to flush clipboard history: {e}");
+        }
+        if let Err(e) = self.settings.read().unwrap().save() {
+            log::error!("Failed to save settings: {e}");
+        }
+
+        let previous = profile::active_profile();
+        profile::set_active_profile(name);
+        let repository = match ClipboardRepository::new(name) {
+            Ok(repo) => {
+                let repo = Arc::new(repo);
+                rebuild_stale_index(&repo);
+                repo
+            }
+            Err(e) => {
+                log::error!("Failed to open the history of profile {name}: {e}");
+                profile::set_active_profile(&previous);
+                self.show_toast(self.i18n.t("toast_profile_failed"), ToastLevel::Error);
+                cx.notify();
+                return;
+            }
+        };
+        let settings = Settings::load().unwrap_or_else(|e| {
+            log::warn!("Failed to load settings of profile {name}, using defaults: {e}");
+            Settings::default()
+        });
+        // Also creates the directory of a new profile, so it is listed from now on
+        if let Err(e) = settings.save() {
+            log::error!("Failed to save settings: {e}");
+        }
+        if let Err(e) = profile::save_last_profile(name) {
+            log::error!("Failed to remember the active profile: {e}");
+        }
+
+        if let Some(ref slot) = self.active_repository {
+            *slot.write().unwrap() = Some(repository.clone());
+        }
+        self.repository = Some(repository as Arc<dyn ClipboardStore>);
+        self.record_limit = settings.initial_record_limit();
+        *self.settings.write().unwrap() = settings;
+        // Private captures only live in memory and aren't tied to a profile's history
+        self.records
+            .lock()
+            .unwrap()
+            .retain(|record| self.privacy_mode.is_private(record.id));
+        self.reload_records();
+        self.selected_index = 0;
+        self.list_state.scroll_to_reveal_item(0);
+        self.apply_loaded_settings(window, cx);
+
+        let message = self
+            .i18n
+            .t("toast_profile_switched")
+            .replace("{name}", name);
+        self.show_toast(message, ToastLevel::Info);
+        cx.notify();
+    }
+
+    /// Apply freshly loaded settings to the running app
+    pub(super) fn apply_loaded_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (language, theme, colors, autostart, hotkey_bindings) = {
+            let settings = self.settings.read().unwrap();
+            (
+                settings.language,
+                settings.theme.clone(),
+                settings.appearance.custom_colors.clone(),
+                settings.autostart.enabled,
+                bindings_from_settings(&settings),
+            )
+        };
+
+        if let Some(tx) = &self.hotkey_tx {
+            let _ = tx.try_send(hotkey_bindings);
+        }
+
+        if let Err(e) = self.i18n.set_language(language) {
+            log::error!("Failed to set language: {e}");
+        }
+        self.selected_language = Language::all()
+            .iter()
+            .position(|&lang| lang == language)
+            .unwrap_or(0);
+        let search_placeholder = self.i18n.t("search_placeholder");
+        self.search_input.update(cx, |input, cx| {
+            input.set_placeholder(search_placeholder, window, cx);
+        });
+
+        self.selected_theme = match theme {
+            AppTheme::Light => 0,
+            AppTheme::Dark => 1,
+            AppTheme::System => 2,
+        };
+        crate::gui::app::set_app_theme(window, cx, &theme.get_theme(), &colors);
+
+        self.autostart_enabled = autostart;
+        if let Err(e) = self.sync_autostart_state() {
+            log::error!("Failed to sync auto-start state: {e}");
+            self.show_toast(self.i18n.t("toast_autostart_failed"), ToastLevel::Error);
+        }
+
+        self.apply_window_settings(window, cx);
+        self.reset_settings_inputs(window, cx);
+    }
+
+    /// Pin and place the window, and lay the list out again for the loaded density
+    fn apply_window_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (always_pinned, anchor) = {
+            let settings = self.settings.read().unwrap();
+            (
+                settings.behavior.always_pinned,
+                settings.appearance.window_anchor,
+            )
+        };
+        self.pinned = always_pinned;
+        set_always_on_top(window, cx, always_pinned);
+        if let Some(display) = window.display(cx) {
+            let bounds = anchored_bounds(anchor, display.visible_bounds(), board_window_size());
+            move_window(window, cx, bounds.origin);
+        }
+        // Row heights depend on the density
+        self.list_state.reset(self.filtered_records.len());
+    }
+
+    /// Show the loaded values in the settings inputs, discarding unsaved edits
+    fn reset_settings_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (activation_key, max_history, min_text_chars, ignored_formats) = {
+            let settings = self.settings.read().unwrap();
+            (
+                settings.hotkey.activation_key.clone(),
+                settings.storage.max_history_records,
+                settings.storage.min_text_chars,
+                settings.storage.ignored_formats.join(", "),
+            )
+        };
+        self.settings_activation_key_input.update(cx, |input, cx| {
+            input.set_placeholder(activation_key, window, cx);
+            input.set_value("", window, cx);
+        });
+        self.settings_max_history_input.update(cx, |input, cx| {
+            input.set_placeholder(max_history.to_string(), window, cx);
+            input.set_value("", window, cx);
+        });
+        self.settings_min_text_chars_input.update(cx, |input, cx| {
+            input.set_placeholder(min_text_chars.to_string(), window, cx);
+            input.set_value("", window, cx);
+        });
+        self.settings_ignored_formats_input.update(cx, |input, cx| {
+            input.set_value(ignored_formats, window, cx);
+        });
+    }
+}
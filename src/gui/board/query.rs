@@ -0,0 +1,172 @@
+//! The query the records are filtered by: the live search box, or the last one submitted
+
+use gpui::{Context, Focusable, KeyDownEvent, Window};
+
+use super::RopyBoard;
+use crate::config::SearchEnter;
+use crate::repository::models::{
+    ClipboardRecord, ContentFilter, RecordFlag, SearchScope, sort_favorites,
+};
+use crate::repository::ranking::rank_records;
+
+/// What narrows the records matching the query down to the rows shown, and their order
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(super) struct ListFilters {
+    pub content_filter: ContentFilter,
+    pub favorites_only: bool,
+    pub flag_filter: Option<RecordFlag>,
+    pub gallery_mode: bool,
+    pub scope: SearchScope,
+    /// Keep search results newest first instead of ranking them
+    pub chronological: bool,
+}
+
+/// What the displayed rows were last computed from
+pub(super) struct ListKey {
+    pub query: String,
+    pub filters: ListFilters,
+    /// See `ClipboardStore::generation`
+    pub generation: u64,
+    /// Loaded records, which also hold the private captures the repository doesn't see
+    pub page: Vec<ClipboardRecord>,
+}
+
+/// Rows shown for the records matching `query`, in the order they are displayed
+///
+/// The number keys and Enter index these rows, not the history.
+pub(super) fn displayed_records(
+    mut records: Vec<ClipboardRecord>,
+    query: &str,
+    filters: ListFilters,
+) -> Vec<ClipboardRecord> {
+    records.retain(|record| {
+        filters.content_filter.matches(&record.content_type)
+            && (!filters.favorites_only || record.favorite)
+            && filters
+                .flag_filter
+                .is_none_or(|flag| record.flag == Some(flag))
+            && (!filters.gallery_mode || ContentFilter::Image.matches(&record.content_type))
+    });
+    if !query.is_empty() && !filters.chronological {
+        rank_records(&mut records, query, filters.scope, chrono::Local::now());
+    } else if filters.favorites_only {
+        sort_favorites(&mut records);
+    }
+    records
+}
+
+/// Query to filter by, given the search box contents and the last query submitted with Enter
+///
+/// An empty search box always applies right away, so clearing the search needs no Enter.
+fn effective_query<'a>(live: &'a str, submitted: &'a str, search_on_enter: bool) -> &'a str {
+    if !search_on_enter || live.trim().is_empty() {
+        live
+    } else {
+        submitted
+    }
+}
+
+/// Row that Enter in the search box confirms, if any
+fn search_enter_index(mode: SearchEnter, result_count: usize) -> Option<usize> {
+    match mode {
+        SearchEnter::ConfirmTop => (result_count > 0).then_some(0),
+        SearchEnter::Blur => None,
+    }
+}
+
+impl RopyBoard {
+    /// Filters currently applied to the list
+    pub(super) fn list_filters(&self) -> ListFilters {
+        let behavior = &self.settings.read().unwrap().behavior;
+        ListFilters {
+            content_filter: self.content_filter,
+            favorites_only: self.favorites_only,
+            flag_filter: self.flag_filter,
+            gallery_mode: self.gallery_mode,
+            scope: behavior.search_scope,
+            chronological: behavior.chronological_search,
+        }
+    }
+
+    /// Query the records are currently filtered by
+    pub(super) fn active_query(&self, cx: &Context<Self>) -> String {
+        let search_on_enter = self.settings.read().unwrap().behavior.search_on_enter;
+        let live = self.search_input.read(cx).value();
+        effective_query(&live, &self.submitted_query, search_on_enter).to_string()
+    }
+
+    /// Apply the search box contents when searching waits for Enter
+    ///
+    /// Returns whether Enter was used up by submitting a changed query, rather than left to
+    /// confirm the selection.
+    pub(super) fn submit_search(&mut self, window: &Window, cx: &mut Context<Self>) -> bool {
+        let search_focused = window
+            .focused(cx)
+            .is_some_and(|handle| handle == self.search_input.focus_handle(cx));
+        if !search_focused || !self.settings.read().unwrap().behavior.search_on_enter {
+            return false;
+        }
+        let live = self.search_input.read(cx).value().to_string();
+        if live == self.submitted_query {
+            return false;
+        }
+        self.submitted_query = live;
+        self.selected_index = 0;
+        cx.notify();
+        true
+    }
+
+    /// Handle Enter in the search box here, since whether the global binding sees it depends
+    /// on how the input routes the key
+    pub(super) fn on_search_enter_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let search_focused = window
+            .focused(cx)
+            .is_some_and(|handle| handle == self.search_input.focus_handle(cx));
+        if !search_focused || event.keystroke.key != "enter" || event.keystroke.modifiers.modified()
+        {
+            return;
+        }
+        cx.stop_propagation();
+        if self.submit_search(window, cx) {
+            return;
+        }
+        let mode = self.settings.read().unwrap().behavior.search_enter;
+        match search_enter_index(mode, self.filtered_records.len()) {
+            Some(index) => self.confirm_record(window, cx, index),
+            None => {
+                window.focus(&self.focus_handle);
+                cx.notify();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_query() {
+        // Live filtering
+        assert_eq!(effective_query("rust", "", false), "rust");
+        // Waiting for Enter keeps the submitted query while typing
+        assert_eq!(effective_query("rus", "go", true), "go");
+        assert_eq!(effective_query("rust", "rust", true), "rust");
+        // Clearing the box needs no Enter
+        assert_eq!(effective_query("", "go", true), "");
+        assert_eq!(effective_query("  ", "go", true), "  ");
+    }
+
+    #[test]
+    fn test_search_enter_index() {
+        assert_eq!(search_enter_index(SearchEnter::ConfirmTop, 3), Some(0));
+        assert_eq!(search_enter_index(SearchEnter::ConfirmTop, 0), None);
+        assert_eq!(search_enter_index(SearchEnter::Blur, 3), None);
+        assert_eq!(search_enter_index(SearchEnter::Blur, 0), None);
+    }
+}
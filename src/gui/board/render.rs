@@ -1,41 +1,100 @@
+use crate::clipboard::thumbnail_path;
+use crate::config::profile::{DEFAULT_PROFILE, active_profile};
 use crate::gui::utils::set_always_on_top;
 #[cfg(target_os = "windows")]
 use crate::gui::utils::start_window_drag;
+use crate::i18n::I18n;
 use crate::repository::ClipboardRecord;
-use crate::repository::models::ContentType;
+use crate::repository::models::{ContentFilter, ContentType, next_flag_filter};
+use crate::repository::url::is_url;
 use gpui::{
-    Context, Entity, anchored, deferred, div, img, list,
-    prelude::{InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement, Styled},
+    Animation, AnimationExt, AppContext, Context, Entity, Img, MouseButton, Render, StyledImage,
+    WeakEntity, Window, div, ease_in_out, img, list,
+    prelude::{
+        FluentBuilder, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement,
+        Styled,
+    },
     px,
 };
 use gpui_component::Icon;
 use gpui_component::button::{Button, ButtonVariants};
 use gpui_component::input::{Input, InputState};
+use gpui_component::menu::ContextMenuExt;
+use gpui_component::scroll::Scrollbar;
 use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
-use regex::Regex;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::sync::OnceLock;
-
-use super::{RopyBoard, preview};
-
-fn get_hex_color(content: &str) -> Option<gpui::Rgba> {
-    static HEX_REGEX: OnceLock<Regex> = OnceLock::new();
-    let regex =
-        HEX_REGEX.get_or_init(|| Regex::new(r"^#([A-Fa-f0-9]{6}|[A-Fa-f0-9]{3})$").unwrap());
-
-    if regex.is_match(content) {
-        let hex = content.trim_start_matches('#');
-        let value = if hex.len() == 3 {
-            let r = u8::from_str_radix(&hex[0..1], 16).ok()?;
-            let g = u8::from_str_radix(&hex[1..2], 16).ok()?;
-            let b = u8::from_str_radix(&hex[2..3], 16).ok()?;
-            ((r as u32 * 17) << 16) | ((g as u32 * 17) << 8) | (b as u32 * 17)
-        } else {
-            u32::from_str_radix(hex, 16).ok()?
-        };
-        Some(gpui::rgb(value))
+use std::rc::Rc;
+use std::sync::Arc;
+
+use super::actions::{CompareRecords, QUICK_PASTE_COUNT, ToggleStickyClipboard};
+use super::color::{ColorFormat, Rgb};
+use super::feedback::COPY_FLASH_DURATION;
+use super::flag::{flag_color, flag_menu};
+use super::{RopyBoard, ToastLevel, preview};
+
+/// Text longer than this many bytes is collapsed in the list
+const LARGE_TEXT_BYTES: usize = 256 * 1024;
+/// Unbroken base64-looking runs at least this long are treated as encoded binary
+const ENCODED_BLOB_CHARS: usize = 1024;
+/// Number of leading characters inspected when looking for binary content
+const BINARY_SAMPLE_CHARS: usize = 4096;
+
+/// How text content should be shown in the list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TextKind {
+    Normal,
+    /// Too long to render without slowing down the list
+    Large,
+    /// Mostly control characters, or an encoded blob such as base64
+    Binary,
+}
+
+/// Detect text that would bog down rendering or is unreadable as text.
+///
+/// Only a bounded prefix is inspected so classifying a huge blob stays cheap.
+pub(super) fn classify_text(content: &str) -> TextKind {
+    let sample: Vec<char> = content.chars().take(BINARY_SAMPLE_CHARS).collect();
+    let control = sample
+        .iter()
+        .filter(|&&c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        .count();
+    if control * 10 > sample.len() {
+        return TextKind::Binary;
+    }
+
+    let is_encoded_char = |c: &char| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '=');
+    if sample.len() >= ENCODED_BLOB_CHARS && sample.iter().all(is_encoded_char) {
+        return TextKind::Binary;
+    }
+
+    if content.len() > LARGE_TEXT_BYTES {
+        TextKind::Large
     } else {
-        None
+        TextKind::Normal
+    }
+}
+
+/// Human-readable size of `bytes`, e.g. `1.5 MB`
+fn format_size(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= KB * KB {
+        format!("{:.1} MB", bytes / (KB * KB))
+    } else {
+        format!("{:.0} KB", bytes / KB)
+    }
+}
+
+/// Icon asset shown at the start of a record row
+fn record_icon(record: &ClipboardRecord) -> &'static str {
+    match record.content_type {
+        ContentType::Text if Rgb::from_hex(&record.content).is_some() => "type-color.svg",
+        ContentType::Text if is_url(&record.content) => "type-link.svg",
+        ContentType::Text => "type-text.svg",
+        ContentType::Image | ContentType::Svg => "type-image.svg",
+        ContentType::FilePath => "type-file.svg",
     }
 }
 
@@ -48,23 +107,102 @@ pub(super) fn create_clear_button(
         .ghost()
         .icon(Icon::empty().path("clear-all.svg"))
         .tooltip(board.i18n.t("clear_all"))
-        .on_click(cx.listener(|this, _, _, _| {
+        .on_click(cx.listener(|this, _, _, cx| {
             this.clear_history();
             this.clear_last_copy_state();
+            cx.notify();
         }))
         .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation())
 }
 
+/// Characters of a text record shown in the list before it is cut off
+const DISPLAY_CHARS: usize = 100;
+
 /// Format clipboard content for display (truncate if too long)
-pub(super) fn format_clipboard_content(record: &ClipboardRecord) -> String {
-    if record.content.chars().count() > 100 {
+///
+/// With `collapse_whitespace`, runs of whitespace are shown as a single space. This only
+/// affects the list; the record content itself is never changed.
+pub(super) fn format_clipboard_content(
+    record: &ClipboardRecord,
+    collapse_whitespace: bool,
+) -> String {
+    let content = if collapse_whitespace {
+        record
+            .content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        record.content.clone()
+    };
+    if content.chars().count() > DISPLAY_CHARS {
         format!(
             "{}...",
-            record.content.chars().take(100).collect::<String>()
+            content.chars().take(DISPLAY_CHARS).collect::<String>()
         )
+    } else {
+        content
+    }
+}
+
+/// Characters of a record left out of its list display, or 0 when it is shown in full
+///
+/// Counted against the full content, which is what copying sends.
+pub(super) fn hidden_char_count(record: &ClipboardRecord, collapse_whitespace: bool) -> usize {
+    let shown = if collapse_whitespace {
+        record
+            .content
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
     } else {
         record.content.clone()
+    };
+    if shown.chars().count() > DISPLAY_CHARS {
+        record.content.chars().count() - DISPLAY_CHARS
+    } else {
+        0
+    }
+}
+
+/// Format a record as its content followed by a localized metadata footer.
+/// Metadata fields that are missing or empty are skipped.
+pub(super) fn format_record_with_metadata(record: &ClipboardRecord, i18n: &I18n) -> String {
+    let mut metadata = vec![i18n.t("metadata_captured").replace(
+        "{time}",
+        &record.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+    )];
+    if let Some(source) = record
+        .source_app
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        metadata.push(i18n.t("metadata_source").replace("{source}", source));
     }
+    format!("{}\n\n[{}]", record.content, metadata.join(" "))
+}
+
+/// Localized one-line description of a record for assistive technology: its type and
+/// its position in the list
+///
+/// The content is left out on purpose, since window titles are visible to other apps,
+/// screen recorders and window switchers.
+pub(super) fn accessible_label(
+    record: &ClipboardRecord,
+    position: usize,
+    count: usize,
+    i18n: &I18n,
+) -> String {
+    let type_key = match record.content_type {
+        ContentType::Text => "a11y_type_text",
+        ContentType::Image | ContentType::Svg => "a11y_type_image",
+        ContentType::FilePath => "a11y_type_file",
+    };
+    i18n.t("a11y_record")
+        .replace("{type}", &i18n.t(type_key))
+        .replace("{position}", &position.to_string())
+        .replace("{count}", &count.to_string())
 }
 
 /// Render the header section with title and settings/clear buttons
@@ -75,7 +213,13 @@ pub fn render_header(board: &RopyBoard, cx: &mut Context<'_, RopyBoard>) -> impl
     } else {
         board.i18n.t("pin")
     };
-    let header = h_flex().justify_between().items_center().mb_4().pt_4();
+    let active_profile = active_profile();
+    let header = h_flex().justify_between().items_center();
+    let header = if board.is_compact() {
+        header.mb_2().pt_2()
+    } else {
+        header.mb_4().pt_4()
+    };
 
     #[cfg(target_os = "windows")]
     let header = header.on_mouse_down(gpui::MouseButton::Left, |_, window, cx| {
@@ -84,11 +228,37 @@ pub fn render_header(board: &RopyBoard, cx: &mut Context<'_, RopyBoard>) -> impl
 
     header
         .child(
-            div()
-                .text_lg()
-                .text_color(cx.theme().foreground)
-                .font_weight(gpui::FontWeight::BOLD)
-                .child(board.i18n.t("app_name")),
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    div()
+                        .text_lg()
+                        .text_color(cx.theme().foreground)
+                        .font_weight(gpui::FontWeight::BOLD)
+                        .child(board.i18n.t("app_name")),
+                )
+                .when(active_profile != DEFAULT_PROFILE, |this| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(active_profile.clone()),
+                    )
+                })
+                .when(board.privacy_mode.is_active(), |this| {
+                    this.child(
+                        Button::new("privacy-badge")
+                            .xsmall()
+                            .danger()
+                            .label(board.i18n.t("privacy_badge"))
+                            .tooltip(board.i18n.t("privacy_mode"))
+                            .on_click(cx.listener(|this, _, _, cx| this.toggle_privacy_mode(cx)))
+                            .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| {
+                                cx.stop_propagation()
+                            }),
+                    )
+                }),
         )
         .child(
             h_flex()
@@ -112,6 +282,17 @@ pub fn render_header(board: &RopyBoard, cx: &mut Context<'_, RopyBoard>) -> impl
                         cx.listener(|_, _, _, cx| cx.stop_propagation()),
                     ),
                 )
+                .child(
+                    if board.append_mode.is_active() {
+                        Button::new("append-button").primary()
+                    } else {
+                        Button::new("append-button").ghost()
+                    }
+                    .icon(Icon::empty().path("append.svg"))
+                    .tooltip(board.i18n.t("append_mode"))
+                    .on_click(cx.listener(|this, _, _, cx| this.toggle_append_mode(cx)))
+                    .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+                )
                 .child(
                     Button::new("about-button")
                         .ghost()
@@ -124,11 +305,35 @@ pub fn render_header(board: &RopyBoard, cx: &mut Context<'_, RopyBoard>) -> impl
                         }))
                         .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
                 )
+                .child(
+                    Button::new("help-button")
+                        .ghost()
+                        .icon(Icon::empty().path("help.svg"))
+                        .tooltip(board.i18n.t("help_title"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.show_help = true;
+                            window.focus(&this.focus_handle);
+                            cx.notify();
+                        }))
+                        .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+                )
+                .child(
+                    Button::new("trash-button")
+                        .ghost()
+                        .icon(Icon::empty().path("trash.svg"))
+                        .tooltip(board.i18n.t("trash_title"))
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.open_trash();
+                            window.focus(&this.focus_handle);
+                            cx.notify();
+                        }))
+                        .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+                )
                 .child(
                     Button::new("settings-button")
                         .ghost()
                         .icon(Icon::empty().path("settings.svg"))
-                        .tooltip("Settings")
+                        .tooltip(board.i18n.t("settings_tooltip"))
                         .on_click(cx.listener(|this, _, window, cx| {
                             this.show_settings = true;
                             window.focus(&this.focus_handle);
@@ -143,71 +348,274 @@ pub fn render_header(board: &RopyBoard, cx: &mut Context<'_, RopyBoard>) -> impl
 /// Render the search input section
 pub(super) fn render_search_input(
     search_input: &Entity<InputState>,
+    compact: bool,
     cx: &mut Context<'_, RopyBoard>,
 ) -> impl IntoElement {
-    v_flex().w_full().mb_4().child(
-        Input::new(search_input)
-            .appearance(false)
-            .border_1()
-            .border_color(cx.theme().border)
-            .rounded_md()
-            .px_3()
-            .py_2(),
+    let input = Input::new(search_input)
+        .appearance(false)
+        .border_1()
+        .border_color(cx.theme().border)
+        .rounded_md();
+    if compact {
+        v_flex().w_full().mb_2().child(input.px_2().py_1())
+    } else {
+        v_flex().w_full().mb_4().child(input.px_3().py_2())
+    }
+}
+
+/// Image element of an image record: its thumbnail, else the original, else `broken`
+///
+/// GPUI reads and decodes the files in the background and moves on to the fallback when
+/// one fails, so rendering never touches the files itself.
+pub(super) fn record_image(
+    record: &ClipboardRecord,
+    style: impl Fn(Img) -> Img + 'static,
+    broken: impl Fn() -> gpui::AnyElement + 'static,
+) -> Img {
+    let original = PathBuf::from(&record.content);
+    let thumbnail = thumbnail_path(&original);
+    let style = Rc::new(style);
+    let broken = Rc::new(broken);
+    let original_style = style.clone();
+    style(img(thumbnail)).with_fallback(move || {
+        let broken = broken.clone();
+        original_style(img(original.clone()))
+            .with_fallback(move || broken())
+            .into_any_element()
+    })
+}
+
+/// Render an image record from its thumbnail, or a placeholder when the file can't be decoded
+///
+/// The row stays clickable and deletable either way.
+fn render_image_record(
+    cx: &mut gpui::App,
+    record: &ClipboardRecord,
+    compact: bool,
+    image_height: f32,
+    broken_label: &str,
+) -> gpui::AnyElement {
+    let max_height = if compact {
+        image_height * 0.6
+    } else {
+        image_height
+    };
+    let color = cx.theme().muted_foreground;
+    let broken_label = broken_label.to_string();
+    record_image(
+        record,
+        move |image| image.max_h(px(max_height)),
+        move || {
+            div()
+                .text_sm()
+                .italic()
+                .text_color(color)
+                .child(broken_label.clone())
+                .into_any_element()
+        },
     )
+    .into_any_element()
 }
 
-fn render_image_record(record: &ClipboardRecord) -> gpui::AnyElement {
-    let path = PathBuf::from(record.content.clone());
-    let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
-    let thumb_name = format!("{file_stem}_thumb.png");
-    let thumb_path = path.parent().unwrap_or(&path).join(thumb_name);
+/// Render an SVG record from its markup at list size, or as its markup when it can't be drawn
+/// Images of the SVG records shown, built once per record instead of on every frame
+#[derive(Clone, Default)]
+pub(super) struct SvgImages(Rc<RefCell<HashMap<(u64, Option<u64>), Arc<gpui::Image>>>>);
+
+impl SvgImages {
+    /// Image of an SVG record's markup
+    pub fn get(&self, record: &ClipboardRecord) -> Arc<gpui::Image> {
+        self.0
+            .borrow_mut()
+            .entry((record.id, record.content_hash))
+            .or_insert_with(|| {
+                Arc::new(gpui::Image::from_bytes(
+                    gpui::ImageFormat::Svg,
+                    record.content.clone().into_bytes(),
+                ))
+            })
+            .clone()
+    }
+
+    /// Drop the images of records that are no longer shown
+    pub fn retain_shown(&self, records: &[ClipboardRecord]) {
+        let shown: HashSet<u64> = records.iter().map(|record| record.id).collect();
+        self.0.borrow_mut().retain(|(id, _), _| shown.contains(id));
+    }
+}
 
-    // Use thumbnail if exists, otherwise fallback to original
-    let display_path = if thumb_path.exists() {
-        thumb_path
+fn render_svg_record(
+    record: &ClipboardRecord,
+    svg_images: &SvgImages,
+    compact: bool,
+    image_height: f32,
+    collapse_whitespace: bool,
+) -> gpui::AnyElement {
+    let max_height = if compact {
+        image_height * 0.6
     } else {
-        path
+        image_height
     };
-    img(display_path).max_h(px(100.0)).into_any_element()
+    let source = format_clipboard_content(record, collapse_whitespace);
+    img(svg_images.get(record))
+        .max_h(px(max_height))
+        .with_fallback(move || {
+            div()
+                .text_sm()
+                .line_height(gpui::relative(1.5))
+                .child(source.clone())
+                .into_any_element()
+        })
+        .into_any_element()
 }
 
-fn render_text_record(cx: &mut gpui::App, record: &ClipboardRecord) -> gpui::AnyElement {
-    let display_content = format_clipboard_content(record);
-    let hex_color = get_hex_color(&record.content);
+fn render_text_record(
+    cx: &mut gpui::App,
+    record: &ClipboardRecord,
+    compact: bool,
+    collapse_whitespace: bool,
+    color_formats_open: bool,
+    more_chars_label: &str,
+    view: WeakEntity<RopyBoard>,
+) -> gpui::AnyElement {
+    let display_content = format_clipboard_content(record, collapse_whitespace);
+    let hidden_chars = hidden_char_count(record, collapse_whitespace);
+    let hex_color = Rgb::from_hex(&record.content).map(|color| gpui::rgb(color.to_u32()));
 
-    let text_el = div()
-        .text_sm()
+    let text_el = div();
+    let text_el = if compact {
+        text_el.text_xs()
+    } else {
+        text_el.text_sm()
+    };
+    let text_el = text_el
         .text_color(cx.theme().secondary_foreground)
         .line_height(gpui::relative(1.5))
-        .child(display_content);
-
-    if let Some(color) = hex_color {
-        h_flex()
-            .items_center()
-            .gap_2()
-            .child(
+        .child(display_content)
+        .when(hidden_chars > 0, |this| {
+            this.child(
                 div()
-                    .w_4()
-                    .h_4()
-                    .rounded_sm()
-                    .bg(color)
-                    .border_1()
-                    .border_color(cx.theme().border),
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(more_chars_label.replace("{count}", &hidden_chars.to_string())),
             )
-            .child(text_el)
-            .into_any_element()
+        });
+
+    if let Some(color) = hex_color {
+        let record_id = record.id;
+        let view_toggle = view.clone();
+        let swatch = div()
+            .id(("color-swatch", record_id as usize))
+            .w_4()
+            .h_4()
+            .flex_shrink_0()
+            .rounded_sm()
+            .bg(color)
+            .border_1()
+            .border_color(cx.theme().border)
+            .cursor_pointer()
+            .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .on_click(move |_event, _window, cx| {
+                // Don't also confirm the record
+                cx.stop_propagation();
+                view_toggle
+                    .update(cx, |this, cx| {
+                        this.color_formats_open =
+                            (this.color_formats_open != Some(record_id)).then_some(record_id);
+                        cx.notify();
+                    })
+                    .ok();
+            });
+        let row = h_flex().items_center().gap_2().child(swatch).child(text_el);
+
+        match Rgb::from_hex(&record.content).filter(|_| color_formats_open) {
+            Some(rgb) => v_flex()
+                .gap_1()
+                .child(row)
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .children(ColorFormat::ALL.into_iter().enumerate().map(
+                            |(index, format)| {
+                                let value = rgb.copy_text(&record.content, format);
+                                let view = view.clone();
+                                Button::new(("color-format", index))
+                                    .xsmall()
+                                    .ghost()
+                                    .label(format.label())
+                                    .tooltip(value.clone())
+                                    .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| {
+                                        cx.stop_propagation()
+                                    })
+                                    .on_click(move |_event, _window, cx| {
+                                        cx.stop_propagation();
+                                        view.update(cx, |this, cx| {
+                                            this.copy_to_clipboard(&value, &ContentType::Text);
+                                            this.color_formats_open = None;
+                                            this.show_toast(
+                                                this.i18n.t("message_copied"),
+                                                ToastLevel::Info,
+                                            );
+                                            cx.notify();
+                                        })
+                                        .ok();
+                                    })
+                            },
+                        )),
+                )
+                .into_any_element(),
+            None => row.into_any_element(),
+        }
     } else {
         text_el.into_any_element()
     }
 }
 
-fn create_preview(
+/// Placeholder shown instead of large or binary-looking text, with a "show anyway" button
+fn render_collapsed_text(
+    cx: &mut gpui::App,
+    index: usize,
+    message: String,
+    show_label: String,
+    on_show: impl Fn(&mut gpui::Window, &mut gpui::App) + 'static,
+) -> gpui::AnyElement {
+    h_flex()
+        .items_center()
+        .gap_2()
+        .child(
+            div()
+                .text_xs()
+                .italic()
+                .text_color(cx.theme().muted_foreground)
+                .child(message),
+        )
+        .child(
+            Button::new(("show-text-btn", index))
+                .xsmall()
+                .ghost()
+                .label(show_label)
+                .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation())
+                .on_click(move |_event, window, cx| {
+                    cx.stop_propagation();
+                    on_show(window, cx);
+                }),
+        )
+        .into_any_element()
+}
+
+pub(super) fn create_preview(
     content_type: &ContentType,
     record_content: &str,
+    broken_image: &str,
     window: &mut gpui::Window,
     cx: &mut gpui::App,
 ) -> gpui::AnyView {
     match content_type {
+        ContentType::Image
+            if !preview::image_header_reads(std::path::Path::new(record_content)) =>
+        {
+            preview::simple_tooltip(broken_image, window, cx)
+        }
         ContentType::Image => preview::image_tooltip(record_content, window, cx),
         _ => {
             let content = if record_content.len() > 800 {
@@ -220,6 +628,159 @@ fn create_preview(
     }
 }
 
+/// Locale key of a content filter's tab label
+pub(super) fn filter_label_key(filter: ContentFilter) -> &'static str {
+    match filter {
+        ContentFilter::All => "filter_all",
+        ContentFilter::Text => "content_type_text",
+        ContentFilter::Image => "content_type_image",
+        ContentFilter::File => "content_type_file",
+    }
+}
+
+/// Render the content type tabs and the favorites-only toggle
+pub(super) fn render_filter_tabs(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> impl IntoElement {
+    let tabs = ContentFilter::ALL
+        .into_iter()
+        .enumerate()
+        .map(|(index, filter)| {
+            let label = board.i18n.t(filter_label_key(filter));
+            let button = Button::new(("filter-tab", index)).xsmall().label(label);
+            let button = if board.content_filter == filter {
+                button.primary()
+            } else {
+                button.ghost()
+            };
+            button.on_click(cx.listener(move |this, _, _, cx| {
+                this.content_filter = filter;
+                this.selected_index = 0;
+                cx.notify();
+            }))
+        });
+
+    let flag_dot = div().size_2().rounded_full();
+    let flag_dot = match board.flag_filter {
+        Some(flag) => flag_dot.bg(flag_color(flag)),
+        None => flag_dot
+            .border_1()
+            .border_color(cx.theme().muted_foreground),
+    };
+    let favorites_icon = if board.favorites_only {
+        "star-filled.svg"
+    } else {
+        "star.svg"
+    };
+    h_flex()
+        .w_full()
+        .justify_between()
+        .items_center()
+        .mb_2()
+        .child(h_flex().gap_1().children(tabs))
+        .child(
+            h_flex()
+                .gap_1()
+                .child({
+                    let button = Button::new("gallery-button")
+                        .xsmall()
+                        .icon(Icon::empty().path("type-image.svg"))
+                        .tooltip(board.i18n.t("filter_gallery"));
+                    let button = if board.gallery_mode {
+                        button.primary()
+                    } else {
+                        button.ghost()
+                    };
+                    button.on_click(cx.listener(|this, _, _, cx| this.toggle_gallery(cx)))
+                })
+                .child(
+                    Button::new("flag-filter-button")
+                        .xsmall()
+                        .ghost()
+                        .child(flag_dot)
+                        .tooltip(board.i18n.t("filter_flag"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.flag_filter = next_flag_filter(this.flag_filter);
+                            this.selected_index = 0;
+                            cx.notify();
+                        })),
+                )
+                .child(
+                    Button::new("favorites-only-button")
+                        .xsmall()
+                        .ghost()
+                        .icon(Icon::empty().path(favorites_icon))
+                        .tooltip(board.i18n.t("filter_favorites_only"))
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.favorites_only = !this.favorites_only;
+                            this.selected_index = 0;
+                            cx.notify();
+                        })),
+                ),
+        )
+}
+
+/// Render a warning for hotkeys that failed to register, with a shortcut to the settings
+pub(super) fn render_hotkey_warning(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    if board.failed_hotkeys.is_empty() {
+        return None;
+    }
+    let message = board
+        .i18n
+        .t("hotkey_in_use")
+        .replace("{hotkey}", &board.failed_hotkeys.join(", "));
+    Some(
+        h_flex()
+            .w_full()
+            .justify_between()
+            .items_center()
+            .gap_2()
+            .mb_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().danger)
+            .child(div().text_xs().text_color(cx.theme().danger).child(message))
+            .child(
+                Button::new("hotkey-warning-settings")
+                    .xsmall()
+                    .ghost()
+                    .label(board.i18n.t("hotkey_choose_another"))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.show_settings = true;
+                        cx.notify();
+                    })),
+            ),
+    )
+}
+
+/// Favorite record being dragged to a new position in the favorites-only list
+#[derive(Clone)]
+pub(super) struct DraggedFavorite {
+    id: u64,
+    label: String,
+}
+
+impl Render for DraggedFavorite {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().accent)
+            .text_xs()
+            .text_color(cx.theme().foreground)
+            .child(self.label.clone())
+    }
+}
+
 impl RopyBoard {
     /// Render the scrollable list of clipboard records
     pub fn render_records_list(&self, context: &mut Context<'_, RopyBoard>) -> impl IntoElement {
@@ -227,81 +788,251 @@ impl RopyBoard {
         let list_state = self.list_state.clone();
         let selected_index = self.selected_index;
         let show_preview = self.show_preview;
+        let copy_path_label = self.i18n.t("copy_path");
+        let copy_path_tooltip = self.i18n.t("copy_path_tooltip");
+        let reveal_label = self.i18n.t("reveal");
+        let reveal_tooltip = self.i18n.t("reveal_tooltip");
+        let pending_delete = self.pending_delete;
+        let delete_confirm_hint = self.i18n.t("delete_confirm_hint");
+        let delete_tooltip = self.i18n.t("delete_tooltip");
+        let favorite_tooltip = self.i18n.t("favorite_tooltip");
+        let note_tooltip = self.i18n.t("note_tooltip");
+        let unknown_content = self.i18n.t("unknown_content");
+        let broken_image = self.i18n.t("image_broken");
+        let large_text_label = self.i18n.t("collapsed_large_text");
+        let binary_text_label = self.i18n.t("collapsed_binary_text");
+        let show_anyway_label = self.i18n.t("show_anyway");
+        let more_chars_label = self.i18n.t("more_chars");
+        let revealed_texts = self.revealed_texts.clone();
+        let show_content_hash = self.settings.read().unwrap().behavior.show_content_hash;
+        let compact = self.is_compact();
+        let collapse_whitespace = self.settings.read().unwrap().appearance.collapse_whitespace;
+        let image_height = self.settings.read().unwrap().appearance.list_image_height();
+        let color_formats_open = self.color_formats_open;
+        let copy_flash = self.copy_flash.clone();
+        let svg_images = self.svg_images.clone();
+        // Dragging only makes sense where favorites are shown in their manual order
+        let drag_favorites = self.favorites_only && self.active_query(context).is_empty();
+        let i18n = self.i18n.clone();
+        let sticky_id = self.sticky.pinned_id();
+        let compare_base = self.compare_base.as_ref().map(|record| record.id);
         let view = context.weak_entity();
-        list(list_state, move |index, window, cx| {
+        let record_list = list(list_state, move |index, _window, cx| {
             let record = &records[index];
             let record_id = record.id;
             let is_selected = index == selected_index;
             let content_type = record.content_type.clone();
             let view_click = view.clone();
             let view_delete = view.clone();
+            let view_copy_path = view.clone();
+            let view_copy_time = view.clone();
+            let view_reveal = view.clone();
+            let view_show_text = view.clone();
+            let view_favorite = view.clone();
+            let view_note = view.clone();
+            let view_color = view.clone();
+            let view_drop = view.clone();
+            let view_menu = view.clone();
+            let menu_i18n = i18n.clone();
+            let record_flag = record.flag;
+            let is_text = record.content_type == ContentType::Text;
+            let draggable = drag_favorites && record.favorite;
+            let created_at = record.created_at;
             let record_content = record.content.clone();
+            let icon = record_icon(record);
+            let collapsed_kind = (content_type == ContentType::Text
+                && !revealed_texts.contains(&record_id))
+            .then(|| classify_text(&record.content))
+            .filter(|kind| *kind != TextKind::Normal);
 
             let preview_data = (content_type.clone(), record_content.clone());
 
-            let mut item = div().pb_2().relative().child(
-                v_flex()
-                    .w_full()
-                    .p_3()
-                    .bg(if is_selected {
-                        cx.theme().accent
-                    } else {
-                        cx.theme().secondary
+            let row = v_flex().w_full();
+            let row = if compact {
+                row.px_2().py_1()
+            } else {
+                row.p_3()
+            };
+            let item = div().relative();
+            let item = if compact { item.pb_1() } else { item.pb_2() };
+            let item = item.child(
+                row.bg(if is_selected {
+                    cx.theme().accent
+                } else {
+                    cx.theme().secondary
+                })
+                .rounded_md()
+                .border_1()
+                .border_color(if is_selected {
+                    cx.theme().accent
+                } else {
+                    cx.theme().border
+                })
+                .hover(|style| style.bg(cx.theme().accent).border_color(cx.theme().accent))
+                .id(("record", index))
+                .when(draggable, |this| {
+                    this.drag_over::<DraggedFavorite>(|style, _, _, cx| {
+                        style.border_color(cx.theme().drop_target)
                     })
-                    .rounded_md()
-                    .border_1()
-                    .border_color(if is_selected {
-                        cx.theme().accent
-                    } else {
-                        cx.theme().border
+                    .on_drop(move |dragged: &DraggedFavorite, _window, cx| {
+                        view_drop
+                            .update(cx, |this, cx| {
+                                this.move_favorite_to(dragged.id, record_id);
+                                cx.notify();
+                            })
+                            .ok();
                     })
-                    .hover(|style| style.bg(cx.theme().accent).border_color(cx.theme().accent))
-                    .id(("record", index))
-                    .child(
-                        h_flex()
-                            .justify_between()
-                            .items_start()
-                            .gap_2()
-                            .child({
-                                let mut content_div = div()
-                                    .flex_1()
-                                    .min_w_0()
-                                    .cursor_pointer()
-                                    .id(("record-content", index))
-                                    .on_click(move |_event, window, cx| {
-                                        view_click
-                                            .update(cx, |this, cx| {
-                                                this.confirm_record(window, cx, index);
-                                            })
-                                            .ok();
-                                    });
-
-                                if !show_preview {
-                                    content_div = content_div.tooltip({
-                                        let (content_type, record_content) = preview_data.clone();
-                                        move |window, cx| {
-                                            create_preview(
-                                                &content_type,
-                                                &record_content,
-                                                window,
-                                                cx,
-                                            )
-                                        }
-                                    });
-                                }
-
-                                content_div
-                                    .child(match content_type {
-                                        ContentType::Text => render_text_record(cx, record),
-                                        ContentType::Image => render_image_record(record),
-                                        _ => div().child("Unknown content").into_any_element(),
+                })
+                .child(
+                    h_flex()
+                        .justify_between()
+                        .items_start()
+                        .gap_2()
+                        .when(draggable, |this| {
+                            let dragged = DraggedFavorite {
+                                id: record_id,
+                                label: record.content.chars().take(40).collect(),
+                            };
+                            this.child(
+                                div()
+                                    .id(("drag-handle", index))
+                                    .flex_shrink_0()
+                                    .cursor(gpui::CursorStyle::OpenHand)
+                                    .text_color(cx.theme().muted_foreground)
+                                    .on_drag(dragged, |dragged, _, _, cx| {
+                                        cx.new(|_| dragged.clone())
                                     })
-                                    .child(
-                                        h_flex()
-                                            .items_center()
-                                            .gap_1()
+                                    .child("⠿"),
+                            )
+                        })
+                        .child(
+                            Icon::empty()
+                                .path(icon)
+                                .small()
+                                .flex_shrink_0()
+                                .when(!compact, |this| this.mt_0p5())
+                                .text_color(cx.theme().muted_foreground),
+                        )
+                        .child({
+                            let mut content_div = div()
+                                .flex_1()
+                                .min_w_0()
+                                .cursor_pointer()
+                                .id(("record-content", index))
+                                .on_click(move |_event, window, cx| {
+                                    view_click
+                                        .update(cx, |this, cx| {
+                                            this.confirm_record(window, cx, index);
+                                        })
+                                        .ok();
+                                });
+
+                            if !show_preview && collapsed_kind.is_none() {
+                                content_div = content_div.tooltip({
+                                    let (content_type, record_content) = preview_data.clone();
+                                    let broken_image = broken_image.clone();
+                                    move |window, cx| {
+                                        create_preview(
+                                            &content_type,
+                                            &record_content,
+                                            &broken_image,
+                                            window,
+                                            cx,
+                                        )
+                                    }
+                                });
+                            }
+
+                            content_div
+                                .child(match (&content_type, collapsed_kind) {
+                                    (ContentType::Text, Some(kind)) => {
+                                        let message = match kind {
+                                            TextKind::Binary => binary_text_label.clone(),
+                                            _ => large_text_label.replace(
+                                                "{size}",
+                                                &format_size(record.content.len()),
+                                            ),
+                                        };
+                                        render_collapsed_text(
+                                            cx,
+                                            index,
+                                            message,
+                                            show_anyway_label.clone(),
+                                            move |_window, cx| {
+                                                view_show_text
+                                                    .update(cx, |this, cx| {
+                                                        this.revealed_texts.insert(record_id);
+                                                        cx.notify();
+                                                    })
+                                                    .ok();
+                                            },
+                                        )
+                                    }
+                                    (ContentType::Text, None) => render_text_record(
+                                        cx,
+                                        record,
+                                        compact,
+                                        collapse_whitespace,
+                                        color_formats_open == Some(record_id),
+                                        &more_chars_label,
+                                        view_color.clone(),
+                                    ),
+                                    (ContentType::Svg, _) => render_svg_record(
+                                        record,
+                                        &svg_images,
+                                        compact,
+                                        image_height,
+                                        collapse_whitespace,
+                                    ),
+                                    (ContentType::Image, _) => render_image_record(
+                                        cx,
+                                        record,
+                                        compact,
+                                        image_height,
+                                        &broken_image,
+                                    ),
+                                    _ => div().child(unknown_content.clone()).into_any_element(),
+                                })
+                                .when_some(record.note.clone(), |this, note| {
+                                    this.child(
+                                        div()
+                                            .text_xs()
+                                            .mt_1()
+                                            .italic()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(note),
+                                    )
+                                })
+                                .when(!record.tags.is_empty(), |this| {
+                                    this.child(h_flex().flex_wrap().gap_1().mt_1().children(
+                                        record.tags.iter().map(|tag| {
+                                            div()
+                                                .text_xs()
+                                                .px_1()
+                                                .rounded_sm()
+                                                .bg(cx.theme().muted)
+                                                .text_color(cx.theme().muted_foreground)
+                                                .child(format!("#{tag}"))
+                                        }),
+                                    ))
+                                })
+                                .when(pending_delete == Some(record_id), |this| {
+                                    this.child(
+                                        div()
+                                            .text_xs()
                                             .mt_1()
-                                            .child(
+                                            .text_color(cx.theme().danger)
+                                            .child(delete_confirm_hint.clone()),
+                                    )
+                                })
+                                .child(
+                                    h_flex()
+                                        .items_center()
+                                        .gap_1()
+                                        .mt_1()
+                                        // Only rows reachable with the number keys get a badge
+                                        .when(index < QUICK_PASTE_COUNT, |this| {
+                                            this.child(
                                                 div()
                                                     .text_xs()
                                                     .text_color(cx.theme().muted_foreground)
@@ -311,55 +1042,366 @@ impl RopyBoard {
                                                     .rounded_sm()
                                                     .child(format!("{}", index + 1)),
                                             )
-                                            .child(
+                                        })
+                                        .child(
+                                            div()
+                                                .id(("record-time", index))
+                                                .text_xs()
+                                                .text_color(cx.theme().muted_foreground)
+                                                .cursor_pointer()
+                                                .hover(|style| {
+                                                    style.text_color(cx.theme().foreground)
+                                                })
+                                                .on_mouse_down(
+                                                    gpui::MouseButton::Left,
+                                                    |_, _, cx| cx.stop_propagation(),
+                                                )
+                                                .on_click(move |_event, _window, cx| {
+                                                    // Don't also confirm the record
+                                                    cx.stop_propagation();
+                                                    view_copy_time
+                                                        .update(cx, |this, _| {
+                                                            this.copy_to_clipboard(
+                                                                &created_at.to_rfc3339(),
+                                                                &ContentType::Text,
+                                                            );
+                                                        })
+                                                        .ok();
+                                                })
+                                                .child(
+                                                    record
+                                                        .created_at
+                                                        .format("%Y-%m-%d %H:%M:%S")
+                                                        .to_string(),
+                                                ),
+                                        )
+                                        .when(record.delete_after_paste, |this| {
+                                            this.child(
+                                                div()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().danger)
+                                                    .child("🔥"),
+                                            )
+                                        })
+                                        .when_some(record.expires_at, |this, expires_at| {
+                                            this.child(
                                                 div()
                                                     .text_xs()
                                                     .text_color(cx.theme().muted_foreground)
-                                                    .child(
-                                                        record
-                                                            .created_at
-                                                            .format("%Y-%m-%d %H:%M:%S")
-                                                            .to_string(),
-                                                    ),
-                                            ),
+                                                    .child(format!(
+                                                        "⏱ {}",
+                                                        expires_at.format("%H:%M")
+                                                    )),
+                                            )
+                                        })
+                                        .when_some(
+                                            record.short_hash().filter(|_| show_content_hash),
+                                            |this, hash| {
+                                                this.child(
+                                                    div()
+                                                        .text_xs()
+                                                        .font_family("monospace")
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .child(format!("#{hash}")),
+                                                )
+                                            },
+                                        ),
+                                )
+                        })
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .items_center()
+                                .when(content_type == ContentType::Image, |this| {
+                                    this.child(
+                                        Button::new(("copy-path-btn", index))
+                                            .xsmall()
+                                            .ghost()
+                                            .label(copy_path_label.clone())
+                                            .tooltip(copy_path_tooltip.clone())
+                                            .on_click(move |_event, window, cx| {
+                                                view_copy_path
+                                                    .update(cx, |this, cx| {
+                                                        this.copy_image_path(window, cx, index);
+                                                    })
+                                                    .ok();
+                                            }),
                                     )
-                            })
-                            .child(
-                                Button::new(("delete-btn", index))
-                                    .xsmall()
-                                    .ghost()
-                                    .label("×")
-                                    .on_click(move |_event, _window, cx| {
-                                        view_delete
-                                            .update(cx, |this, cx| {
-                                                this.delete_record(record_id);
-                                                // TODO Delete associated last copy state
-                                                cx.notify();
-                                            })
-                                            .ok();
-                                    }),
-                            ),
-                    ),
+                                })
+                                .when(
+                                    matches!(
+                                        content_type,
+                                        ContentType::Image | ContentType::FilePath
+                                    ),
+                                    |this| {
+                                        this.child(
+                                            Button::new(("reveal-btn", index))
+                                                .xsmall()
+                                                .ghost()
+                                                .label(reveal_label.clone())
+                                                .tooltip(reveal_tooltip.clone())
+                                                .on_click(move |_event, _window, cx| {
+                                                    view_reveal
+                                                        .update(cx, |this, _| {
+                                                            this.reveal_record(index);
+                                                        })
+                                                        .ok();
+                                                }),
+                                        )
+                                    },
+                                )
+                                .child(
+                                    Button::new(("favorite-btn", index))
+                                        .xsmall()
+                                        .ghost()
+                                        .icon(Icon::empty().path(if record.favorite {
+                                            "star-filled.svg"
+                                        } else {
+                                            "star.svg"
+                                        }))
+                                        .tooltip(favorite_tooltip.clone())
+                                        .on_click(move |_event, _window, cx| {
+                                            view_favorite
+                                                .update(cx, |this, cx| {
+                                                    this.toggle_favorite(record_id);
+                                                    cx.notify();
+                                                })
+                                                .ok();
+                                        }),
+                                )
+                                .child(
+                                    Button::new(("note-btn", index))
+                                        .xsmall()
+                                        .ghost()
+                                        .label("✎")
+                                        .tooltip(note_tooltip.clone())
+                                        .on_click(move |_event, window, cx| {
+                                            view_note
+                                                .update(cx, |this, cx| {
+                                                    this.open_note_editor(record_id, window, cx);
+                                                })
+                                                .ok();
+                                        }),
+                                )
+                                .child(
+                                    Button::new(("delete-btn", index))
+                                        .xsmall()
+                                        .ghost()
+                                        .label("×")
+                                        .tooltip(delete_tooltip.clone())
+                                        .on_click(move |_event, _window, cx| {
+                                            view_delete
+                                                .update(cx, |this, cx| {
+                                                    this.trash_record(record_id);
+                                                    // TODO Delete associated last copy state
+                                                    cx.notify();
+                                                })
+                                                .ok();
+                                        }),
+                                ),
+                        ),
+                )
+                // Right-clicking selects the row, so the menu's actions apply to it
+                .on_mouse_down(MouseButton::Right, move |_event, _window, cx| {
+                    view_menu
+                        .update(cx, |this, cx| {
+                            this.selected_index = index;
+                            cx.notify();
+                        })
+                        .ok();
+                })
+                .context_menu(move |menu, _window, _cx| {
+                    let menu = menu
+                        .menu_with_check(
+                            menu_i18n.t("menu_sticky"),
+                            sticky_id == Some(record_id),
+                            Box::new(ToggleStickyClipboard),
+                        )
+                        .separator();
+                    // Only text records can be compared
+                    let menu = if is_text {
+                        menu.menu_with_check(
+                            menu_i18n.t("menu_compare"),
+                            compare_base == Some(record_id),
+                            Box::new(CompareRecords),
+                        )
+                    } else {
+                        menu.menu_with_disabled(
+                            menu_i18n.t("menu_compare"),
+                            Box::new(CompareRecords),
+                            true,
+                        )
+                    }
+                    .separator();
+                    flag_menu(menu, &menu_i18n, record_flag)
+                }),
             );
 
-            if is_selected && show_preview {
-                let (content_type, record_content) = preview_data;
-                item =
-                    item.child(
-                        deferred(
-                            div().absolute().top_full().left_0().child(
-                                anchored().snap_to_window().child(div().mt_1().child(
-                                    create_preview(&content_type, &record_content, window, cx),
-                                )),
-                            ),
-                        )
-                        .with_priority(1),
-                    );
-            }
+            // Color strip along the left edge of a flagged row
+            let item = match record_flag {
+                Some(flag) => item.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .w_1()
+                        .when(compact, |this| this.bottom_1())
+                        .when(!compact, |this| this.bottom_2())
+                        .rounded_l_md()
+                        .bg(flag_color(flag)),
+                ),
+                None => item,
+            };
+
+            // Fading highlight over a row that was just copied
+            let flash = copy_flash
+                .as_ref()
+                .filter(|flash| flash.matches(&record.content));
+            let item = match flash {
+                Some(flash) => item.child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .when(compact, |this| this.bottom_1())
+                        .when(!compact, |this| this.bottom_2())
+                        .rounded_md()
+                        .bg(cx.theme().primary)
+                        .with_animation(
+                            ("copy-flash", flash.sequence),
+                            Animation::new(COPY_FLASH_DURATION).with_easing(ease_in_out),
+                            |this, delta| this.opacity(0.4 * (1.0 - delta)),
+                        ),
+                ),
+                None => item,
+            };
 
             item.into_any_element()
         })
-        .w_full()
-        .flex_1()
+        .size_full();
+
+        // Thin overlay scrollbar that only shows while scrolling
+        div().relative().w_full().flex_1().child(record_list).child(
+            div()
+                .absolute()
+                .top_0()
+                .right_0()
+                .bottom_0()
+                .w(px(8.))
+                .child(Scrollbar::vertical(&self.list_state)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    fn text_record(content: &str, source_app: Option<&str>) -> ClipboardRecord {
+        ClipboardRecord {
+            source_app: source_app.map(str::to_string),
+            ..ClipboardRecord::test_record(1, content)
+        }
+    }
+
+    #[test]
+    fn test_record_icon() {
+        assert_eq!(record_icon(&text_record("hello", None)), "type-text.svg");
+        assert_eq!(record_icon(&text_record("#ff8800", None)), "type-color.svg");
+        assert_eq!(
+            record_icon(&text_record("https://example.com/a?b=c", None)),
+            "type-link.svg"
+        );
+        assert_eq!(
+            record_icon(&text_record("see https://example.com", None)),
+            "type-text.svg"
+        );
+
+        let mut record = text_record("/tmp/a.png", None);
+        record.content_type = ContentType::Image;
+        assert_eq!(record_icon(&record), "type-image.svg");
+        record.content_type = ContentType::FilePath;
+        assert_eq!(record_icon(&record), "type-file.svg");
+    }
+
+    #[test]
+    fn test_classify_text() {
+        assert_eq!(classify_text("hello\tworld\r\n"), TextKind::Normal);
+        assert_eq!(classify_text(""), TextKind::Normal);
+        assert_eq!(classify_text(&"a".repeat(100)), TextKind::Normal);
+        assert_eq!(classify_text("ab\0\x01\x02\x03"), TextKind::Binary);
+
+        let base64 = "QUJD".repeat(512);
+        assert_eq!(classify_text(&base64), TextKind::Binary);
+        // Prose isn't mistaken for an encoded blob
+        assert_eq!(classify_text(&"word ".repeat(400)), TextKind::Normal);
+
+        let large = "line of text\n".repeat(LARGE_TEXT_BYTES / 10);
+        assert_eq!(classify_text(&large), TextKind::Large);
+        assert_eq!(format_size(large.len()), "333 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 / 2), "1.5 MB");
+    }
+
+    #[test]
+    fn test_format_clipboard_content() {
+        let record = text_record("fn main() {\n\t\tprintln!();\n}\n", None);
+        assert_eq!(
+            format_clipboard_content(&record, true),
+            "fn main() { println!(); }"
+        );
+        assert_eq!(format_clipboard_content(&record, false), record.content);
+        // Only the display is normalized
+        assert_eq!(record.content, "fn main() {\n\t\tprintln!();\n}\n");
+        // Copying with metadata uses the original content too
+        let i18n = I18n::new(Language::English).unwrap();
+        assert!(format_record_with_metadata(&record, &i18n).starts_with(&record.content));
+
+        let long = text_record(&"word  ".repeat(50), None);
+        let display = format_clipboard_content(&long, true);
+        assert_eq!(display.chars().count(), 103);
+        assert!(display.ends_with("..."));
+    }
+
+    #[test]
+    fn test_hidden_char_count() {
+        assert_eq!(hidden_char_count(&text_record("short", None), false), 0);
+        let long = text_record(&"x".repeat(DISPLAY_CHARS + 25), None);
+        assert_eq!(hidden_char_count(&long, false), 25);
+
+        // Shown in full once its whitespace is collapsed
+        let spaced = text_record(&format!("a{}b", " ".repeat(DISPLAY_CHARS)), None);
+        assert_eq!(hidden_char_count(&spaced, false), 2);
+        assert_eq!(hidden_char_count(&spaced, true), 0);
+    }
+
+    #[test]
+    fn test_format_record_with_metadata() {
+        let i18n = I18n::new(Language::English).unwrap();
+
+        let record = text_record("hello", Some("Terminal"));
+        let formatted = format_record_with_metadata(&record, &i18n);
+        assert!(formatted.starts_with("hello\n\n[captured: "));
+        assert!(formatted.ends_with(" from Terminal]"));
+
+        // Empty source is skipped
+        let record = text_record("hello", Some("  "));
+        let formatted = format_record_with_metadata(&record, &i18n);
+        assert!(!formatted.contains("from"));
+        assert!(formatted.ends_with(']'));
+    }
+
+    #[test]
+    fn test_accessible_label() {
+        let i18n = I18n::new(Language::English).unwrap();
+
+        let mut record = text_record("hunter2", None);
+        record.note = Some("password".to_string());
+        assert_eq!(accessible_label(&record, 3, 12, &i18n), "Text, 3 of 12");
+
+        record.content = "/tmp/shot.png".to_string();
+        record.content_type = ContentType::Image;
+        assert_eq!(accessible_label(&record, 1, 1, &i18n), "Image, 1 of 1");
     }
 }
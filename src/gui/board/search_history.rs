@@ -0,0 +1,226 @@
+//! Recent search queries offered as suggestions while the search box is empty
+
+use gpui::{
+    Context, Focusable, KeyDownEvent, Window, div,
+    prelude::{
+        FluentBuilder, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement,
+        Styled,
+    },
+};
+use gpui_component::{ActiveTheme, v_flex};
+
+use super::{RopyBoard, ToastLevel};
+
+/// Maximum number of remembered queries
+pub(super) const SEARCH_HISTORY_LIMIT: usize = 20;
+
+/// Whether every word of the query is a `#tag` or `@name` filter
+fn is_tag_only_query(query: &str) -> bool {
+    let mut words = query.split_whitespace().peekable();
+    words.peek().is_some() && words.all(|word| word.starts_with('#') || word.starts_with('@'))
+}
+
+/// Move `query` to the front of `history`, dropping duplicates and the oldest entries
+///
+/// Returns whether the history changed.
+fn push_search_history(history: &mut Vec<String>, query: &str, record_tag_queries: bool) -> bool {
+    let query = query.trim();
+    if query.is_empty() || (!record_tag_queries && is_tag_only_query(query)) {
+        return false;
+    }
+    if history.first().is_some_and(|latest| latest == query) {
+        return false;
+    }
+    history.retain(|entry| entry != query);
+    history.insert(0, query.to_string());
+    history.truncate(SEARCH_HISTORY_LIMIT);
+    true
+}
+
+impl RopyBoard {
+    /// Remember the current search query, if any
+    pub(super) fn remember_search(&mut self, cx: &mut Context<Self>) {
+        let query = self.active_query(cx);
+        let result = {
+            let mut settings = self.settings.write().unwrap();
+            let record_tag_queries = settings.behavior.record_tag_searches;
+            if !push_search_history(
+                &mut settings.behavior.search_history,
+                &query,
+                record_tag_queries,
+            ) {
+                return;
+            }
+            settings.save()
+        };
+        if let Err(e) = result {
+            log::error!("Failed to save settings: {e}");
+            self.show_toast(self.i18n.t("toast_settings_save_failed"), ToastLevel::Error);
+        }
+    }
+
+    /// Whether past queries are offered: the search box is focused and empty
+    fn search_history_visible(&self, window: &Window, cx: &Context<Self>) -> bool {
+        window
+            .focused(cx)
+            .is_some_and(|handle| handle == self.search_input.focus_handle(cx))
+            && self.search_input.read(cx).value().is_empty()
+            && !self
+                .settings
+                .read()
+                .unwrap()
+                .behavior
+                .search_history
+                .is_empty()
+    }
+
+    /// Fill the search box with a past query
+    fn apply_search_history(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(query) = self
+            .settings
+            .read()
+            .unwrap()
+            .behavior
+            .search_history
+            .get(index)
+            .cloned()
+        else {
+            return;
+        };
+        self.history_selected = None;
+        self.selected_index = 0;
+        self.submitted_query = query.clone();
+        self.search_input.update(cx, |input, cx| {
+            input.set_value(query, window, cx);
+        });
+        cx.notify();
+    }
+
+    /// Select past queries with up/down and apply one with enter, before the input sees the keys
+    pub(super) fn on_search_history_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.search_history_visible(window, cx) {
+            self.history_selected = None;
+            return;
+        }
+        let count = self.settings.read().unwrap().behavior.search_history.len();
+        match event.keystroke.key.as_str() {
+            "down" => {
+                self.history_selected = Some(self.history_selected.map_or(0, |i| (i + 1) % count));
+            }
+            "up" => {
+                self.history_selected = Some(
+                    self.history_selected
+                        .map_or(count - 1, |i| (i + count - 1) % count),
+                );
+            }
+            "enter" => {
+                let Some(index) = self.history_selected else {
+                    return;
+                };
+                self.apply_search_history(index, window, cx);
+            }
+            _ => return,
+        }
+        cx.stop_propagation();
+        cx.notify();
+    }
+}
+
+/// Render past queries below the search box while it is focused and empty
+pub(super) fn render_search_history(
+    board: &RopyBoard,
+    window: &Window,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    if !board.search_history_visible(window, cx) {
+        return None;
+    }
+    let history = board
+        .settings
+        .read()
+        .unwrap()
+        .behavior
+        .search_history
+        .clone();
+    let items = history.into_iter().enumerate().map(|(index, query)| {
+        let selected = board.history_selected == Some(index);
+        div()
+            .id(("search-history", index))
+            .px_2()
+            .py_1()
+            .rounded_sm()
+            .text_sm()
+            .cursor_pointer()
+            .text_color(cx.theme().foreground)
+            .when(selected, |this| this.bg(cx.theme().accent))
+            .hover(|style| style.bg(cx.theme().accent))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.apply_search_history(index, window, cx);
+            }))
+            .child(query)
+    });
+    Some(
+        v_flex()
+            .id("search-history-list")
+            .w_full()
+            .mb_2()
+            .p_1()
+            .max_h(gpui::px(200.))
+            .overflow_y_scroll()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .child(
+                div()
+                    .px_2()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(board.i18n.t("search_history_title")),
+            )
+            .children(items),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_tag_only_query() {
+        assert!(is_tag_only_query("#work"));
+        assert!(is_tag_only_query("#work @alice"));
+        assert!(!is_tag_only_query("#work report"));
+        assert!(!is_tag_only_query("report"));
+        assert!(!is_tag_only_query("   "));
+    }
+
+    #[test]
+    fn test_push_search_history() {
+        let mut history = Vec::new();
+        assert!(push_search_history(&mut history, " foo ", true));
+        assert!(push_search_history(&mut history, "bar", true));
+        assert_eq!(history, ["bar", "foo"]);
+
+        // Re-running a query moves it to the front instead of duplicating it
+        assert!(push_search_history(&mut history, "foo", true));
+        assert_eq!(history, ["foo", "bar"]);
+        assert!(!push_search_history(&mut history, "foo", true));
+        assert!(!push_search_history(&mut history, "", true));
+
+        assert!(!push_search_history(&mut history, "#tag @name", false));
+        assert!(push_search_history(&mut history, "#tag @name", true));
+        assert_eq!(history[0], "#tag @name");
+
+        for i in 0..30 {
+            push_search_history(&mut history, &format!("query {i}"), true);
+        }
+        assert_eq!(history.len(), SEARCH_HISTORY_LIMIT);
+        assert_eq!(history[0], "query 29");
+    }
+}
@@ -1,14 +1,19 @@
+use crate::config::{CopyFeedback, Density, PasteMode, SearchEnter, WindowAnchor, profile};
 use crate::i18n::{I18n, Language};
+use crate::repository::models::ContentType;
 use gpui::{
     Context, div,
-    prelude::{InteractiveElement, IntoElement, ParentElement, Styled},
+    prelude::{
+        FluentBuilder, InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement,
+        Styled,
+    },
     px,
 };
 use gpui_component::button::{Button, ButtonVariants};
 use gpui_component::input::Input;
 use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
 
-use super::RopyBoard;
+use super::{RopyBoard, ToastLevel};
 #[cfg(target_os = "windows")]
 use crate::gui::utils::start_window_drag;
 
@@ -80,6 +85,538 @@ fn render_theme_selector(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) ->
         }))
 }
 
+/// Render a button per profile, the active one highlighted, and an input to add a profile
+fn render_profile_selector(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) -> impl IntoElement {
+    let active = profile::active_profile();
+
+    v_flex()
+        .gap_2()
+        .child(
+            h_flex().gap_2().items_center().flex_wrap().children(
+                profile::list_profiles()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, name)| {
+                        let button = Button::new(("profile-button", index))
+                            .small()
+                            .label(name.clone());
+                        let button = if name == active {
+                            button.primary()
+                        } else {
+                            button.ghost()
+                        };
+                        button.on_click(cx.listener(move |board, _, window, cx| {
+                            board.switch_profile(&name, window, cx);
+                        }))
+                    }),
+            ),
+        )
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    Input::new(&board.settings_profile_input)
+                        .appearance(false)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .px_2()
+                        .py_1()
+                        .flex_1(),
+                )
+                .child(
+                    Button::new("profile-create")
+                        .small()
+                        .ghost()
+                        .label(board.i18n.t("settings_profile_create"))
+                        .on_click(cx.listener(|board, _, window, cx| {
+                            board.create_profile(window, cx);
+                        })),
+                ),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("settings_profile_hint")),
+        )
+}
+
+/// Render the theme file buttons: export, import and reset to the built-in preset colors
+fn render_theme_sharing(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) -> impl IntoElement {
+    let has_custom_colors = !board
+        .settings
+        .read()
+        .unwrap()
+        .appearance
+        .custom_colors
+        .is_empty();
+
+    v_flex()
+        .gap_1()
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    Button::new("theme-export")
+                        .small()
+                        .ghost()
+                        .label(board.i18n.t("settings_theme_export"))
+                        .on_click(cx.listener(|board, _, window, cx| {
+                            board.export_theme(window, cx);
+                        })),
+                )
+                .child(
+                    Button::new("theme-import")
+                        .small()
+                        .ghost()
+                        .label(board.i18n.t("settings_theme_import"))
+                        .on_click(cx.listener(|board, _, window, cx| {
+                            board.import_theme(window, cx);
+                        })),
+                )
+                .when(has_custom_colors, |this| {
+                    this.child(
+                        Button::new("theme-reset-colors")
+                            .small()
+                            .ghost()
+                            .label(board.i18n.t("settings_theme_reset_colors"))
+                            .on_click(cx.listener(|board, _, window, cx| {
+                                board.reset_theme_colors(window, cx);
+                            })),
+                    )
+                }),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("settings_theme_file_hint")),
+        )
+}
+
+/// Render list density buttons. The choice is saved and applied immediately.
+fn render_density_selector(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) -> impl IntoElement {
+    let current = board.settings.read().unwrap().appearance.density;
+    let densities = [
+        (
+            Density::Comfortable,
+            board.i18n.t("settings_density_comfortable"),
+        ),
+        (Density::Compact, board.i18n.t("settings_density_compact")),
+    ];
+
+    h_flex()
+        .gap_2()
+        .items_center()
+        .children(
+            densities
+                .into_iter()
+                .enumerate()
+                .map(|(index, (density, name))| {
+                    let mut button = Button::new(("density-button", index)).small().label(name);
+
+                    button = if current == density {
+                        button.primary()
+                    } else {
+                        button.ghost()
+                    };
+
+                    button.on_click(cx.listener(move |board, _, _window, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.appearance.density = density;
+                            settings.save()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+}
+
+/// Render paste mode buttons. The choice is saved and applied immediately.
+fn render_paste_mode_selector(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let current = board.settings.read().unwrap().behavior.paste_mode;
+    let modes = [
+        (PasteMode::Copy, board.i18n.t("settings_paste_mode_copy")),
+        (PasteMode::Cut, board.i18n.t("settings_paste_mode_cut")),
+    ];
+
+    h_flex()
+        .justify_between()
+        .items_center()
+        .child(
+            div()
+                .text_color(cx.theme().foreground)
+                .child(board.i18n.t("settings_paste_mode")),
+        )
+        .child(h_flex().gap_2().children(modes.into_iter().enumerate().map(
+            |(index, (mode, name))| {
+                let button = Button::new(("paste-mode-button", index))
+                    .small()
+                    .label(name);
+                let button = if current == mode {
+                    button.primary()
+                } else {
+                    button.ghost()
+                };
+                button.on_click(cx.listener(move |board, _, _window, cx| {
+                    let result = {
+                        let mut settings = board.settings.write().unwrap();
+                        settings.behavior.paste_mode = mode;
+                        settings.save()
+                    };
+                    if let Err(e) = result {
+                        log::error!("Failed to save settings: {e}");
+                        board.show_toast(
+                            board.i18n.t("toast_settings_save_failed"),
+                            ToastLevel::Error,
+                        );
+                    }
+                    cx.notify();
+                }))
+            },
+        )))
+}
+
+/// Render the buttons choosing what Enter does in the search box. The choice applies immediately.
+fn render_search_enter_selector(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let current = board.settings.read().unwrap().behavior.search_enter;
+    let modes = [
+        (
+            SearchEnter::ConfirmTop,
+            board.i18n.t("settings_search_enter_confirm"),
+        ),
+        (
+            SearchEnter::Blur,
+            board.i18n.t("settings_search_enter_blur"),
+        ),
+    ];
+
+    h_flex()
+        .justify_between()
+        .items_center()
+        .child(
+            div()
+                .text_color(cx.theme().foreground)
+                .child(board.i18n.t("settings_search_enter")),
+        )
+        .child(h_flex().gap_2().children(modes.into_iter().enumerate().map(
+            |(index, (mode, name))| {
+                let button = Button::new(("search-enter-button", index))
+                    .small()
+                    .label(name);
+                let button = if current == mode {
+                    button.primary()
+                } else {
+                    button.ghost()
+                };
+                button.on_click(cx.listener(move |board, _, _window, cx| {
+                    let result = {
+                        let mut settings = board.settings.write().unwrap();
+                        settings.behavior.search_enter = mode;
+                        settings.save()
+                    };
+                    if let Err(e) = result {
+                        log::error!("Failed to save settings: {e}");
+                        board.show_toast(
+                            board.i18n.t("toast_settings_save_failed"),
+                            ToastLevel::Error,
+                        );
+                    }
+                    cx.notify();
+                }))
+            },
+        )))
+}
+
+/// Render window position buttons. The choice is saved and used when the window is created.
+fn render_anchor_selector(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) -> impl IntoElement {
+    let current = board.settings.read().unwrap().appearance.window_anchor;
+    let anchors = [
+        (WindowAnchor::Center, "◎", "settings_anchor_center"),
+        (WindowAnchor::TopLeft, "↖", "settings_anchor_top_left"),
+        (WindowAnchor::TopRight, "↗", "settings_anchor_top_right"),
+        (WindowAnchor::BottomLeft, "↙", "settings_anchor_bottom_left"),
+        (
+            WindowAnchor::BottomRight,
+            "↘",
+            "settings_anchor_bottom_right",
+        ),
+    ];
+
+    v_flex()
+        .gap_1()
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_window_anchor")),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .children(anchors.into_iter().enumerate().map(
+                            |(index, (anchor, label, tooltip))| {
+                                let button = Button::new(("anchor-button", index))
+                                    .small()
+                                    .label(label)
+                                    .tooltip(board.i18n.t(tooltip));
+                                let button = if current == anchor {
+                                    button.primary()
+                                } else {
+                                    button.ghost()
+                                };
+                                button.on_click(cx.listener(move |board, _, _window, cx| {
+                                    let result = {
+                                        let mut settings = board.settings.write().unwrap();
+                                        settings.appearance.window_anchor = anchor;
+                                        settings.save()
+                                    };
+                                    if let Err(e) = result {
+                                        log::error!("Failed to save settings: {e}");
+                                        board.show_toast(
+                                            board.i18n.t("toast_settings_save_failed"),
+                                            ToastLevel::Error,
+                                        );
+                                    }
+                                    cx.notify();
+                                }))
+                            },
+                        )),
+                ),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("settings_window_anchor_hint")),
+        )
+}
+
+/// Render list image height buttons. The choice is saved and applied immediately.
+fn render_list_image_height_selector(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let current = board
+        .settings
+        .read()
+        .unwrap()
+        .appearance
+        .list_image_height();
+    let options = [
+        (60.0, board.i18n.t("settings_list_image_small")),
+        (100.0, board.i18n.t("settings_list_image_medium")),
+        (180.0, board.i18n.t("settings_list_image_large")),
+        (300.0, board.i18n.t("settings_list_image_huge")),
+    ];
+
+    h_flex()
+        .justify_between()
+        .items_center()
+        .child(
+            div()
+                .text_color(cx.theme().foreground)
+                .child(board.i18n.t("settings_list_image_height")),
+        )
+        .child(
+            h_flex()
+                .gap_2()
+                .children(
+                    options
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (height, name))| {
+                            let button = Button::new(("list-image-height-button", index))
+                                .small()
+                                .label(name);
+                            let button = if current == height {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            };
+                            button.on_click(cx.listener(move |board, _, _window, cx| {
+                                let result = {
+                                    let mut settings = board.settings.write().unwrap();
+                                    settings.appearance.list_image_height = height;
+                                    settings.save()
+                                };
+                                if let Err(e) = result {
+                                    log::error!("Failed to save settings: {e}");
+                                    board.show_toast(
+                                        board.i18n.t("toast_settings_save_failed"),
+                                        ToastLevel::Error,
+                                    );
+                                }
+                                cx.notify();
+                            }))
+                        }),
+                ),
+        )
+}
+
+/// Render copy feedback buttons. The choice is saved and applied to the next copy.
+fn render_copy_feedback_selector(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let current = board.settings.read().unwrap().appearance.copy_feedback;
+    let options = [
+        (
+            CopyFeedback::None,
+            board.i18n.t("settings_copy_feedback_none"),
+        ),
+        (
+            CopyFeedback::Flash,
+            board.i18n.t("settings_copy_feedback_flash"),
+        ),
+        (
+            CopyFeedback::Sound,
+            board.i18n.t("settings_copy_feedback_sound"),
+        ),
+    ];
+
+    h_flex()
+        .justify_between()
+        .items_center()
+        .child(
+            div()
+                .text_color(cx.theme().foreground)
+                .child(board.i18n.t("settings_copy_feedback")),
+        )
+        .child(
+            h_flex()
+                .gap_2()
+                .children(
+                    options
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (feedback, name))| {
+                            let button = Button::new(("copy-feedback-button", index))
+                                .small()
+                                .label(name);
+                            let button = if current == feedback {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            };
+                            button.on_click(cx.listener(move |board, _, _window, cx| {
+                                let result = {
+                                    let mut settings = board.settings.write().unwrap();
+                                    settings.appearance.copy_feedback = feedback;
+                                    settings.save()
+                                };
+                                if let Err(e) = result {
+                                    log::error!("Failed to save settings: {e}");
+                                    board.show_toast(
+                                        board.i18n.t("toast_settings_save_failed"),
+                                        ToastLevel::Error,
+                                    );
+                                }
+                                cx.notify();
+                            }))
+                        }),
+                ),
+        )
+}
+
+/// Render copy statistics with a reset button
+fn render_usage_stats(board: &mut RopyBoard, cx: &mut Context<RopyBoard>) -> impl IntoElement {
+    let stats = board.usage_stats();
+    let image_label = board.i18n.t("content_type_image");
+    let svg_label = board.i18n.t("content_type_svg");
+    let file_label = board.i18n.t("content_type_file");
+
+    let counts = h_flex()
+        .justify_between()
+        .items_center()
+        .child(
+            div().text_color(cx.theme().foreground).child(
+                board
+                    .i18n
+                    .t("stats_copies")
+                    .replace("{today}", &stats.today.to_string())
+                    .replace("{week}", &stats.this_week.to_string()),
+            ),
+        )
+        .child(
+            Button::new("reset-stats-button")
+                .small()
+                .ghost()
+                .label(board.i18n.t("stats_reset"))
+                .on_click(cx.listener(|board, _, _, cx| {
+                    board.reset_usage_stats();
+                    cx.notify();
+                })),
+        );
+
+    let top = if stats.top.is_empty() {
+        v_flex().child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("stats_empty")),
+        )
+    } else {
+        v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(board.i18n.t("stats_top")),
+            )
+            .children(stats.top.into_iter().map(|usage| {
+                let summary = match usage.content_type {
+                    ContentType::Text => usage.content.replace(['\n', '\r'], " "),
+                    ContentType::Image => image_label.clone(),
+                    ContentType::FilePath => format!("{file_label}: {}", usage.content),
+                    ContentType::Svg => svg_label.clone(),
+                };
+                h_flex()
+                    .gap_2()
+                    .justify_between()
+                    .text_sm()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w_0()
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .text_color(cx.theme().foreground)
+                            .child(summary),
+                    )
+                    .child(
+                        div()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("×{}", usage.use_count)),
+                    )
+            }))
+    };
+
+    v_flex().gap_2().child(counts).child(top)
+}
+
 /// Render the settings panel content
 pub(super) fn render_settings_content(
     board: &mut RopyBoard,
@@ -99,10 +636,26 @@ pub(super) fn render_settings_content(
                     board.settings_max_history_input.update(cx, |input, cx| {
                         input.set_value("", window, cx);
                     });
+                    board.settings_min_text_chars_input.update(cx, |input, cx| {
+                        input.set_value("", window, cx);
+                    });
+                    let ignored_formats = board
+                        .settings
+                        .read()
+                        .unwrap()
+                        .storage
+                        .ignored_formats
+                        .join(", ");
+                    board
+                        .settings_ignored_formats_input
+                        .update(cx, |input, cx| {
+                            input.set_value(ignored_formats, window, cx);
+                        });
                     board.settings_activation_key_input.update(cx, |input, cx| {
                         input.set_value("", window, cx);
                     });
 
+                    board.capturing_hotkey = false;
                     board.show_settings = false;
                     window.focus(&board.focus_handle);
                     cx.notify();
@@ -135,16 +688,44 @@ pub(super) fn render_settings_content(
                 .px_3()
                 .py_2(),
         );
-    let activation_key_label = v_flex()
+    let min_text_chars_input_field = v_flex()
         .gap_1()
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .justify_between()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_min_text_chars")),
+                )
+                .child(
+                    Input::new(&board.settings_min_text_chars_input)
+                        .appearance(false)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .w(px(60.0))
+                        .px_3()
+                        .py_2(),
+                ),
+        )
         .child(
             div()
                 .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("settings_min_text_chars_hint")),
+        );
+    let ignored_formats_input_field = v_flex()
+        .gap_1()
+        .child(
+            div()
                 .text_color(cx.theme().foreground)
-                .child(board.i18n.t("settings_activation_key")),
+                .child(board.i18n.t("settings_ignored_formats")),
         )
         .child(
-            Input::new(&board.settings_activation_key_input)
+            Input::new(&board.settings_ignored_formats_input)
                 .appearance(false)
                 .border_1()
                 .border_color(cx.theme().border)
@@ -156,7 +737,281 @@ pub(super) fn render_settings_content(
             div()
                 .text_xs()
                 .text_color(cx.theme().muted_foreground)
-                .child(board.i18n.t("settings_hotkey_hint")),
+                .child(board.i18n.t("settings_ignored_formats_hint")),
+        );
+    let activation_key_label = v_flex()
+        .gap_1()
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().foreground)
+                .child(board.i18n.t("settings_activation_key")),
+        )
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    Input::new(&board.settings_activation_key_input)
+                        .appearance(false)
+                        .border_1()
+                        .border_color(cx.theme().border)
+                        .rounded_md()
+                        .flex_1()
+                        .px_3()
+                        .py_2(),
+                )
+                .child(
+                    Button::new("hotkey-record-button")
+                        .small()
+                        .map(|button| {
+                            if board.capturing_hotkey {
+                                button.primary()
+                            } else {
+                                button.ghost()
+                            }
+                        })
+                        .label(board.i18n.t("settings_hotkey_record"))
+                        .on_click(cx.listener(|board, _, window, cx| {
+                            board.toggle_hotkey_capture(window, cx);
+                        })),
+                ),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(if board.capturing_hotkey {
+                    cx.theme().foreground
+                } else {
+                    cx.theme().muted_foreground
+                })
+                .child(if board.capturing_hotkey {
+                    board.i18n.t("settings_hotkey_recording")
+                } else {
+                    board.i18n.t("settings_hotkey_hint")
+                }),
+        )
+        .when(!board.failed_hotkeys.is_empty(), |this| {
+            this.child(
+                div().text_xs().text_color(cx.theme().danger).child(
+                    board
+                        .i18n
+                        .t("hotkey_in_use")
+                        .replace("{hotkey}", &board.failed_hotkeys.join(", ")),
+                ),
+            )
+        });
+    let appearance_section = v_flex()
+        .gap_2()
+        .child(
+            div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(board.i18n.t("settings_appearance")),
+        )
+        .child(render_density_selector(board, cx))
+        .child(render_list_image_height_selector(board, cx))
+        .child(render_anchor_selector(board, cx))
+        .child(render_copy_feedback_selector(board, cx))
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_collapse_whitespace")),
+                )
+                .child({
+                    let enabled = board
+                        .settings
+                        .read()
+                        .unwrap()
+                        .appearance
+                        .collapse_whitespace;
+                    let button = Button::new("collapse-whitespace-toggle").small();
+                    let button = if enabled {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(move |board, _, _, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.appearance.collapse_whitespace = !enabled;
+                            settings.save()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_chronological_search")),
+                )
+                .child({
+                    let enabled = board.settings.read().unwrap().behavior.chronological_search;
+                    let button = Button::new("chronological-search-toggle").small();
+                    let button = if enabled {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(move |board, _, _, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.behavior.chronological_search = !enabled;
+                            settings.save()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_hide_on_focus_loss")),
+                )
+                .child({
+                    let enabled = board.settings.read().unwrap().behavior.hide_on_focus_loss;
+                    let button = Button::new("hide-on-focus-loss-toggle").small();
+                    let button = if enabled {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(move |board, _, _, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.behavior.hide_on_focus_loss = !enabled;
+                            settings.save()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_confirm_quit")),
+                )
+                .child({
+                    let enabled = board.settings.read().unwrap().behavior.confirm_quit;
+                    let button = Button::new("confirm-quit-toggle").small();
+                    let button = if enabled {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(move |board, _, _, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.behavior.confirm_quit = !enabled;
+                            settings.save()
+                        };
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_search_on_enter")),
+                )
+                .child({
+                    let enabled = board.settings.read().unwrap().behavior.search_on_enter;
+                    let button = Button::new("search-on-enter-toggle").small();
+                    let button = if enabled {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(move |board, _, _, cx| {
+                        let result = {
+                            let mut settings = board.settings.write().unwrap();
+                            settings.behavior.search_on_enter = !enabled;
+                            settings.save()
+                        };
+                        // Start from what is in the search box, so turning it on changes nothing
+                        board.submitted_query = board.search_input.read(cx).value().to_string();
+                        if let Err(e) = result {
+                            log::error!("Failed to save settings: {e}");
+                            board.show_toast(
+                                board.i18n.t("toast_settings_save_failed"),
+                                ToastLevel::Error,
+                            );
+                        }
+                        cx.notify();
+                    }))
+                }),
+        )
+        .child(render_search_enter_selector(board, cx))
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_privacy_mode")),
+                )
+                .child({
+                    let button = Button::new("privacy-mode-toggle").small();
+                    let button = if board.privacy_mode.is_active() {
+                        button.primary().label(board.i18n.t("settings_on"))
+                    } else {
+                        button.ghost().label(board.i18n.t("settings_off"))
+                    };
+                    button.on_click(cx.listener(|board, _, _, cx| {
+                        board.toggle_privacy_mode(cx);
+                    }))
+                }),
         );
     let hotkey_section = v_flex()
         .gap_2()
@@ -189,7 +1044,8 @@ pub(super) fn render_settings_content(
                 .font_weight(gpui::FontWeight::BOLD)
                 .child(board.i18n.t("settings_theme")),
         )
-        .child(render_theme_selector(board, cx));
+        .child(render_theme_selector(board, cx))
+        .child(render_theme_sharing(board, cx));
     let storage_section = v_flex()
         .gap_2()
         .child(
@@ -199,7 +1055,46 @@ pub(super) fn render_settings_content(
                 .font_weight(gpui::FontWeight::BOLD)
                 .child(board.i18n.t("settings_storage")),
         )
-        .child(max_history_input_field);
+        .child(max_history_input_field)
+        .child(min_text_chars_input_field)
+        .child(ignored_formats_input_field)
+        .child(render_paste_mode_selector(board, cx))
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_integrity")),
+                )
+                .child(
+                    Button::new("integrity-check-button")
+                        .small()
+                        .ghost()
+                        .label(board.i18n.t("settings_integrity_run"))
+                        .on_click(
+                            cx.listener(|board, _, window, cx| board.check_integrity(window, cx)),
+                        ),
+                ),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_thumbnails")),
+                )
+                .child(
+                    Button::new("repair-thumbnails-button")
+                        .small()
+                        .ghost()
+                        .label(board.i18n.t("settings_thumbnails_run"))
+                        .on_click(cx.listener(|board, _, _, cx| board.repair_thumbnails(cx))),
+                ),
+        );
     let autostart_section = v_flex()
         .gap_2()
         .child(
@@ -233,7 +1128,65 @@ pub(super) fn render_settings_content(
                         board.toggle_autostart(cx);
                     }))
                 }),
+        )
+        .child(
+            h_flex()
+                .justify_between()
+                .items_center()
+                .child(
+                    div()
+                        .text_color(cx.theme().foreground)
+                        .child(board.i18n.t("settings_config_file")),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            Button::new("open-config-button")
+                                .small()
+                                .ghost()
+                                .label(board.i18n.t("settings_config_open"))
+                                .on_click(cx.listener(|board, _, _, cx| {
+                                    board.open_config_file(cx);
+                                })),
+                        )
+                        .child(
+                            Button::new("reload-config-button")
+                                .small()
+                                .ghost()
+                                .label(board.i18n.t("settings_config_reload"))
+                                .on_click(cx.listener(|board, _, window, cx| {
+                                    board.reload_config(window, cx);
+                                })),
+                        ),
+                ),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("settings_config_hint")),
         );
+    let profile_section = v_flex()
+        .gap_2()
+        .child(
+            div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(board.i18n.t("settings_profile")),
+        )
+        .child(render_profile_selector(board, cx));
+    let stats_section = v_flex()
+        .gap_2()
+        .child(
+            div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(board.i18n.t("settings_stats")),
+        )
+        .child(render_usage_stats(board, cx));
     let header = h_flex()
         .justify_between()
         .items_center()
@@ -269,14 +1222,22 @@ pub(super) fn render_settings_content(
         .size_full()
         .child(header)
         .child(
-            v_flex()
-                .gap_4()
+            div()
+                .id("settings-sections")
                 .flex_1()
-                .child(language_section)
-                .child(theme_section)
-                .child(hotkey_section)
-                .child(storage_section)
-                .child(autostart_section),
+                .overflow_y_scroll()
+                .child(
+                    v_flex()
+                        .gap_4()
+                        .child(language_section)
+                        .child(theme_section)
+                        .child(appearance_section)
+                        .child(hotkey_section)
+                        .child(storage_section)
+                        .child(autostart_section)
+                        .child(profile_section)
+                        .child(stats_section),
+                ),
         )
         .child(setting_button_group)
 }
@@ -0,0 +1,161 @@
+use gpui::{
+    Context, div,
+    prelude::{IntoElement, ParentElement, Styled},
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, h_flex};
+
+use super::RopyBoard;
+use super::flag::flag_label_key;
+use super::query::ListFilters;
+use super::render::filter_label_key;
+use crate::repository::models::{ContentFilter, RecordFlag};
+
+/// An active narrowing of the list that can be cleared from the status bar
+#[derive(Debug, Clone, PartialEq)]
+enum StatusChip {
+    Query(String),
+    ContentFilter(ContentFilter),
+    FavoritesOnly,
+    Flag(RecordFlag),
+    Gallery,
+}
+
+/// Chips for every active query and filter, in display order
+fn status_chips(query: &str, filters: &ListFilters) -> Vec<StatusChip> {
+    let mut chips = Vec::new();
+    let query = query.trim();
+    if !query.is_empty() {
+        chips.push(StatusChip::Query(query.to_string()));
+    }
+    if filters.content_filter != ContentFilter::All {
+        chips.push(StatusChip::ContentFilter(filters.content_filter));
+    }
+    if filters.favorites_only {
+        chips.push(StatusChip::FavoritesOnly);
+    }
+    if let Some(flag) = filters.flag_filter {
+        chips.push(StatusChip::Flag(flag));
+    }
+    if filters.gallery_mode {
+        chips.push(StatusChip::Gallery);
+    }
+    chips
+}
+
+/// Render the bar summarizing the active query and filters with result counts
+pub(super) fn render_status_bar(
+    board: &RopyBoard,
+    query: &str,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let chips = status_chips(query, &board.list_filters());
+    let total = board.history_len();
+    let counts = board
+        .i18n
+        .t("status_results")
+        .replace("{shown}", &board.filtered_records.len().to_string())
+        .replace("{total}", &total.to_string());
+
+    let chip_elements = chips.into_iter().enumerate().map(|(index, chip)| {
+        let label = match &chip {
+            StatusChip::Query(query) => board.i18n.t("status_query").replace("{query}", query),
+            StatusChip::ContentFilter(filter) => {
+                let name = board.i18n.t(filter_label_key(*filter));
+                board.i18n.t("status_type").replace("{type}", &name)
+            }
+            StatusChip::FavoritesOnly => board.i18n.t("status_favorites"),
+            StatusChip::Flag(flag) => {
+                let name = board.i18n.t(flag_label_key(*flag));
+                board.i18n.t("status_flag").replace("{flag}", &name)
+            }
+            StatusChip::Gallery => board.i18n.t("status_gallery"),
+        };
+        h_flex()
+            .items_center()
+            .gap_1()
+            .pl_2()
+            .rounded_md()
+            .bg(cx.theme().secondary)
+            .text_xs()
+            .text_color(cx.theme().secondary_foreground)
+            .child(
+                div()
+                    .max_w_32()
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .child(label),
+            )
+            .child(
+                Button::new(("status-chip-clear", index))
+                    .xsmall()
+                    .ghost()
+                    .label("×")
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        match &chip {
+                            StatusChip::Query(_) => {
+                                this.search_input.update(cx, |input, cx| {
+                                    input.set_value("", window, cx);
+                                });
+                            }
+                            StatusChip::ContentFilter(_) => {
+                                this.content_filter = ContentFilter::All
+                            }
+                            StatusChip::FavoritesOnly => this.favorites_only = false,
+                            StatusChip::Flag(_) => this.flag_filter = None,
+                            StatusChip::Gallery => {
+                                this.toggle_gallery(cx);
+                                return;
+                            }
+                        }
+                        this.selected_index = 0;
+                        cx.notify();
+                    })),
+            )
+    });
+
+    h_flex()
+        .w_full()
+        .items_center()
+        .justify_between()
+        .gap_2()
+        .mb_2()
+        .child(h_flex().gap_1().flex_wrap().children(chip_elements))
+        .child(
+            div()
+                .flex_shrink_0()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(counts),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_chips() {
+        let none = ListFilters::default();
+        assert!(status_chips("", &none).is_empty());
+        assert!(status_chips("   ", &none).is_empty());
+        let all = ListFilters {
+            content_filter: ContentFilter::Image,
+            favorites_only: true,
+            flag_filter: Some(RecordFlag::Blue),
+            gallery_mode: true,
+            ..ListFilters::default()
+        };
+        assert_eq!(
+            status_chips(" todo ", &all),
+            vec![
+                StatusChip::Query("todo".to_string()),
+                StatusChip::ContentFilter(ContentFilter::Image),
+                StatusChip::FavoritesOnly,
+                StatusChip::Flag(RecordFlag::Blue),
+                StatusChip::Gallery,
+            ]
+        );
+    }
+}
@@ -0,0 +1,294 @@
+//! Keyboard-driven editor for a record's tags
+
+use gpui::{
+    Context, Focusable, KeyDownEvent, Window, div,
+    prelude::{FluentBuilder, InteractiveElement, IntoElement, ParentElement, Styled},
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::input::Input;
+use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
+
+use super::{RopyBoard, ToastLevel};
+use crate::repository::ClipboardRecord;
+use crate::repository::fuzzy::best_matches;
+
+/// Existing tags offered below the input
+const MAX_TAG_SUGGESTIONS: usize = 5;
+
+/// Known tags matching what is typed, best first, leaving out those the record already has
+fn tag_suggestions<'a>(typed: &str, known: &'a [String], current: &[String]) -> Vec<&'a str> {
+    let candidates: Vec<&str> = known
+        .iter()
+        .filter(|tag| !current.contains(tag))
+        .map(String::as_str)
+        .collect();
+    let typed = typed.trim().trim_start_matches('#');
+    let matches = if typed.is_empty() {
+        candidates
+    } else {
+        best_matches(typed, &candidates)
+    };
+    matches.into_iter().take(MAX_TAG_SUGGESTIONS).collect()
+}
+
+impl RopyBoard {
+    /// Open the tag editor for the selected record
+    pub(super) fn open_tag_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(id) = self
+            .filtered_records
+            .get(self.selected_index)
+            .map(|record| record.id)
+        else {
+            return;
+        };
+        if self.refuse_private_edit(id) {
+            cx.notify();
+            return;
+        }
+        self.known_tags = match self.repository {
+            Some(ref repo) => repo.all_tags().unwrap_or_default(),
+            None => Vec::new(),
+        };
+        self.editing_tags = Some(id);
+        self.tag_input.update(cx, |input, cx| {
+            input.set_value("", window, cx);
+        });
+        window.focus(&self.tag_input.focus_handle(cx));
+        cx.notify();
+    }
+
+    pub(super) fn close_tag_editor(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.editing_tags = None;
+        window.focus(&self.focus_handle);
+        cx.notify();
+    }
+
+    /// Tags of the record being edited
+    ///
+    /// Read from the rows shown, which also hold records found by a search beyond the
+    /// loaded history.
+    fn edited_tags(&self) -> Vec<String> {
+        let Some(id) = self.editing_tags else {
+            return Vec::new();
+        };
+        self.filtered_records
+            .iter()
+            .find(|r| r.id == id)
+            .map(|record| record.tags.clone())
+            .unwrap_or_default()
+    }
+
+    /// Add or remove a tag on the record being edited, keeping the editor open
+    fn change_tag(&mut self, tag: &str, add: bool, cx: &mut Context<Self>) {
+        let Some(id) = self.editing_tags else {
+            return;
+        };
+        let Some(ref repo) = self.repository else {
+            return;
+        };
+        let result = if add {
+            repo.add_tag(id, tag)
+        } else {
+            repo.remove_tag(id, tag)
+        };
+        match result {
+            Ok(Some(updated)) => {
+                for tag in &updated.tags {
+                    if let Err(index) = self.known_tags.binary_search(tag) {
+                        self.known_tags.insert(index, tag.clone());
+                    }
+                }
+                self.replace_record(updated);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to update clipboard record: {e}");
+                self.show_toast(self.i18n.t("toast_update_failed"), ToastLevel::Error);
+            }
+        }
+        cx.notify();
+    }
+
+    /// Show an updated record in the rows right away, and in the loaded history if it is there
+    fn replace_record(&mut self, updated: ClipboardRecord) {
+        if let Some(existing) = self
+            .records
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|r| r.id == updated.id)
+        {
+            *existing = updated.clone();
+        }
+        if let Some(existing) = self
+            .filtered_records
+            .iter_mut()
+            .find(|r| r.id == updated.id)
+        {
+            *existing = updated;
+        }
+    }
+
+    /// Add the typed tag, or close the editor when nothing is typed
+    fn submit_tag(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let typed = self.tag_input.read(cx).value().to_string();
+        if typed.trim().is_empty() {
+            self.close_tag_editor(window, cx);
+            return;
+        }
+        self.change_tag(&typed, true, cx);
+        self.tag_input.update(cx, |input, cx| {
+            input.set_value("", window, cx);
+        });
+    }
+
+    /// Enter adds, Tab completes the best suggestion, Backspace in an empty input removes the
+    /// last tag and Escape closes, before the input or the board sees the keys
+    pub(super) fn on_tag_key(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.editing_tags.is_none() {
+            return;
+        }
+        let typed = self.tag_input.read(cx).value().to_string();
+        match event.keystroke.key.as_str() {
+            "enter" => self.submit_tag(window, cx),
+            "escape" => self.close_tag_editor(window, cx),
+            "tab" => {
+                let current = self.edited_tags();
+                let Some(suggestion) = tag_suggestions(&typed, &self.known_tags, &current)
+                    .first()
+                    .map(|tag| tag.to_string())
+                else {
+                    return;
+                };
+                self.tag_input.update(cx, |input, cx| {
+                    input.set_value(suggestion, window, cx);
+                });
+            }
+            "backspace" if typed.is_empty() => {
+                let Some(last) = self.edited_tags().pop() else {
+                    return;
+                };
+                self.change_tag(&last, false, cx);
+            }
+            _ => return,
+        }
+        cx.stop_propagation();
+    }
+}
+
+/// Render the tag editor over the board while a record's tags are being edited
+pub(super) fn render_tag_editor(
+    board: &RopyBoard,
+    cx: &mut Context<'_, RopyBoard>,
+) -> Option<impl IntoElement> {
+    board.editing_tags?;
+    let current = board.edited_tags();
+    let typed = board.tag_input.read(cx).value().to_string();
+    let suggestions: Vec<String> = tag_suggestions(&typed, &board.known_tags, &current)
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let chips: Vec<_> = current
+        .into_iter()
+        .enumerate()
+        .map(|(index, tag)| {
+            h_flex()
+                .items_center()
+                .gap_1()
+                .pl_2()
+                .rounded_md()
+                .bg(cx.theme().muted)
+                .text_xs()
+                .child(format!("#{tag}"))
+                .child(
+                    Button::new(("remove-tag", index))
+                        .xsmall()
+                        .ghost()
+                        .label("×")
+                        .tooltip(board.i18n.t("tag_remove"))
+                        .on_click(
+                            cx.listener(move |this, _, _, cx| this.change_tag(&tag, false, cx)),
+                        ),
+                )
+        })
+        .collect();
+    let suggestion_buttons: Vec<_> = suggestions
+        .into_iter()
+        .enumerate()
+        .map(|(index, tag)| {
+            Button::new(("suggest-tag", index))
+                .xsmall()
+                .ghost()
+                .label(format!("#{tag}"))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.change_tag(&tag, true, cx);
+                    this.tag_input.update(cx, |input, cx| {
+                        input.set_value("", window, cx);
+                    });
+                }))
+        })
+        .collect();
+
+    Some(
+        v_flex()
+            .id("tag-editor")
+            .absolute()
+            .top(gpui::px(48.))
+            .left_4()
+            .right_4()
+            .p_2()
+            .gap_1()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .shadow_lg()
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(board.i18n.t("tag_editor_hint")),
+            )
+            .child(h_flex().flex_wrap().gap_1().children(chips))
+            .child(
+                Input::new(&board.tag_input)
+                    .appearance(false)
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .rounded_md()
+                    .px_2()
+                    .py_1(),
+            )
+            .when(!board.known_tags.is_empty(), |this| {
+                this.child(h_flex().flex_wrap().gap_1().children(suggestion_buttons))
+            }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_suggestions() {
+        let known: Vec<String> = ["api", "personal", "project", "work"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let current = vec!["work".to_string()];
+
+        assert_eq!(
+            tag_suggestions("", &known, &current),
+            vec!["api", "personal", "project"]
+        );
+        assert_eq!(tag_suggestions("#prj", &known, &current), vec!["project"]);
+        // Tags already on the record are never offered
+        assert!(tag_suggestions("work", &known, &current).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Exporting, importing and resetting the appearance as a shareable theme file
+
+use std::path::Path;
+
+use gpui::{Context, PathPromptOptions, Window};
+
+use super::{RopyBoard, ToastLevel};
+use crate::config::{AppTheme, ThemeColors, ThemeFile, write_atomic};
+
+/// Suggested file name for an exported theme
+const THEME_FILE_NAME: &str = "ropy-theme.toml";
+
+impl RopyBoard {
+    /// Ask where to save the current appearance and write it there as TOML
+    pub(super) fn export_theme(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let theme = ThemeFile::from_settings(&self.settings.read().unwrap());
+        let directory = dirs::home_dir().unwrap_or_else(std::env::temp_dir);
+        let path_rx = cx.prompt_for_new_path(&directory, Some(THEME_FILE_NAME));
+
+        cx.spawn_in(window, async move |this, cx| {
+            let path = match path_rx.await {
+                Ok(Ok(Some(path))) => path,
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(e)) => {
+                    log::error!("Failed to choose a theme export path: {e}");
+                    return;
+                }
+            };
+            let result = theme
+                .to_toml()
+                .map_err(|e| e.to_string())
+                .and_then(|text| write_atomic(&path, text).map_err(|e| e.to_string()));
+            this.update(cx, |board, cx| {
+                match result {
+                    Ok(()) => {
+                        let message = board
+                            .i18n
+                            .t("toast_theme_exported")
+                            .replace("{path}", &path.display().to_string());
+                        board.show_toast(message, ToastLevel::Info);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to export theme to {}: {e}", path.display());
+                        board.show_toast(
+                            board.i18n.t("toast_theme_export_failed"),
+                            ToastLevel::Error,
+                        );
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Ask for a theme file and apply it once it has been validated
+    pub(super) fn import_theme(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let paths_rx = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: None,
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let path = match paths_rx.await {
+                Ok(Ok(Some(paths))) => match paths.into_iter().next() {
+                    Some(path) => path,
+                    None => return,
+                },
+                Ok(Ok(None)) | Err(_) => return,
+                Ok(Err(e)) => {
+                    log::error!("Failed to choose a theme file: {e}");
+                    return;
+                }
+            };
+            this.update_in(cx, |board, window, cx| {
+                board.finish_import_theme(&path, window, cx);
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn finish_import_theme(&mut self, path: &Path, window: &mut Window, cx: &mut Context<Self>) {
+        let theme = std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| ThemeFile::from_toml(&text).map_err(|e| e.to_string()));
+        match theme {
+            Ok(theme) => {
+                self.update_appearance(|settings| theme.apply_to(settings), window, cx);
+                self.show_toast(self.i18n.t("toast_theme_imported"), ToastLevel::Info);
+            }
+            Err(e) => {
+                log::error!("Failed to import theme from {}: {e}", path.display());
+                self.show_toast(self.i18n.t("toast_theme_import_failed"), ToastLevel::Error);
+            }
+        }
+    }
+
+    /// Drop the custom colors, going back to the built-in Light/Dark/System preset
+    pub(super) fn reset_theme_colors(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.update_appearance(
+            |settings| settings.appearance.custom_colors = ThemeColors::default(),
+            window,
+            cx,
+        );
+    }
+
+    /// Change the appearance settings, save them and apply the result right away
+    fn update_appearance(
+        &mut self,
+        change: impl FnOnce(&mut crate::config::Settings),
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let (result, app_theme, colors) = {
+            let mut settings = self.settings.write().unwrap();
+            change(&mut settings);
+            (
+                settings.save(),
+                settings.theme.clone(),
+                settings.appearance.custom_colors.clone(),
+            )
+        };
+        if let Err(e) = result {
+            log::error!("Failed to save settings: {e}");
+            self.show_toast(self.i18n.t("toast_settings_save_failed"), ToastLevel::Error);
+        }
+        self.selected_theme = match app_theme {
+            AppTheme::Light => 0,
+            AppTheme::Dark => 1,
+            AppTheme::System => 2,
+        };
+        crate::gui::app::set_app_theme(window, cx, &app_theme.get_theme(), &colors);
+        cx.notify();
+    }
+}
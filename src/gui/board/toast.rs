@@ -0,0 +1,119 @@
+use gpui::{
+    App, div,
+    prelude::{FluentBuilder, InteractiveElement, IntoElement, ParentElement, Styled},
+};
+use gpui_component::{ActiveTheme, v_flex};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long a toast stays on screen
+const TOAST_DURATION: Duration = Duration::from_secs(3);
+/// Maximum number of toasts shown at once; older ones are dropped first
+const MAX_TOASTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastLevel {
+    Info,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+struct Toast {
+    message: String,
+    level: ToastLevel,
+    shown_at: Instant,
+}
+
+/// Queue of transient messages rendered over the board
+#[derive(Debug, Default)]
+pub struct ToastQueue {
+    toasts: VecDeque<Toast>,
+}
+
+impl ToastQueue {
+    pub fn push(&mut self, message: String, level: ToastLevel, now: Instant) {
+        // Repeated failures would otherwise flood the queue with the same message
+        self.toasts
+            .retain(|toast| toast.message != message || toast.level != level);
+        if self.toasts.len() >= MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            message,
+            level,
+            shown_at: now,
+        });
+    }
+
+    /// Drop toasts shown longer than the toast duration, returning whether any were removed
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.toasts.len();
+        self.toasts
+            .retain(|toast| now.duration_since(toast.shown_at) < TOAST_DURATION);
+        self.toasts.len() != before
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// Message, level and time shown of the newest toast
+    pub fn latest(&self) -> Option<(&str, ToastLevel, Instant)> {
+        self.toasts
+            .back()
+            .map(|toast| (toast.message.as_str(), toast.level, toast.shown_at))
+    }
+}
+
+/// Render the queued toasts stacked at the bottom of the window
+pub fn render_toasts(queue: &ToastQueue, cx: &App) -> impl IntoElement {
+    v_flex()
+        .id("toasts")
+        .absolute()
+        .bottom_4()
+        .left_4()
+        .right_4()
+        .gap_2()
+        .children(queue.toasts.iter().map(|toast| {
+            div()
+                .w_full()
+                .px_3()
+                .py_2()
+                .rounded_md()
+                .shadow_lg()
+                .border_1()
+                .text_sm()
+                .bg(cx.theme().popover)
+                .text_color(cx.theme().popover_foreground)
+                .border_color(cx.theme().border)
+                .when(toast.level == ToastLevel::Error, |this| {
+                    this.border_color(cx.theme().danger)
+                        .text_color(cx.theme().danger)
+                })
+                .child(toast.message.clone())
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_queue_expire_and_limit() {
+        let start = Instant::now();
+        let mut queue = ToastQueue::default();
+        for i in 0..4 {
+            queue.push(format!("message {i}"), ToastLevel::Info, start);
+        }
+        assert_eq!(queue.toasts.len(), MAX_TOASTS);
+        assert_eq!(queue.toasts[0].message, "message 1");
+
+        queue.push("message 3".to_string(), ToastLevel::Info, start);
+        assert_eq!(queue.toasts.len(), MAX_TOASTS);
+        assert_eq!(queue.latest(), Some(("message 3", ToastLevel::Info, start)));
+
+        assert!(!queue.expire(start + Duration::from_secs(1)));
+        assert!(queue.expire(start + TOAST_DURATION));
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::{DateTime, Duration, Local};
+use gpui::{
+    Context, div,
+    prelude::{InteractiveElement, IntoElement, ParentElement, StatefulInteractiveElement, Styled},
+};
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{ActiveTheme, Sizable, h_flex, v_flex};
+
+use super::RopyBoard;
+use super::render::format_clipboard_content;
+#[cfg(target_os = "windows")]
+use crate::gui::utils::start_window_drag;
+use crate::i18n::I18n;
+use crate::repository::models::ContentType;
+
+/// Time left until a trashed record is purged, e.g. `5h 12m`
+fn format_remaining(remaining: Duration, i18n: &I18n) -> String {
+    if remaining <= Duration::zero() {
+        return i18n.t("trash_purging");
+    }
+    let hours = remaining.num_hours();
+    let minutes = remaining.num_minutes() % 60;
+    let remaining = if hours > 0 {
+        i18n.t("trash_hours_minutes")
+            .replace("{hours}", &hours.to_string())
+            .replace("{minutes}", &minutes.to_string())
+    } else {
+        i18n.t("trash_minutes")
+            .replace("{minutes}", &minutes.max(1).to_string())
+    };
+    i18n.t("trash_purged_in").replace("{time}", &remaining)
+}
+
+/// Time at which a record deleted at `deleted_at` will be purged
+fn purge_time(deleted_at: DateTime<Local>, retention_hours: u32) -> DateTime<Local> {
+    deleted_at + Duration::hours(retention_hours.into())
+}
+
+/// Render the recently deleted records with restore buttons
+pub(super) fn render_trash_content(
+    board: &mut RopyBoard,
+    cx: &mut Context<RopyBoard>,
+) -> impl IntoElement {
+    let (retention_hours, collapse_whitespace) = {
+        let settings = board.settings.read().unwrap();
+        (
+            settings.storage.trash_retention_hours,
+            settings.appearance.collapse_whitespace,
+        )
+    };
+    let trashed = board.trash_entries.clone();
+    let now = Local::now();
+
+    let header = h_flex()
+        .justify_between()
+        .items_center()
+        .mb_4()
+        .pt_4()
+        .child(
+            Button::new("trash-back-button")
+                .small()
+                .ghost()
+                .label(board.i18n.t("about_back"))
+                .on_click(cx.listener(|board, _, window, cx| {
+                    board.show_trash = false;
+                    window.focus(&board.focus_handle);
+                    cx.notify();
+                }))
+                .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+        )
+        .child(
+            div()
+                .text_lg()
+                .text_color(cx.theme().foreground)
+                .font_weight(gpui::FontWeight::BOLD)
+                .child(board.i18n.t("trash_title")),
+        )
+        .child(
+            Button::new("restore-all-button")
+                .small()
+                .ghost()
+                .label(board.i18n.t("trash_restore_all"))
+                .on_click(cx.listener(|board, _, _, cx| {
+                    board.restore_all_records();
+                    cx.notify();
+                }))
+                .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation()),
+        );
+
+    #[cfg(target_os = "windows")]
+    let header = header.on_mouse_down(gpui::MouseButton::Left, |_, window, cx| {
+        start_window_drag(window, cx);
+    });
+
+    let restore_label = board.i18n.t("trash_restore");
+    let image_label = board.i18n.t("content_type_image");
+    let svg_label = board.i18n.t("content_type_svg");
+    let file_label = board.i18n.t("content_type_file");
+
+    let body = if trashed.is_empty() {
+        v_flex().flex_1().items_center().justify_center().child(
+            div()
+                .text_sm()
+                .text_color(cx.theme().muted_foreground)
+                .child(board.i18n.t("trash_empty")),
+        )
+    } else {
+        v_flex()
+            .flex_1()
+            .gap_2()
+            .children(trashed.into_iter().map(|entry| {
+                let id = entry.record.id;
+                let summary = match entry.record.content_type {
+                    ContentType::Text => {
+                        format_clipboard_content(&entry.record, collapse_whitespace)
+                    }
+                    ContentType::Image => image_label.clone(),
+                    ContentType::FilePath => format!("{file_label}: {}", entry.record.content),
+                    ContentType::Svg => svg_label.clone(),
+                };
+                let remaining = purge_time(entry.deleted_at, retention_hours) - now;
+                h_flex()
+                    .id(("trash-record", id as usize))
+                    .justify_between()
+                    .items_center()
+                    .gap_2()
+                    .p_2()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(cx.theme().border)
+                    .bg(cx.theme().secondary)
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .min_w_0()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(cx.theme().foreground)
+                                    .overflow_hidden()
+                                    .text_ellipsis()
+                                    .whitespace_nowrap()
+                                    .child(summary),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(format_remaining(remaining, &board.i18n)),
+                            ),
+                    )
+                    .child(
+                        Button::new(("restore-button", id as usize))
+                            .small()
+                            .ghost()
+                            .label(restore_label.clone())
+                            .on_click(cx.listener(move |board, _, _, cx| {
+                                board.restore_record(id);
+                                cx.notify();
+                            })),
+                    )
+            }))
+    };
+
+    v_flex().size_full().child(header).child(
+        div()
+            .id("trash-list")
+            .flex_1()
+            .overflow_y_scroll()
+            .child(body),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::Language;
+
+    #[test]
+    fn test_format_remaining() {
+        let i18n = I18n::new(Language::English).unwrap();
+        assert_eq!(
+            format_remaining(Duration::minutes(312), &i18n),
+            "Purged in 5h 12m"
+        );
+        assert_eq!(
+            format_remaining(Duration::seconds(20), &i18n),
+            "Purged in 1m"
+        );
+        assert_eq!(
+            format_remaining(Duration::seconds(-5), &i18n),
+            i18n.t("trash_purging")
+        );
+    }
+}
@@ -1,46 +1,149 @@
+use crate::config::Settings;
 use global_hotkey::HotKeyState;
 use global_hotkey::hotkey::HotKey;
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager};
 use gpui::{BackgroundExecutor, ForegroundExecutor};
+use std::collections::HashMap;
 use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum HotkeyError {
+    #[error("Hotkey is empty")]
+    Empty,
+    #[error("Hotkey has no main key: {0}")]
+    MissingKey(String),
+    #[error("Hotkey has more than one main key: {0}")]
+    MultipleKeys(String),
+    #[error("Failed to parse hotkey {0}: {1}")]
+    Parse(String, String),
+}
+
+/// Modifier names in canonical order, paired with their accepted aliases
+const MODIFIERS: [(&str, &[&str]); 4] = [
+    ("control", &["control", "ctrl"]),
+    ("alt", &["alt", "opt", "option"]),
+    ("shift", &["shift"]),
+    ("cmd", &["cmd", "command", "super", "meta"]),
+];
+
+/// Normalize a user-entered hotkey string into its canonical form.
+///
+/// Casing and whitespace are ignored, modifier aliases are mapped to a single
+/// name and modifiers are sorted, e.g. `Ctrl + Shift + D` becomes `control+shift+d`.
+pub fn normalize_hotkey(input: &str) -> Result<String, HotkeyError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(HotkeyError::Empty);
+    }
+
+    let mut modifiers = [false; MODIFIERS.len()];
+    let mut key: Option<String> = None;
+    for part in input.split('+') {
+        let part = part.trim().to_lowercase();
+        if part.is_empty() {
+            continue;
+        }
+        if let Some(index) = MODIFIERS
+            .iter()
+            .position(|(_, aliases)| aliases.contains(&part.as_str()))
+        {
+            modifiers[index] = true;
+        } else if key.is_some() {
+            return Err(HotkeyError::MultipleKeys(input.to_string()));
+        } else {
+            key = Some(part);
+        }
+    }
+
+    let key = key.ok_or_else(|| HotkeyError::MissingKey(input.to_string()))?;
+    let normalized = MODIFIERS
+        .iter()
+        .zip(modifiers)
+        .filter(|(_, enabled)| *enabled)
+        .map(|((name, _), _)| *name)
+        .chain(std::iter::once(key.as_str()))
+        .collect::<Vec<_>>()
+        .join("+");
+
+    normalized
+        .parse::<HotKey>()
+        .map_err(|e| HotkeyError::Parse(input.to_string(), e.to_string()))?;
+    Ok(normalized)
+}
+
+/// Actions that can be triggered by a global hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Show and focus the clipboard window
+    Activate,
+    /// Clear the OS clipboard
+    ClearClipboard,
+}
+
+/// Hotkey strings paired with the action they trigger
+pub type HotkeyBindings = Vec<(HotkeyAction, String)>;
+
+/// Build the hotkey bindings from settings. Empty hotkeys are skipped.
+pub fn bindings_from_settings(settings: &Settings) -> HotkeyBindings {
+    [
+        (HotkeyAction::Activate, &settings.hotkey.activation_key),
+        (
+            HotkeyAction::ClearClipboard,
+            &settings.hotkey.clear_clipboard_key,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, hotkey)| !hotkey.is_empty())
+    .map(|(action, hotkey)| (action, hotkey.clone()))
+    .collect()
+}
 
 /// Start a global hotkey listener in a background task with a custom callback.
 ///
-/// Registers the configured hotkey and invokes the provided callback when the hotkey is pressed.
-/// Returns a sender to update the hotkey string dynamically.
-pub fn start_hotkey_listener<F>(
-    initial_hotkey: String,
+/// Registers the configured hotkeys and invokes the provided callback with the matching
+/// action when one is pressed. After every (re)registration `on_registered` receives the
+/// hotkeys that could not be registered, which is empty when all of them succeeded.
+/// Returns a sender to update the bindings dynamically.
+pub fn start_hotkey_listener<F, G>(
+    initial_bindings: HotkeyBindings,
     fg_executor: ForegroundExecutor,
     bg_executor: BackgroundExecutor,
     on_hotkey: F,
-) -> async_channel::Sender<String>
+    on_registered: G,
+) -> async_channel::Sender<HotkeyBindings>
 where
-    F: Fn() + 'static,
+    F: Fn(HotkeyAction) + 'static,
+    G: Fn(Vec<String>) + 'static,
 {
-    let (tx, rx) = async_channel::unbounded::<String>();
+    let (tx, rx) = async_channel::unbounded::<HotkeyBindings>();
     fg_executor
         .spawn(async move {
-            let mut current_hotkey = initial_hotkey;
-            let mut _manage_handle = register_hotkey(&current_hotkey);
+            let mut current_bindings = initial_bindings;
+            let (mut _manage_handle, mut actions, failed) = register_hotkeys(&current_bindings);
+            on_registered(failed);
             let receiver = GlobalHotKeyEvent::receiver();
             loop {
                 // Check for hotkey updates
                 let mut updated = false;
-                while let Ok(new_hotkey) = rx.try_recv() {
-                    current_hotkey = new_hotkey;
+                while let Ok(new_bindings) = rx.try_recv() {
+                    current_bindings = new_bindings;
                     updated = true;
                 }
 
                 if updated {
                     drop(_manage_handle);
-                    _manage_handle = register_hotkey(&current_hotkey);
+                    let failed;
+                    (_manage_handle, actions, failed) = register_hotkeys(&current_bindings);
+                    on_registered(failed);
                 }
 
                 // Poll for hotkey events
                 if let Ok(event) = receiver.try_recv()
                     && event.state() == HotKeyState::Pressed
+                    && let Some(action) = actions.get(&event.id())
                 {
-                    on_hotkey();
+                    on_hotkey(*action);
                 }
 
                 // Small sleep to avoid busy waiting
@@ -51,37 +154,55 @@ where
     tx
 }
 
-fn register_hotkey(hotkey_str: &str) -> Option<GlobalHotKeyManager> {
-    if hotkey_str.is_empty() {
-        return None;
+/// Register all bindings on a single manager
+///
+/// Returns the manager, a map from hotkey ID to action and the hotkeys that failed to register,
+/// e.g. because another application already uses them.
+fn register_hotkeys(
+    bindings: &[(HotkeyAction, String)],
+) -> (
+    Option<GlobalHotKeyManager>,
+    HashMap<u32, HotkeyAction>,
+    Vec<String>,
+) {
+    let mut actions = HashMap::new();
+    let mut failed = Vec::new();
+    if bindings.is_empty() {
+        return (None, actions, failed);
     }
     let manager = match GlobalHotKeyManager::new() {
         Ok(m) => m,
         Err(err) => {
-            eprintln!("Failed to create GlobalHotKeyManager: {err}");
-            return None;
+            log::error!("Failed to create GlobalHotKeyManager: {err}");
+            failed.extend(bindings.iter().map(|(_, hotkey)| hotkey.clone()));
+            return (None, actions, failed);
         }
     };
-    match hotkey_str.parse::<HotKey>() {
-        Ok(hotkey) => {
-            if let Err(err) = manager.register(hotkey) {
-                eprintln!(
-                    "Failed to register hotkey {}: {}. The hotkey listener will not be available.",
-                    hotkey_str, err
+    for (action, hotkey_str) in bindings {
+        match hotkey_str.parse::<HotKey>() {
+            Ok(hotkey) => {
+                if let Err(err) = manager.register(hotkey) {
+                    log::error!(
+                        "Failed to register hotkey {}: {}. The hotkey listener will not be available.",
+                        hotkey_str,
+                        err
+                    );
+                    failed.push(hotkey_str.clone());
+                } else {
+                    actions.insert(hotkey.id(), *action);
+                }
+            }
+            Err(err) => {
+                log::error!(
+                    "Failed to parse hotkey {}: {}. The hotkey listener will not be available.",
+                    hotkey_str,
+                    err
                 );
-                None
-            } else {
-                Some(manager)
+                failed.push(hotkey_str.clone());
             }
         }
-        Err(err) => {
-            eprintln!(
-                "Failed to parse hotkey {}: {}. The hotkey listener will not be available.",
-                hotkey_str, err
-            );
-            None
-        }
     }
+    (Some(manager), actions, failed)
 }
 
 #[cfg(test)]
@@ -89,6 +210,72 @@ mod tests {
     use super::*;
     use global_hotkey::GlobalHotKeyEvent;
 
+    #[test]
+    fn test_normalize_hotkey_variants() {
+        assert_eq!(normalize_hotkey("ctrl+shift+d").unwrap(), "control+shift+d");
+        assert_eq!(
+            normalize_hotkey("Ctrl + Shift + D").unwrap(),
+            "control+shift+d"
+        );
+        assert_eq!(
+            normalize_hotkey("shift+control+d").unwrap(),
+            "control+shift+d"
+        );
+        assert_eq!(normalize_hotkey("Command+Shift+V").unwrap(), "shift+cmd+v");
+        assert_eq!(normalize_hotkey("opt+space").unwrap(), "alt+space");
+    }
+
+    #[test]
+    fn test_normalize_hotkey_errors() {
+        assert_eq!(normalize_hotkey("  "), Err(HotkeyError::Empty));
+        assert!(matches!(
+            normalize_hotkey("ctrl+shift"),
+            Err(HotkeyError::MissingKey(_))
+        ));
+        assert!(matches!(
+            normalize_hotkey("ctrl+a+b"),
+            Err(HotkeyError::MultipleKeys(_))
+        ));
+        assert!(matches!(
+            normalize_hotkey("ctrl+notakey"),
+            Err(HotkeyError::Parse(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_bindings_from_settings() {
+        let mut settings = Settings::default();
+        assert_eq!(
+            bindings_from_settings(&settings),
+            vec![(
+                HotkeyAction::Activate,
+                settings.hotkey.activation_key.clone()
+            )]
+        );
+
+        settings.hotkey.clear_clipboard_key = "control+shift+x".to_string();
+        let bindings = bindings_from_settings(&settings);
+        assert_eq!(bindings.len(), 2);
+        assert_eq!(
+            bindings[1],
+            (HotkeyAction::ClearClipboard, "control+shift+x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_register_hotkeys_reports_failures() {
+        let (_, actions, failed) = register_hotkeys(&[]);
+        assert!(actions.is_empty());
+        assert!(failed.is_empty());
+
+        let (_manager, actions, failed) = register_hotkeys(&[
+            (HotkeyAction::Activate, "control+shift+notakey".to_string()),
+            (HotkeyAction::ClearClipboard, "control+shift+f9".to_string()),
+        ]);
+        assert_eq!(failed[0], "control+shift+notakey");
+        assert_eq!(actions.len() + failed.len(), 2);
+    }
+
     #[test]
     fn test_hotkey_registration_and_unregistration() {
         // This test verifies registration/unregistration and receiver availability
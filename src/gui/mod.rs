@@ -1,6 +1,8 @@
 mod app;
 mod board;
 mod hotkey;
+#[cfg(target_os = "linux")]
+mod session;
 mod tray;
 mod utils;
 #[cfg(target_os = "linux")]
@@ -0,0 +1,71 @@
+use std::env;
+
+/// Display server the Linux session runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionType {
+    X11,
+    Wayland,
+    Unknown,
+}
+
+impl SessionType {
+    /// Detect the session type from the environment
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var("XDG_SESSION_TYPE").ok().as_deref(),
+            env::var("WAYLAND_DISPLAY").ok().as_deref(),
+            env::var("DISPLAY").ok().as_deref(),
+        )
+    }
+
+    fn from_env(
+        session_type: Option<&str>,
+        wayland_display: Option<&str>,
+        display: Option<&str>,
+    ) -> Self {
+        let is_set = |value: Option<&str>| value.is_some_and(|v| !v.is_empty());
+        match session_type.map(str::to_lowercase).as_deref() {
+            Some("wayland") => return Self::Wayland,
+            Some("x11") => return Self::X11,
+            _ => {}
+        }
+        // XDG_SESSION_TYPE is missing or "tty", so fall back to the display variables.
+        // WAYLAND_DISPLAY wins because XWayland also sets DISPLAY.
+        if is_set(wayland_display) {
+            Self::Wayland
+        } else if is_set(display) {
+            Self::X11
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_type_from_env() {
+        assert_eq!(
+            SessionType::from_env(Some("wayland"), None, Some(":0")),
+            SessionType::Wayland
+        );
+        assert_eq!(
+            SessionType::from_env(Some("X11"), Some("wayland-0"), None),
+            SessionType::X11
+        );
+        assert_eq!(
+            SessionType::from_env(None, Some("wayland-0"), Some(":0")),
+            SessionType::Wayland
+        );
+        assert_eq!(
+            SessionType::from_env(Some("tty"), Some(""), Some(":0")),
+            SessionType::X11
+        );
+        assert_eq!(
+            SessionType::from_env(None, None, None),
+            SessionType::Unknown
+        );
+    }
+}
@@ -13,20 +13,32 @@ use tray_icon::{
     menu::{Menu, MenuId, MenuItem},
 };
 
+/// IDs of the tray menu items
+pub struct TrayMenuIds {
+    pub show: MenuId,
+    pub privacy: MenuId,
+    pub next_profile: MenuId,
+    pub quit: MenuId,
+}
+
 /// Initialize and return the tray icon
 pub fn init_tray(
     settings: Arc<RwLock<Settings>>,
-) -> Result<(TrayIcon, MenuId, MenuId), Box<dyn std::error::Error>> {
+) -> Result<(TrayIcon, TrayMenuIds), Box<dyn std::error::Error>> {
     let language = settings.read().unwrap().language;
     let i18n = I18n::new(language).unwrap_or_default();
 
     // Create menu items
     let show_item = MenuItem::new(i18n.t("tray_show"), true, None);
+    let privacy_item = MenuItem::new(i18n.t("tray_privacy"), true, None);
+    let profile_item = MenuItem::new(i18n.t("tray_next_profile"), true, None);
     let quit_item = MenuItem::new(i18n.t("tray_quit"), true, None);
 
     // Create menu
     let tray_menu = Menu::new();
     tray_menu.append(&show_item)?;
+    tray_menu.append(&privacy_item)?;
+    tray_menu.append(&profile_item)?;
     tray_menu.append(&quit_item)?;
 
     let icon = create_icon()?;
@@ -39,7 +51,13 @@ pub fn init_tray(
         .with_menu_on_left_click(false)
         .build()?;
 
-    Ok((tray, show_item.id().clone(), quit_item.id().clone()))
+    let ids = TrayMenuIds {
+        show: show_item.id().clone(),
+        privacy: privacy_item.id().clone(),
+        next_profile: profile_item.id().clone(),
+        quit: quit_item.id().clone(),
+    };
+    Ok((tray, ids))
 }
 
 /// Create a simple icon for the tray
@@ -55,6 +73,8 @@ fn create_icon() -> Result<Icon, Box<dyn std::error::Error>> {
 
 pub enum TrayEvent {
     Show,
+    TogglePrivacy,
+    NextProfile,
     Quit,
 }
 
@@ -65,8 +85,8 @@ pub fn start_tray_handler_inner(
     bg_executor: BackgroundExecutor,
 ) {
     match init_tray(settings) {
-        Ok((tray, show_id, quit_id)) => {
-            println!("[ropy] Tray icon initialized successfully");
+        Ok((tray, ids)) => {
+            log::info!("Tray icon initialized successfully");
             // Keep tray icon alive for the lifetime of the application
             Box::leak(Box::new(tray));
 
@@ -79,9 +99,13 @@ pub fn start_tray_handler_inner(
 
                     loop {
                         while let Ok(event) = menu_channel.try_recv() {
-                            if event.id == show_id {
+                            if event.id == ids.show {
                                 let _ = tx.send(TrayEvent::Show);
-                            } else if event.id == quit_id {
+                            } else if event.id == ids.privacy {
+                                let _ = tx.send(TrayEvent::TogglePrivacy);
+                            } else if event.id == ids.next_profile {
+                                let _ = tx.send(TrayEvent::NextProfile);
+                            } else if event.id == ids.quit {
                                 let _ = tx.send(TrayEvent::Quit);
                             }
                         }
@@ -100,7 +124,7 @@ pub fn start_tray_handler_inner(
                 .detach();
         }
         Err(e) => {
-            eprintln!("[ropy] Failed to initialize tray icon: {e}");
+            log::error!("Failed to initialize tray icon: {e}");
         }
     }
 }
@@ -1,4 +1,10 @@
-use gpui::{Context, Window};
+use crate::config::WindowAnchor;
+use crate::repository::ClipboardRepository;
+use gpui::{Bounds, Context, Pixels, Point, Size, Window, px, size};
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Arc, OnceLock};
 
 #[cfg(not(target_os = "linux"))]
 use raw_window_handle::{HasWindowHandle, RawWindowHandle};
@@ -7,11 +13,50 @@ use raw_window_handle::{HasWindowHandle, RawWindowHandle};
 use windows_sys::Win32::UI::Input::KeyboardAndMouse::ReleaseCapture;
 #[cfg(target_os = "windows")]
 use windows_sys::Win32::UI::WindowsAndMessaging::{
-    HTCAPTION, PostMessageA, SW_HIDE, SW_RESTORE, SetForegroundWindow, ShowWindow, WM_NCLBUTTONDOWN,
+    HTCAPTION, PostMessageA, SPI_GETCLIENTAREAANIMATION, SW_HIDE, SW_RESTORE, SetForegroundWindow,
+    ShowWindow, SystemParametersInfoW, WM_NCLBUTTONDOWN,
 };
 
 #[cfg(target_os = "macos")]
-use objc2::{msg_send, runtime::AnyObject};
+use objc2::{
+    encode::{Encode, Encoding},
+    msg_send,
+    runtime::AnyObject,
+};
+
+/// Distance between an anchored window and the edges of the work area
+const ANCHOR_MARGIN: f32 = 8.;
+
+/// Size of the main board window
+pub fn board_window_size() -> Size<Pixels> {
+    size(px(400.), px(600.))
+}
+
+/// Bounds of a window of `window_size` placed at `anchor` within the `work_area`
+pub fn anchored_bounds(
+    anchor: WindowAnchor,
+    work_area: Bounds<Pixels>,
+    window_size: Size<Pixels>,
+) -> Bounds<Pixels> {
+    let margin = px(ANCHOR_MARGIN);
+    let left = work_area.origin.x + margin;
+    let top = work_area.origin.y + margin;
+    // Keep the window on screen even when the work area is smaller than it
+    let right = (work_area.origin.x + work_area.size.width - window_size.width - margin).max(left);
+    let bottom =
+        (work_area.origin.y + work_area.size.height - window_size.height - margin).max(top);
+    let origin = match anchor {
+        WindowAnchor::Center => Point::new(
+            work_area.origin.x + (work_area.size.width - window_size.width) / 2.,
+            work_area.origin.y + (work_area.size.height - window_size.height) / 2.,
+        ),
+        WindowAnchor::TopLeft => Point::new(left, top),
+        WindowAnchor::TopRight => Point::new(right, top),
+        WindowAnchor::BottomLeft => Point::new(left, bottom),
+        WindowAnchor::BottomRight => Point::new(right, bottom),
+    };
+    Bounds::new(origin, window_size)
+}
 
 /// Hide the window based on the platform
 pub fn hide_window<T>(_window: &mut Window, _cx: &mut Context<T>) {
@@ -28,10 +73,14 @@ pub fn hide_window<T>(_window: &mut Window, _cx: &mut Context<T>) {
     _cx.hide();
 
     #[cfg(target_os = "linux")]
-    if let Some(x11) = crate::gui::app::X11.get() {
-        if let Err(e) = x11.hide_window() {
-            eprintln!("[ropy] Failed to hide window: {e}")
+    match crate::gui::app::X11.get() {
+        Some(x11) => {
+            if let Err(e) = x11.hide_window() {
+                log::error!("Failed to hide window: {e}")
+            }
         }
+        // Wayland clients cannot unmap themselves, so minimize instead
+        None => _window.minimize_window(),
     }
 }
 
@@ -50,12 +99,13 @@ pub fn active_window<T>(_window: &mut Window, _cx: &mut Context<T>) {
     #[cfg(target_os = "macos")]
     _cx.activate(true);
     #[cfg(target_os = "linux")]
-    {
-        if let Some(x11) = crate::gui::app::X11.get() {
+    match crate::gui::app::X11.get() {
+        Some(x11) => {
             if let Err(e) = x11.display_and_activate_window() {
-                eprintln!("[ropy] Failed to activate window: {e}")
+                log::error!("Failed to activate window: {e}")
             }
         }
+        None => _window.activate_window(),
     }
 }
 
@@ -96,10 +146,78 @@ pub fn set_always_on_top<T>(_window: &mut Window, _cx: &mut Context<T>, always_o
     {
         if let Some(x11) = crate::gui::app::X11.get() {
             if let Err(e) = x11.set_always_on_top(always_on_top) {
-                eprintln!("[ropy] Failed to set always on top: {e}")
+                log::error!("Failed to set always on top: {e}")
+            }
+        }
+    }
+}
+
+/// Point in AppKit screen coordinates, measured from the bottom of the primary display
+#[cfg(target_os = "macos")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct NSPoint {
+    x: f64,
+    y: f64,
+}
+
+#[cfg(target_os = "macos")]
+unsafe impl Encode for NSPoint {
+    const ENCODING: Encoding = Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]);
+}
+
+/// Move the window so its top-left corner is at `origin`, in screen coordinates
+///
+/// Wayland doesn't let clients place their windows, so there it stays where it is.
+pub fn move_window<T>(_window: &mut Window, _cx: &mut Context<T>, _origin: Point<Pixels>) {
+    #[cfg(target_os = "windows")]
+    if let Ok(handle) = _window.window_handle()
+        && let RawWindowHandle::Win32(handle) = handle.as_raw()
+    {
+        let scale = _window.scale_factor();
+        let hwnd = handle.hwnd.get() as *mut std::ffi::c_void;
+        unsafe {
+            use windows_sys::Win32::UI::WindowsAndMessaging::{
+                SWP_NOACTIVATE, SWP_NOSIZE, SWP_NOZORDER, SetWindowPos,
+            };
+            SetWindowPos(
+                hwnd,
+                std::ptr::null_mut(),
+                (f32::from(_origin.x) * scale) as i32,
+                (f32::from(_origin.y) * scale) as i32,
+                0,
+                0,
+                SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE,
+            );
+        }
+    }
+    #[cfg(target_os = "macos")]
+    if let Ok(handle) = _window.window_handle()
+        && let RawWindowHandle::AppKit(handle) = handle.as_raw()
+        && let Some(primary) = _cx.primary_display()
+    {
+        let top_left = NSPoint {
+            x: f32::from(_origin.x) as f64,
+            y: f32::from(primary.bounds().size.height - _origin.y) as f64,
+        };
+        let ns_view = handle.ns_view.as_ptr() as *mut AnyObject;
+        unsafe {
+            let ns_window: *mut AnyObject = msg_send![ns_view, window];
+            if !ns_window.is_null() {
+                let _: () = msg_send![ns_window, setFrameTopLeftPoint: top_left];
             }
         }
     }
+    #[cfg(target_os = "linux")]
+    if let Some(x11) = crate::gui::app::X11.get() {
+        let scale = _window.scale_factor();
+        if let Err(e) = x11.move_window(
+            (f32::from(_origin.x) * scale) as i32,
+            (f32::from(_origin.y) * scale) as i32,
+        ) {
+            log::error!("Failed to move window: {e}")
+        }
+    }
 }
 
 /// Start dragging the window
@@ -116,3 +234,225 @@ pub fn start_window_drag(window: &mut Window, _cx: &mut gpui::App) {
         }
     }
 }
+
+/// Whether Windows has a program registered for the file's extension
+///
+/// `start` exits successfully either way, showing an "Open with" picker when none is.
+#[cfg(target_os = "windows")]
+fn has_file_association(path: &Path) -> bool {
+    let Some(extension) = path.extension() else {
+        return false;
+    };
+    Command::new("cmd")
+        .args(["/D", "/C", "assoc"])
+        .arg(format!(".{}", extension.to_string_lossy()))
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Open a file with the OS default text editor
+///
+/// Fails when no editor is associated with text files.
+pub fn open_in_default_editor(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    if !has_file_association(path) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no program is associated with {}", path.display()),
+        ));
+    }
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]).arg(path);
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg("-t").arg(path);
+        command
+    };
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(path);
+        command
+    };
+
+    // These launchers return once the editor is started, and fail without a handler
+    let status = command.status()?;
+    if !status.success() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no default editor for {} ({status})", path.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Open the OS file manager with the given file selected
+///
+/// On Linux there is no common way to select a file, so its parent directory is opened.
+pub fn reveal_in_file_manager(path: &Path) -> io::Result<()> {
+    if !path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{} does not exist", path.display()),
+        ));
+    }
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    };
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    };
+    #[cfg(target_os = "linux")]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+
+    command.spawn().map(|_| ())
+}
+
+/// Whether the system asks apps to keep animations to a minimum
+///
+/// Read once per run, since it may start a process to query the setting.
+pub fn prefers_reduced_motion() -> bool {
+    static REDUCED_MOTION: OnceLock<bool> = OnceLock::new();
+    *REDUCED_MOTION.get_or_init(query_reduced_motion)
+}
+
+#[cfg(target_os = "windows")]
+fn query_reduced_motion() -> bool {
+    let mut animations_enabled: i32 = 1;
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            &mut animations_enabled as *mut i32 as *mut std::ffi::c_void,
+            0,
+        )
+    };
+    ok != 0 && animations_enabled == 0
+}
+
+#[cfg(target_os = "macos")]
+fn query_reduced_motion() -> bool {
+    command_output(
+        "defaults",
+        &["read", "com.apple.universalaccess", "reduceMotion"],
+    )
+    .is_some_and(|output| output.trim() == "1")
+}
+
+#[cfg(target_os = "linux")]
+fn query_reduced_motion() -> bool {
+    command_output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "enable-animations"],
+    )
+    .is_some_and(|output| output.trim() == "false")
+}
+
+/// Standard output of a command that exited successfully
+#[cfg(not(target_os = "windows"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Play a WAV file with the player that ships with the OS, returning once it has finished
+pub fn play_sound(path: &Path) -> io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let status = {
+        let script = format!(
+            "(New-Object Media.SoundPlayer '{}').PlaySync()",
+            path.display().to_string().replace('\'', "''")
+        );
+        Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            .status()?
+    };
+    #[cfg(target_os = "macos")]
+    let status = Command::new("afplay").arg(path).status()?;
+    #[cfg(target_os = "linux")]
+    let status = match Command::new("paplay").arg(path).status() {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Command::new("aplay").arg("-q").arg(path).status()?
+        }
+        result => result?,
+    };
+
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "failed to play {} ({status})",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchored_bounds() {
+        // A work area below a 25px menu bar
+        let work_area = Bounds::new(Point::new(px(0.), px(25.)), size(px(1920.), px(1055.)));
+        let window = size(px(400.), px(600.));
+
+        let origin = |anchor| anchored_bounds(anchor, work_area, window).origin;
+        assert_eq!(
+            origin(WindowAnchor::Center),
+            Point::new(px(760.), px(252.5))
+        );
+        assert_eq!(origin(WindowAnchor::TopLeft), Point::new(px(8.), px(33.)));
+        assert_eq!(
+            origin(WindowAnchor::TopRight),
+            Point::new(px(1512.), px(33.))
+        );
+        assert_eq!(
+            origin(WindowAnchor::BottomLeft),
+            Point::new(px(8.), px(472.))
+        );
+        assert_eq!(
+            origin(WindowAnchor::BottomRight),
+            Point::new(px(1512.), px(472.))
+        );
+
+        // Too small a work area pins the window to its top-left corner
+        let small = Bounds::new(Point::new(px(0.), px(0.)), size(px(300.), px(500.)));
+        assert_eq!(
+            anchored_bounds(WindowAnchor::BottomRight, small, window).origin,
+            Point::new(px(8.), px(8.))
+        );
+    }
+}
+
+/// Rebuild a stale search index on a background thread, searches scan until it is done
+pub fn rebuild_stale_index(repository: &Arc<ClipboardRepository>) {
+    if !repository.index_is_stale() {
+        return;
+    }
+    let repository = repository.clone();
+    std::thread::spawn(move || match repository.reindex_if_stale() {
+        Ok(_) => log::info!("Search index rebuilt"),
+        Err(e) => log::error!("Failed to rebuild the search index: {e}"),
+    });
+}
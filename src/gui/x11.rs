@@ -2,7 +2,9 @@ use std::{error::Error, io, thread, time::Duration};
 
 use x11rb::{
     connection::Connection,
-    protocol::xproto::{AtomEnum, ClientMessageEvent, ConnectionExt, EventMask},
+    protocol::xproto::{
+        AtomEnum, ClientMessageEvent, ConfigureWindowAux, ConnectionExt, EventMask,
+    },
     rust_connection::RustConnection,
     wrapper::ConnectionExt as _,
 };
@@ -109,6 +111,15 @@ impl X11 {
         self.send_wm_state_and_sync(self.net_wm_state_above, always_on_top, self.root_id)
     }
 
+    /// Move the window's top-left corner to `x`, `y` in physical pixels
+    pub fn move_window(&self, x: i32, y: i32) -> Result<(), Box<dyn Error>> {
+        self.connection
+            .configure_window(self.window_id, &ConfigureWindowAux::new().x(x).y(y))?;
+        self.connection.sync()?;
+
+        Ok(())
+    }
+
     pub fn display_and_activate_window(&self) -> Result<(), Box<dyn Error>> {
         self.display_window()?;
         self.active_window()?;
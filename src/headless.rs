@@ -0,0 +1,157 @@
+//! Headless core for embedding ropy's storage and capture without the GUI.
+
+use crate::config::Settings;
+use crate::repository::errors::RepositoryError;
+use crate::repository::{ClipboardRecord, ClipboardRepository};
+use image::DynamicImage;
+use std::io::Write;
+use std::path::Path;
+
+/// Owns the clipboard repository and applies the storage settings to captures
+pub struct RopyCore {
+    repository: ClipboardRepository,
+    settings: Settings,
+}
+
+impl RopyCore {
+    /// Create a core backed by the on-disk repository of the active profile
+    pub fn new(settings: Settings) -> Result<Self, RepositoryError> {
+        let repository = ClipboardRepository::new(&crate::config::profile::active_profile())?;
+        // Nothing to keep responsive here, so the index is rebuilt before the first search
+        repository.reindex_if_stale()?;
+        Ok(Self::with_repository(repository, settings))
+    }
+
+    /// Create a core backed by an existing repository
+    pub fn with_repository(repository: ClipboardRepository, settings: Settings) -> Self {
+        Self {
+            repository,
+            settings,
+        }
+    }
+
+    pub fn repository(&self) -> &ClipboardRepository {
+        &self.repository
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Capture text content as a new record
+    pub fn capture_text(&self, text: String) -> Result<ClipboardRecord, RepositoryError> {
+        let record = self.repository.save_text(text)?;
+        self.enforce_history_limit()?;
+        Ok(record)
+    }
+
+    /// Capture an image, storing it in the images directory as a new record
+    pub fn capture_image(&self, image: DynamicImage) -> Result<ClipboardRecord, RepositoryError> {
+        let path = crate::clipboard::save_image(image)
+            .ok_or_else(|| RepositoryError::Insert("Failed to save image file".to_string()))?;
+        let record = self.repository.save_image_from_path(path)?;
+        if let Err(e) = crate::clipboard::save_thumbnail(Path::new(&record.content)) {
+            log::warn!("Failed to save the thumbnail of {}: {e}", record.content);
+        }
+        self.enforce_history_limit()?;
+        Ok(record)
+    }
+
+    /// Get the most recent records, newest first
+    pub fn recent(&self, limit: usize) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        self.repository.get_recent(limit)
+    }
+
+    /// Search text records by keyword
+    pub fn search(&self, query: &str) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        self.repository.search(query)
+    }
+
+    /// Delete a record by ID
+    pub fn delete(&self, id: u64) -> Result<bool, RepositoryError> {
+        self.repository.delete(id)
+    }
+
+    /// Write all records as a JSON array, newest first
+    pub fn export_json<W: Write>(&self, writer: W) -> Result<(), RepositoryError> {
+        let records = self.repository.get_recent(usize::MAX)?;
+        serde_json::to_writer_pretty(writer, &records)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))
+    }
+
+    /// Stream all records as JSON Lines, newest first, returning how many were written
+    pub fn export_jsonl<W: Write>(&self, writer: W) -> Result<usize, RepositoryError> {
+        self.repository.export_jsonl(writer)
+    }
+
+    fn enforce_history_limit(&self) -> Result<(), RepositoryError> {
+        let storage = &self.settings.storage;
+        let size_budget = storage
+            .max_total_bytes
+            .map(|max| (max, storage.evict_images_first));
+        self.repository
+            .trim_history(storage.max_history_records, size_budget)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn create_test_core(max_history_records: usize) -> RopyCore {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let repository = ClipboardRepository::init(
+            temp_dir.path().join("test.db"),
+            temp_dir.path().join("images"),
+        )
+        .expect("Failed to create test repository");
+        let mut settings = Settings::default();
+        settings.storage.max_history_records = max_history_records;
+        RopyCore::with_repository(repository, settings)
+    }
+
+    #[test]
+    fn test_capture_and_search() {
+        let core = create_test_core(10);
+        core.capture_text("Hello World".to_string())
+            .expect("Failed to capture");
+        core.capture_text("Goodbye".to_string())
+            .expect("Failed to capture");
+
+        let results = core.search("hello").expect("Failed to search");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "Hello World");
+    }
+
+    #[test]
+    fn test_capture_enforces_history_limit() {
+        let core = create_test_core(2);
+        for i in 1..=4 {
+            core.capture_text(format!("Record {i}"))
+                .expect("Failed to capture");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let recent = core.recent(10).expect("Failed to get recent");
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].content, "Record 4");
+    }
+
+    #[test]
+    fn test_export_json() {
+        let core = create_test_core(10);
+        core.capture_text("Exported".to_string())
+            .expect("Failed to capture");
+
+        let mut buffer = Vec::new();
+        core.export_json(&mut buffer).expect("Failed to export");
+        let records: Vec<ClipboardRecord> =
+            serde_json::from_slice(&buffer).expect("Failed to parse export");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].content, "Exported");
+    }
+}
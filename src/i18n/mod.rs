@@ -94,9 +94,9 @@ impl Default for I18n {
         match Self::new(Language::default()) {
             Ok(i18n) => i18n,
             Err(e) => {
-                eprintln!("[ropy] Warning: Failed to load default language translations: {e}");
-                eprintln!(
-                    "[ropy] Falling back to empty translations - all strings will show as '[Missing: key]'"
+                log::warn!("Failed to load default language translations: {e}");
+                log::warn!(
+                    "Falling back to empty translations - all strings will show as '[Missing: key]'"
                 );
                 Self {
                     current_language: Language::default(),
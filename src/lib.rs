@@ -0,0 +1,13 @@
+//! Ropy organizes previous yanks.
+//!
+//! The storage, capture and configuration core is usable without the GUI: build with
+//! `default-features = false` and use [`headless::RopyCore`]. The GPUI application lives
+//! in [`gui`] behind the `gui` feature.
+
+pub mod clipboard;
+pub mod config;
+#[cfg(feature = "gui")]
+pub mod gui;
+pub mod headless;
+pub mod i18n;
+pub mod repository;
@@ -0,0 +1,56 @@
+use env_logger::Env;
+
+/// Environment variable holding the log filter, e.g. `ROPY_LOG=debug`
+const LOG_ENV: &str = "ROPY_LOG";
+/// Level used when neither the flag nor the environment variable is set
+const DEFAULT_LEVEL: &str = "warn";
+
+/// Initialize the global logger.
+///
+/// `--log-level <filter>` takes precedence over `ROPY_LOG`. Both accept any
+/// `env_logger` filter such as `info` or `ropy=debug`.
+pub fn init(args: &[String]) {
+    let mut builder =
+        env_logger::Builder::from_env(Env::default().filter_or(LOG_ENV, DEFAULT_LEVEL));
+    if let Some(level) = log_level_from_args(args) {
+        builder.parse_filters(level);
+    }
+    builder.init();
+}
+
+/// Find the value of `--log-level` in either `--log-level info` or `--log-level=info` form
+fn log_level_from_args(args: &[String]) -> Option<&str> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--log-level" {
+            return iter.next().map(String::as_str);
+        }
+        if let Some(level) = arg.strip_prefix("--log-level=") {
+            return Some(level);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    #[test]
+    fn test_log_level_from_args() {
+        assert_eq!(
+            log_level_from_args(&args(&["ropy", "--log-level", "debug"])),
+            Some("debug")
+        );
+        assert_eq!(
+            log_level_from_args(&args(&["ropy", "--silent", "--log-level=info"])),
+            Some("info")
+        );
+        assert_eq!(log_level_from_args(&args(&["ropy", "--silent"])), None);
+        assert_eq!(log_level_from_args(&args(&["ropy", "--log-level"])), None);
+    }
+}
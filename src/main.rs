@@ -1,11 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod clipboard;
-mod config;
-mod gui;
-mod i18n;
-mod repository;
-
+mod cli;
+mod logging;
 #[cfg(target_os = "windows")]
 mod single_instance;
 
@@ -13,15 +9,25 @@ mod single_instance;
 mod monitor;
 
 fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    logging::init(&args);
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     // Ensure single instance on Windows
     #[cfg(target_os = "windows")]
-    if !single_instance::ensure_single_instance() {
+    let Some(instance) = single_instance::ensure_single_instance() else {
         return;
-    }
+    };
 
     // Monitor RSS in debug mode
     #[cfg(debug_assertions)]
     let _ = monitor::spawn_rss_monitor(std::time::Duration::from_secs(2));
 
-    gui::launch_app();
+    ropy::gui::launch_app(move || {
+        // Let a new instance start as soon as this one has saved its state
+        #[cfg(target_os = "windows")]
+        drop(instance);
+    });
 }
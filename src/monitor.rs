@@ -3,9 +3,9 @@ use std::{thread, time::Duration};
 #[cfg(debug_assertions)]
 use sysinfo::{Pid, ProcessesToUpdate, System};
 
-/// Spawn a background thread that periodically prints the OS RSS for this process
+/// Spawn a background thread that periodically logs the OS RSS for this process
 ///
-/// interval: how frequently to print (Duration)
+/// interval: how frequently to log (Duration)
 #[cfg(debug_assertions)]
 pub fn spawn_rss_monitor(interval: Duration) -> std::thread::JoinHandle<()> {
     let pid = Pid::from(std::process::id() as usize);
@@ -15,10 +15,10 @@ pub fn spawn_rss_monitor(interval: Duration) -> std::thread::JoinHandle<()> {
             // Refresh only the process to reduce overhead
             sys.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
             if let Some(proc) = sys.process(pid) {
-                // Normalize units from sysinfo depending on platform and print MB only
+                // Normalize units from sysinfo depending on platform and log MB only
                 let rss_bytes = proc.memory();
                 let rss_mb = rss_bytes as f64 / 1024.0 / 1024.0;
-                println!("[rss-monitor] memory={rss_mb:.2} MB");
+                log::debug!("[rss-monitor] memory={rss_mb:.2} MB");
             }
             thread::sleep(interval);
         }
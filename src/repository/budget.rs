@@ -0,0 +1,92 @@
+//! Choose which records to evict to keep storage under a size budget.
+
+use std::cmp::Reverse;
+
+/// Bytes a record takes on disk, including its image files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordSize {
+    pub id: u64,
+    pub is_image: bool,
+    pub bytes: u64,
+}
+
+/// IDs of the records to delete so the total size fits in `max_bytes`.
+///
+/// `sizes` must be ordered oldest first. Records are evicted oldest first; with
+/// `images_first`, image records go before all others, largest first. The newest record
+/// is always kept so a capture larger than the whole budget still shows up.
+pub fn records_to_evict(sizes: &[RecordSize], max_bytes: u64, images_first: bool) -> Vec<u64> {
+    let mut total: u64 = sizes.iter().map(|size| size.bytes).sum();
+    if total <= max_bytes {
+        return Vec::new();
+    }
+
+    let Some((_newest, candidates)) = sizes.split_last() else {
+        return Vec::new();
+    };
+    let mut candidates = candidates.to_vec();
+    if images_first {
+        // Stable, so records of the same kind and size stay oldest first
+        candidates
+            .sort_by_key(|size| (!size.is_image, Reverse(size.is_image.then_some(size.bytes))));
+    }
+
+    let mut evicted = Vec::new();
+    for size in candidates {
+        if total <= max_bytes {
+            break;
+        }
+        total -= size.bytes;
+        evicted.push(size.id);
+    }
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn size(id: u64, is_image: bool, bytes: u64) -> RecordSize {
+        RecordSize {
+            id,
+            is_image,
+            bytes,
+        }
+    }
+
+    #[test]
+    fn test_under_budget_keeps_everything() {
+        let sizes = [size(1, false, 10), size(2, true, 20)];
+        assert!(records_to_evict(&sizes, 30, true).is_empty());
+        assert!(records_to_evict(&[], 0, false).is_empty());
+    }
+
+    #[test]
+    fn test_evicts_oldest_first() {
+        let sizes = [
+            size(1, false, 10),
+            size(2, true, 50),
+            size(3, false, 10),
+            size(4, false, 10),
+        ];
+        assert_eq!(records_to_evict(&sizes, 30, false), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_evicts_large_images_first() {
+        let sizes = [
+            size(1, false, 10),
+            size(2, true, 20),
+            size(3, true, 50),
+            size(4, false, 10),
+        ];
+        assert_eq!(records_to_evict(&sizes, 40, true), vec![3]);
+        assert_eq!(records_to_evict(&sizes, 15, true), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_newest_record_is_kept() {
+        let sizes = [size(1, false, 10), size(2, true, 100)];
+        assert_eq!(records_to_evict(&sizes, 50, true), vec![1]);
+    }
+}
@@ -20,6 +20,10 @@ pub enum RepositoryError {
     Delete(String),
     /// Flush error
     Flush(String),
+    /// Archive read or write error
+    Archive(String),
+    /// Writing a record to a file failed
+    Export(String),
 }
 
 impl std::fmt::Display for RepositoryError {
@@ -34,6 +38,8 @@ impl std::fmt::Display for RepositoryError {
             RepositoryError::Query(e) => write!(f, "Query error: {e}"),
             RepositoryError::Delete(e) => write!(f, "Delete error: {e}"),
             RepositoryError::Flush(e) => write!(f, "Flush error: {e}"),
+            RepositoryError::Archive(e) => write!(f, "Archive error: {e}"),
+            RepositoryError::Export(e) => write!(f, "Export error: {e}"),
         }
     }
 }
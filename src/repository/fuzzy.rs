@@ -0,0 +1,75 @@
+//! Fuzzy matching for short names such as tags and snippet names
+
+use super::normalize::normalize_for_search;
+
+/// Score how well `query` fuzzy-matches `candidate`, ignoring case and accents.
+///
+/// Every query character must appear in the candidate in order. Higher scores are
+/// better: consecutive matches and matches at the start are rewarded, gaps are penalized.
+/// Returns `None` when the query is not a subsequence of the candidate.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let query: Vec<char> = normalize_for_search(query).chars().collect();
+    let candidate: Vec<char> = normalize_for_search(candidate).chars().collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0i64;
+    let mut query_index = 0;
+    let mut last_match: Option<usize> = None;
+    for (index, ch) in candidate.iter().enumerate() {
+        if query_index < query.len() && *ch == query[query_index] {
+            score += match last_match {
+                None if index == 0 => 10,
+                Some(last) if last + 1 == index => 8,
+                Some(last) => 2 - (index - last - 1) as i64,
+                None => 2 - index as i64,
+            };
+            last_match = Some(index);
+            query_index += 1;
+        }
+    }
+
+    if query_index < query.len() {
+        return None;
+    }
+    // Prefer candidates that are close in length to the query
+    Some(score - (candidate.len() - query.len()) as i64)
+}
+
+/// Return the candidates matching `query`, best match first.
+///
+/// An exact (case-insensitive) match, if any, is returned alone.
+pub fn best_matches<'a>(query: &str, candidates: &[&'a str]) -> Vec<&'a str> {
+    if let Some(exact) = candidates.iter().find(|c| c.eq_ignore_ascii_case(query)) {
+        return vec![exact];
+    }
+
+    let mut scored: Vec<(i64, &'a str)> = candidates
+        .iter()
+        .filter_map(|c| fuzzy_score(query, c).map(|score| (score, *c)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, c)| c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("prjct", "project").is_some());
+        assert!(fuzzy_score("PRJ", "project").is_some());
+        assert!(fuzzy_score("xyz", "project").is_none());
+        assert!(fuzzy_score("proj", "project") > fuzzy_score("pjt", "project"));
+    }
+
+    #[test]
+    fn test_best_matches() {
+        let tags = ["personal", "project", "projection", "work"];
+        assert_eq!(best_matches("prjct", &tags), vec!["project", "projection"]);
+        assert_eq!(best_matches("Work", &tags), vec!["work"]);
+        assert!(best_matches("zzz", &tags).is_empty());
+    }
+}
@@ -0,0 +1,48 @@
+//! Record IDs: creation timestamps in nanoseconds, unique within the process
+
+use chrono::{DateTime, Local};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Last ID handed out, shared by saved records, in-memory records and image file names
+static LAST_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Reserve `count` consecutive IDs from the timestamp of `now`, returning the first
+///
+/// Bumped past the last ID handed out when the clock hasn't moved on, so captures made
+/// within the same nanosecond never share an ID or a file name and still sort in order.
+pub fn next_id(now: DateTime<Local>, count: u64) -> u64 {
+    let timestamp = now.timestamp_nanos_opt().unwrap_or(0) as u64;
+    let mut id = 0;
+    let _ = LAST_ID.fetch_update(Ordering::AcqRel, Ordering::Acquire, |last| {
+        id = timestamp.max(last + 1);
+        Some(id + count.saturating_sub(1))
+    });
+    id
+}
+
+/// Make sure IDs handed out from now on sort after `id`, e.g. the newest stored record
+pub fn observe(id: u64) {
+    LAST_ID.fetch_max(id, Ordering::AcqRel);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_are_unique_within_one_tick() {
+        let now = Local::now();
+        let first = next_id(now, 3);
+        let second = next_id(now, 1);
+        assert!(second >= first + 3);
+        assert!(next_id(now, 1) > second);
+    }
+
+    #[test]
+    fn test_observed_ids_are_skipped() {
+        let now = Local::now();
+        let stored = now.timestamp_nanos_opt().unwrap() as u64 + 1_000_000;
+        observe(stored);
+        assert!(next_id(now, 1) > stored);
+    }
+}
@@ -0,0 +1,83 @@
+//! Copying every captured image out of the repository, with an index describing them
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Name of the index written next to the exported images
+pub const IMAGE_INDEX_FILE: &str = "index.json";
+
+/// One exported image in `index.json`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ImageIndexEntry {
+    /// File name inside the export directory
+    pub file: String,
+    /// ID of the record the image came from
+    pub id: u64,
+    /// When the image was captured
+    pub created_at: DateTime<Local>,
+    /// Note written on the record, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Result of [`super::ClipboardRepository::export_images`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageExportReport {
+    /// Images copied into the export directory
+    pub exported: usize,
+    /// IDs of image records whose file is gone, which were left out
+    pub missing: Vec<u64>,
+}
+
+/// File name of an exported image, from its capture time
+///
+/// Images captured within the same second get a numeric suffix, so no file is overwritten.
+pub fn image_export_name(
+    created_at: &DateTime<Local>,
+    source: &Path,
+    taken: &mut HashSet<String>,
+) -> String {
+    let stamp = created_at.format("%Y-%m-%d_%H-%M-%S");
+    let extension = source
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("png")
+        .to_ascii_lowercase();
+    let mut name = format!("{stamp}.{extension}");
+    let mut counter = 2;
+    while !taken.insert(name.clone()) {
+        name = format!("{stamp}_{counter}.{extension}");
+        counter += 1;
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_image_export_name() {
+        let time = Local.with_ymd_and_hms(2024, 3, 5, 14, 7, 9).unwrap();
+        let mut taken = HashSet::new();
+        assert_eq!(
+            image_export_name(&time, Path::new("/images/1.png"), &mut taken),
+            "2024-03-05_14-07-09.png"
+        );
+        assert_eq!(
+            image_export_name(&time, Path::new("/images/2.PNG"), &mut taken),
+            "2024-03-05_14-07-09_2.png"
+        );
+        assert_eq!(
+            image_export_name(&time, Path::new("/images/3.gif"), &mut taken),
+            "2024-03-05_14-07-09.gif"
+        );
+        assert_eq!(
+            image_export_name(&time, Path::new("/images/4"), &mut taken),
+            "2024-03-05_14-07-09_3.png"
+        );
+    }
+}
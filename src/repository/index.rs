@@ -0,0 +1,103 @@
+//! Trigram keys of the persisted search index
+//!
+//! Every run of three characters of a record's normalized searchable text and note is stored as
+//! `[len][trigram bytes][record id]`, so all records containing a trigram share a key prefix.
+//! A keyword of at least three characters can only match records holding all its trigrams.
+//!
+//! Only the first [`MAX_INDEXED_CHARS`] characters of each text are indexed. Records with
+//! longer text get an extra entry under the empty token and are always checked directly.
+
+use std::collections::HashSet;
+
+/// Version of the index layout, bumped to force a rebuild of existing indexes
+pub const INDEX_VERSION: u8 = 8;
+/// Characters of each text that are indexed, bounding the entries written per record
+pub const MAX_INDEXED_CHARS: usize = 16 * 1024;
+/// Token of records whose text is longer than what is indexed
+pub const PARTIAL_TOKEN: &str = "";
+/// Characters per index token
+const TRIGRAM_CHARS: usize = 3;
+
+/// Distinct trigrams of already normalized text, see `super::normalize`. Text shorter than a trigram has none.
+pub fn trigrams(text: &str) -> HashSet<String> {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .windows(TRIGRAM_CHARS)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// Trigrams of the indexed start of already normalized text, with [`PARTIAL_TOKEN`] added
+/// when the text goes on past it
+pub fn indexed_trigrams(text: &str) -> HashSet<String> {
+    match text.char_indices().nth(MAX_INDEXED_CHARS) {
+        Some((end, _)) => {
+            let mut grams = trigrams(&text[..end]);
+            grams.insert(PARTIAL_TOKEN.to_string());
+            grams
+        }
+        None => trigrams(text),
+    }
+}
+
+/// Key prefix shared by all index entries of `trigram`
+pub fn index_prefix(trigram: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + trigram.len() + 8);
+    // A trigram is at most 12 bytes, so its length always fits
+    key.push(trigram.len() as u8);
+    key.extend_from_slice(trigram.as_bytes());
+    key
+}
+
+/// Index key linking `trigram` to record `id`
+pub fn index_key(trigram: &str, id: u64) -> Vec<u8> {
+    let mut key = index_prefix(trigram);
+    key.extend_from_slice(&id.to_be_bytes());
+    key
+}
+
+/// Record ID stored at the end of an index key
+pub fn id_from_key(key: &[u8]) -> Option<u64> {
+    let bytes = key.get(key.len().checked_sub(8)?..)?;
+    bytes.try_into().ok().map(u64::from_be_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trigrams() {
+        let grams = trigrams("hello");
+        assert_eq!(grams.len(), 3);
+        assert!(grams.contains("hel") && grams.contains("ell") && grams.contains("llo"));
+        assert_eq!(trigrams("aaaa").len(), 1);
+        assert!(trigrams("ab").is_empty());
+        // Characters, not bytes
+        assert!(trigrams("你好世").contains("你好世"));
+    }
+
+    #[test]
+    fn test_indexed_trigrams() {
+        assert_eq!(indexed_trigrams("hello"), trigrams("hello"));
+        assert!(!indexed_trigrams("hello").contains(PARTIAL_TOKEN));
+
+        let long = format!("{}xyz", "a".repeat(MAX_INDEXED_CHARS));
+        let grams = indexed_trigrams(&long);
+        assert!(grams.contains(PARTIAL_TOKEN));
+        assert!(grams.contains("aaa"));
+        assert!(!grams.contains("xyz"));
+        // Never mistaken for the prefix of a real trigram
+        assert!(!index_key("abc", 1).starts_with(&index_prefix(PARTIAL_TOKEN)));
+    }
+
+    #[test]
+    fn test_index_key_round_trip() {
+        let key = index_key("你好世", 42);
+        assert!(key.starts_with(&index_prefix("你好世")));
+        assert_eq!(id_from_key(&key), Some(42));
+        assert_eq!(id_from_key(&[1, 2]), None);
+        // The length byte keeps a shorter token from prefixing a longer one
+        assert!(!index_key("abc", 1).starts_with(&index_prefix("ab")));
+    }
+}
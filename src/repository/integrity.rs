@@ -0,0 +1,146 @@
+//! Results of checking the history database against the images directory.
+
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::clipboard::thumbnail_path;
+
+/// Image files younger than this are never reported as orphans, since a capture writes its
+/// image before the record pointing at it
+pub const ORPHAN_GRACE: Duration = Duration::from_secs(60);
+
+/// Problems found by [`super::ClipboardRepository::verify`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntegrityReport {
+    /// Image records, in the history or the trash, whose image file is gone
+    pub missing_images: usize,
+    /// Files in the images directory that no record refers to
+    pub orphan_files: usize,
+    /// Stored values that can't be read as records
+    pub bad_records: usize,
+    /// Whether the problems were repaired: records without images and orphan files are
+    /// deleted, unreadable values are moved to a quarantine tree
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    /// Whether nothing was found
+    pub fn is_clean(&self) -> bool {
+        self.missing_images == 0 && self.orphan_files == 0 && self.bad_records == 0
+    }
+}
+
+impl std::fmt::Display for IntegrityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_clean() {
+            return write!(f, "No problems found");
+        }
+        write!(
+            f,
+            "{} record(s) with a missing image, {} orphan image file(s), {} unreadable record(s)",
+            self.missing_images, self.orphan_files, self.bad_records
+        )?;
+        if self.repaired {
+            write!(f, " (repaired)")?;
+        }
+        Ok(())
+    }
+}
+
+/// File names of an image record's files: the image and its thumbnail
+pub fn image_file_names(path: &Path) -> [OsString; 2] {
+    [
+        path.file_name().unwrap_or_default().to_os_string(),
+        thumbnail_path(path)
+            .file_name()
+            .unwrap_or_default()
+            .to_os_string(),
+    ]
+}
+
+/// Whether a file in the images directory belongs to no record
+///
+/// Files are matched by name, so an image referenced through a differently spelled path is
+/// still kept.
+pub fn is_orphan(
+    path: &Path,
+    modified: Option<SystemTime>,
+    referenced: &HashSet<OsString>,
+    now: SystemTime,
+) -> bool {
+    let recent = modified
+        .and_then(|modified| now.duration_since(modified).ok())
+        .is_none_or(|age| age < ORPHAN_GRACE);
+    !recent
+        && path
+            .file_name()
+            .is_some_and(|name| !referenced.contains(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_file_names() {
+        let [image, thumb] = image_file_names(Path::new("/data/images/123.png"));
+        assert_eq!(image, "123.png");
+        assert_eq!(thumb, "123_thumb.png");
+        // Thumbnails are always PNG, and only the extension is replaced
+        let [image, thumb] = image_file_names(Path::new("/data/my.png.files/photo.jpg"));
+        assert_eq!(image, "photo.jpg");
+        assert_eq!(thumb, "photo_thumb.png");
+    }
+
+    #[test]
+    fn test_is_orphan() {
+        let referenced: HashSet<OsString> = image_file_names(Path::new("/images/1.png"))
+            .into_iter()
+            .collect();
+        let now = SystemTime::now();
+        let old = Some(now - ORPHAN_GRACE * 2);
+
+        assert!(!is_orphan(
+            Path::new("/images/1.png"),
+            old,
+            &referenced,
+            now
+        ));
+        assert!(!is_orphan(
+            Path::new("/images/1_thumb.png"),
+            old,
+            &referenced,
+            now
+        ));
+        assert!(is_orphan(Path::new("/images/2.png"), old, &referenced, now));
+        // Possibly still being captured
+        assert!(!is_orphan(
+            Path::new("/images/2.png"),
+            Some(now),
+            &referenced,
+            now
+        ));
+        assert!(!is_orphan(
+            Path::new("/images/2.png"),
+            None,
+            &referenced,
+            now
+        ));
+    }
+
+    #[test]
+    fn test_report_display() {
+        assert_eq!(IntegrityReport::default().to_string(), "No problems found");
+        let report = IntegrityReport {
+            orphan_files: 2,
+            repaired: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            report.to_string(),
+            "0 record(s) with a missing image, 2 orphan image file(s), 0 unreadable record(s) (repaired)"
+        );
+    }
+}
@@ -0,0 +1,544 @@
+//! In-memory clipboard store for tests and previews that must not touch disk.
+
+use chrono::{DateTime, Local};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use super::budget::{RecordSize, records_to_evict};
+use super::errors::RepositoryError;
+use super::ids;
+use super::models::{
+    ClipboardRecord, ContentType, RecordFlag, SearchScope, TrashedRecord, clean_note, content_hash,
+};
+use super::normalize::normalize_for_search;
+use super::stats::{ItemUsage, TOP_ITEMS, UsageStats, day_bucket, week_start};
+use super::store::ClipboardStore;
+
+#[derive(Default)]
+struct MemState {
+    /// Records keyed by ID, so iteration is chronological like the sled tree
+    records: BTreeMap<u64, ClipboardRecord>,
+    trash: BTreeMap<u64, TrashedRecord>,
+    daily_usage: BTreeMap<chrono::NaiveDate, u64>,
+    item_usage: HashMap<u64, ItemUsage>,
+    /// Bumped on every record write, like the sled store's
+    generation: u64,
+}
+
+impl MemState {
+    /// Records for writing, counting the write
+    fn records_mut(&mut self) -> &mut BTreeMap<u64, ClipboardRecord> {
+        self.generation += 1;
+        &mut self.records
+    }
+}
+
+/// Clipboard store kept entirely in memory
+///
+/// Behaves like [`ClipboardRepository`](super::ClipboardRepository) except that
+/// nothing is persisted and image files are never removed.
+#[derive(Default)]
+pub struct MemStore {
+    state: Mutex<MemState>,
+}
+
+impl MemStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemState> {
+        // A panic while holding the lock can't leave the maps half-updated
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl ClipboardStore for MemStore {
+    fn save(
+        &self,
+        content: String,
+        content_type: ContentType,
+    ) -> Result<ClipboardRecord, RepositoryError> {
+        let record = ClipboardRecord::new(ids::next_id(Local::now(), 1), content, content_type);
+        self.state().records_mut().insert(record.id, record.clone());
+        Ok(record)
+    }
+
+    fn save_image_from_path(&self, file_path: String) -> Result<ClipboardRecord, RepositoryError> {
+        self.save(file_path, ContentType::Image)
+    }
+
+    fn save_image_group(
+        &self,
+        file_paths: Vec<String>,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let group_id = ids::next_id(Local::now(), file_paths.len() as u64);
+        let records: Vec<ClipboardRecord> = file_paths
+            .into_iter()
+            .enumerate()
+            .map(|(offset, file_path)| ClipboardRecord {
+                group_id: Some(group_id),
+                ..ClipboardRecord::new(group_id + offset as u64, file_path, ContentType::Image)
+            })
+            .collect();
+        let mut state = self.state();
+        for record in &records {
+            state.records_mut().insert(record.id, record.clone());
+        }
+        Ok(records)
+    }
+
+    fn get_by_id(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        Ok(self.state().records.get(&id).cloned())
+    }
+
+    fn get_recent(&self, limit: usize) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        Ok(self
+            .state()
+            .records
+            .values()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    fn search_with_scope(
+        &self,
+        keyword: &str,
+        scope: SearchScope,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let keyword = normalize_for_search(keyword);
+        Ok(self
+            .state()
+            .records
+            .values()
+            .rev()
+            .filter(|record| record.matches_keyword(&keyword, scope))
+            .cloned()
+            .collect())
+    }
+
+    fn update(&self, record: &ClipboardRecord) -> Result<(), RepositoryError> {
+        self.state().records_mut().insert(record.id, record.clone());
+        Ok(())
+    }
+
+    fn update_content(
+        &self,
+        id: u64,
+        content: String,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.content_hash =
+            (record.content_type == ContentType::Text).then(|| content_hash(content.as_bytes()));
+        record.content = content;
+        Ok(Some(record.clone()))
+    }
+
+    fn set_favorite(
+        &self,
+        id: u64,
+        favorite: bool,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.favorite = favorite;
+        if !favorite {
+            record.favorite_order = None;
+        }
+        Ok(Some(record.clone()))
+    }
+
+    fn set_flag(
+        &self,
+        id: u64,
+        flag: Option<RecordFlag>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.flag = flag;
+        Ok(Some(record.clone()))
+    }
+
+    fn add_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.add_tag(tag);
+        Ok(Some(record.clone()))
+    }
+
+    fn remove_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.remove_tag(tag);
+        Ok(Some(record.clone()))
+    }
+
+    fn all_tags(&self) -> Result<Vec<String>, RepositoryError> {
+        let tags: BTreeSet<String> = self
+            .state()
+            .records
+            .values()
+            .flat_map(|record| record.tags.iter().cloned())
+            .collect();
+        Ok(tags.into_iter().collect())
+    }
+
+    fn set_note(
+        &self,
+        id: u64,
+        note: Option<String>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().get_mut(&id) else {
+            return Ok(None);
+        };
+        record.note = clean_note(note);
+        Ok(Some(record.clone()))
+    }
+
+    fn set_favorite_order(&self, ids: &[u64]) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let mut updated = Vec::with_capacity(ids.len());
+        for (position, id) in ids.iter().enumerate() {
+            if let Some(record) = state.records_mut().get_mut(id) {
+                record.favorite_order = Some(position as u32);
+                updated.push(record.clone());
+            }
+        }
+        Ok(updated)
+    }
+
+    fn touch(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let now = Local::now();
+        let mut state = self.state();
+        let Some(mut record) = state.records_mut().remove(&id) else {
+            return Ok(None);
+        };
+        record.id = ids::next_id(now, 1);
+        record.created_at = now;
+        state.records_mut().insert(record.id, record.clone());
+        Ok(Some(record))
+    }
+
+    fn remove_expired(&self, now: DateTime<Local>) -> Result<Vec<u64>, RepositoryError> {
+        let mut state = self.state();
+        let expired: Vec<u64> = state
+            .records
+            .values()
+            .filter(|record| record.is_expired(now))
+            .map(|record| record.id)
+            .collect();
+        for id in &expired {
+            state.records_mut().remove(id);
+        }
+        Ok(expired)
+    }
+
+    fn delete(&self, id: u64) -> Result<bool, RepositoryError> {
+        Ok(self.state().records_mut().remove(&id).is_some())
+    }
+
+    fn trash(&self, id: u64) -> Result<bool, RepositoryError> {
+        let mut state = self.state();
+        let Some(record) = state.records_mut().remove(&id) else {
+            return Ok(false);
+        };
+        state.trash.insert(
+            id,
+            TrashedRecord {
+                record,
+                deleted_at: Local::now(),
+            },
+        );
+        Ok(true)
+    }
+
+    fn list_trash(&self) -> Result<Vec<TrashedRecord>, RepositoryError> {
+        let mut trashed: Vec<TrashedRecord> = self.state().trash.values().cloned().collect();
+        trashed.sort_by_key(|record| std::cmp::Reverse(record.deleted_at));
+        Ok(trashed)
+    }
+
+    fn restore(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let mut state = self.state();
+        let Some(trashed) = state.trash.remove(&id) else {
+            return Ok(None);
+        };
+        state.records_mut().insert(id, trashed.record.clone());
+        Ok(Some(trashed.record))
+    }
+
+    fn purge_trash(&self, cutoff: DateTime<Local>) -> Result<usize, RepositoryError> {
+        let mut state = self.state();
+        let before = state.trash.len();
+        state.trash.retain(|_, entry| entry.deleted_at >= cutoff);
+        Ok(before - state.trash.len())
+    }
+
+    fn clear(&self) -> Result<(), RepositoryError> {
+        let mut state = self.state();
+        state.records_mut().clear();
+        state.trash.clear();
+        state.daily_usage.clear();
+        state.item_usage.clear();
+        Ok(())
+    }
+
+    fn count(&self) -> usize {
+        self.state().records.len()
+    }
+
+    fn generation(&self) -> u64 {
+        self.state().generation
+    }
+
+    fn cleanup_old_records(&self, keep_count: usize) -> Result<usize, RepositoryError> {
+        let mut state = self.state();
+        let mut removed = 0;
+        while state.records.len() > keep_count {
+            state.records_mut().pop_first();
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    fn enforce_size_budget(
+        &self,
+        max_bytes: u64,
+        images_first: bool,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        let mut state = self.state();
+        // Nothing is on disk, so a record's size is its serialized form
+        let sizes: Vec<RecordSize> = state
+            .records
+            .values()
+            .map(|record| RecordSize {
+                id: record.id,
+                is_image: record.content_type == ContentType::Image,
+                bytes: serde_json::to_vec(record).map_or(0, |value| value.len() as u64),
+            })
+            .collect();
+        let evicted = records_to_evict(&sizes, max_bytes, images_first);
+        for id in &evicted {
+            state.records_mut().remove(id);
+        }
+        Ok(evicted)
+    }
+
+    fn trim_history(
+        &self,
+        max_records: usize,
+        size_budget: Option<(u64, bool)>,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        self.cleanup_old_records(max_records)?;
+        match size_budget {
+            Some((max_bytes, images_first)) => self.enforce_size_budget(max_bytes, images_first),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn export_single(&self, id: u64, dest: &Path) -> Result<bool, RepositoryError> {
+        let Some(record) = self.get_by_id(id)? else {
+            return Ok(false);
+        };
+        record
+            .write_to_file(dest)
+            .map_err(|e| RepositoryError::Export(e.to_string()))?;
+        Ok(true)
+    }
+
+    fn record_use(
+        &self,
+        record: &ClipboardRecord,
+        at: DateTime<Local>,
+    ) -> Result<(), RepositoryError> {
+        let mut state = self.state();
+        *state.daily_usage.entry(day_bucket(at)).or_default() += 1;
+
+        let hash = record
+            .content_hash
+            .unwrap_or_else(|| content_hash(record.content.as_bytes()));
+        let use_count = state
+            .item_usage
+            .get(&hash)
+            .map_or(0, |usage| usage.use_count);
+        state.item_usage.insert(
+            hash,
+            ItemUsage {
+                content: record.content.clone(),
+                content_type: record.content_type.clone(),
+                use_count: use_count + 1,
+            },
+        );
+        Ok(())
+    }
+
+    fn usage_stats(&self, now: DateTime<Local>) -> Result<UsageStats, RepositoryError> {
+        let state = self.state();
+        let today = day_bucket(now);
+        let mut stats = UsageStats::default();
+        for (day, count) in state.daily_usage.range(week_start(today)..=today) {
+            if *day == today {
+                stats.today = *count;
+            }
+            stats.this_week += count;
+        }
+        stats.top = state.item_usage.values().cloned().collect();
+        stats
+            .top
+            .sort_by_key(|item| std::cmp::Reverse(item.use_count));
+        stats.top.truncate(TOP_ITEMS);
+        Ok(stats)
+    }
+
+    fn reset_usage(&self) -> Result<(), RepositoryError> {
+        let mut state = self.state();
+        state.daily_usage.clear();
+        state.item_usage.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_save_and_search() {
+        let store = MemStore::new();
+        let first = store.save_text("Hello World".to_string()).unwrap();
+        let second = store.save_text("Goodbye".to_string()).unwrap();
+        assert!(second.id > first.id);
+        assert_eq!(store.count(), 2);
+
+        let recent = store.get_recent(10).unwrap();
+        assert_eq!(recent[0].id, second.id);
+        assert_eq!(recent[1].id, first.id);
+
+        let results = store.search("hello").unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, first.id);
+    }
+
+    #[test]
+    fn test_image_group_ids_are_unique() {
+        let store = MemStore::new();
+        let group = store
+            .save_image_group(vec!["a.png".to_string(), "b.png".to_string()])
+            .unwrap();
+        let next = store.save_text("after".to_string()).unwrap();
+        assert_eq!(group[1].id, group[0].id + 1);
+        assert!(group.iter().all(|r| r.group_id == Some(group[0].id)));
+        assert!(next.id > group[1].id);
+        assert_eq!(store.count(), 3);
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let store = MemStore::new();
+        let record = store.save_text("keep me".to_string()).unwrap();
+        assert!(store.trash(record.id).unwrap());
+        assert_eq!(store.count(), 0);
+        assert_eq!(store.list_trash().unwrap().len(), 1);
+
+        let restored = store.restore(record.id).unwrap().unwrap();
+        assert_eq!(restored.content, "keep me");
+        assert_eq!(store.count(), 1);
+
+        store.trash(record.id).unwrap();
+        assert_eq!(
+            store
+                .purge_trash(Local::now() - Duration::hours(1))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            store
+                .purge_trash(Local::now() + Duration::hours(1))
+                .unwrap(),
+            1
+        );
+        assert!(store.list_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_cleanup_keeps_newest() {
+        let store = MemStore::new();
+        for i in 0..5 {
+            store.save_text(format!("record {i}")).unwrap();
+        }
+        assert_eq!(store.cleanup_old_records(2).unwrap(), 3);
+        let contents: Vec<String> = store
+            .get_recent(10)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.content)
+            .collect();
+        assert_eq!(contents, ["record 4", "record 3"]);
+    }
+
+    #[test]
+    fn test_touch_moves_to_top() {
+        let store = MemStore::new();
+        let first = store.save_text("first".to_string()).unwrap();
+        store.save_text("second".to_string()).unwrap();
+        store.set_favorite(first.id, true).unwrap();
+
+        let touched = store.touch(first.id).unwrap().unwrap();
+        assert!(touched.favorite);
+        let recent = store.get_recent(10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].id, touched.id);
+        assert_eq!(recent[0].content, "first");
+    }
+
+    #[test]
+    fn test_enforce_size_budget() {
+        let store = MemStore::new();
+        for i in 0..4 {
+            store.save_text(format!("record {i}")).unwrap();
+        }
+        assert!(
+            store
+                .enforce_size_budget(u64::MAX, true)
+                .unwrap()
+                .is_empty()
+        );
+
+        let evicted = store.enforce_size_budget(1, true).unwrap();
+        assert_eq!(evicted.len(), 3);
+        assert_eq!(store.get_recent(10).unwrap()[0].content, "record 3");
+    }
+
+    #[test]
+    fn test_usage_stats() {
+        let store = MemStore::new();
+        let record = store.save_text("often".to_string()).unwrap();
+        let now = Local::now();
+        store.record_use(&record, now).unwrap();
+        store.record_use(&record, now).unwrap();
+
+        let stats = store.usage_stats(now).unwrap();
+        assert_eq!(stats.today, 2);
+        assert_eq!(stats.this_week, 2);
+        assert_eq!(stats.top[0].use_count, 2);
+
+        store.reset_usage().unwrap();
+        assert_eq!(store.usage_stats(now).unwrap(), UsageStats::default());
+    }
+}
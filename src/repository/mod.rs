@@ -1,6 +1,30 @@
+pub mod budget;
 pub mod errors;
+pub mod fuzzy;
+pub mod ids;
+pub mod image_export;
+pub mod index;
+pub mod integrity;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mem;
 pub mod models;
+pub mod normalize;
+pub mod ranking;
 pub mod repo;
+pub mod stats;
+pub mod store;
+pub mod url;
 
-pub use models::ClipboardRecord;
+pub use image_export::ImageExportReport;
+pub use integrity::IntegrityReport;
+#[cfg(any(test, feature = "test-util"))]
+pub use mem::MemStore;
+pub use models::{ClipboardRecord, TrashedRecord};
 pub use repo::ClipboardRepository;
+pub use stats::UsageStats;
+pub use store::ClipboardStore;
+
+use std::sync::{Arc, RwLock};
+
+/// Repository of the active profile, replaced when switching profiles
+pub type ActiveRepository = Arc<RwLock<Option<Arc<ClipboardRepository>>>>;
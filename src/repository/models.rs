@@ -2,6 +2,11 @@
 
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use super::normalize::normalize_for_search;
 
 /// Data model for clipboard records
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -14,6 +19,293 @@ pub struct ClipboardRecord {
     pub created_at: DateTime<Local>,
     /// Content type
     pub content_type: ContentType,
+    /// Application the content was copied from, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_app: Option<String>,
+    /// Shared identifier for records captured together from a single clipboard change
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<u64>,
+    /// Hash of the content used for deduplication
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<u64>,
+    /// Time after which the record is deleted automatically
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Local>>,
+    /// Delete the record right after it is pasted once
+    #[serde(default)]
+    pub delete_after_paste: bool,
+    /// Some bytes could not be decoded when the text was captured
+    #[serde(default)]
+    pub lossy_encoding: bool,
+    /// Marked as a favorite by the user
+    #[serde(default)]
+    pub favorite: bool,
+    /// Position among favorites chosen by the user, lowest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorite_order: Option<u32>,
+    /// Short annotation written by the user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Color the user flagged the record with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<RecordFlag>,
+    /// Tags assigned by the user, normalized by [`normalize_tag`] and sorted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ClipboardRecord {
+    /// Record captured now with only its content set, hashed when the content is textual
+    pub fn new(id: u64, content: String, content_type: ContentType) -> Self {
+        Self {
+            id,
+            content_hash: content_type
+                .is_textual()
+                .then(|| content_hash(content.as_bytes())),
+            content,
+            created_at: Local::now(),
+            content_type,
+            source_app: None,
+            group_id: None,
+            expires_at: None,
+            delete_after_paste: false,
+            lossy_encoding: false,
+            favorite: false,
+            favorite_order: None,
+            note: None,
+            flag: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Short hex form of the content hash, for display
+    pub fn short_hash(&self) -> Option<String> {
+        self.content_hash
+            .map(|hash| format!("{hash:016x}")[..8].to_string())
+    }
+
+    /// Whether the record has expired at the given time
+    pub fn is_expired(&self, now: DateTime<Local>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Text matched against search keywords within the given scope
+    pub fn search_text(&self, scope: SearchScope) -> Option<&str> {
+        match (&self.content_type, scope) {
+            (ContentType::Text, _) => Some(&self.content),
+            (_, SearchScope::TextOnly) => None,
+            (ContentType::Svg, SearchScope::All) => Some(&self.content),
+            // Match image and file records by name rather than their full path
+            (ContentType::Image | ContentType::FilePath, SearchScope::All) => {
+                Path::new(&self.content).file_name()?.to_str()
+            }
+        }
+    }
+
+    /// Save the record as a standalone file: the text itself, or a copy of the image file
+    ///
+    /// File records are written as their path. Fails when `dest` is a directory.
+    pub fn write_to_file(&self, dest: &Path) -> std::io::Result<()> {
+        if dest.is_dir() {
+            return Err(std::io::Error::other(format!(
+                "{} is a directory",
+                dest.display()
+            )));
+        }
+        match self.content_type {
+            ContentType::Image => std::fs::copy(&self.content, dest).map(|_| ()),
+            ContentType::Text | ContentType::FilePath | ContentType::Svg => {
+                std::fs::write(dest, &self.content)
+            }
+        }
+    }
+
+    /// Add a tag, returning whether the record didn't have it yet
+    pub fn add_tag(&mut self, name: &str) -> bool {
+        let Some(tag) = normalize_tag(name) else {
+            return false;
+        };
+        match self.tags.binary_search(&tag) {
+            Ok(_) => false,
+            Err(index) => {
+                self.tags.insert(index, tag);
+                true
+            }
+        }
+    }
+
+    /// Remove a tag, returning whether the record had it
+    pub fn remove_tag(&mut self, name: &str) -> bool {
+        let Some(tag) = normalize_tag(name) else {
+            return false;
+        };
+        let before = self.tags.len();
+        self.tags.retain(|t| *t != tag);
+        self.tags.len() != before
+    }
+
+    /// Whether the searchable text or the note contains an already normalized keyword
+    pub fn matches_keyword(&self, keyword: &str, scope: SearchScope) -> bool {
+        self.search_text(scope)
+            .into_iter()
+            .chain(self.note.as_deref())
+            .any(|text| normalize_for_search(text).contains(keyword))
+    }
+}
+
+/// Color a record can be flagged with, for grouping records at a glance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordFlag {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl RecordFlag {
+    /// Flags in menu order
+    pub const ALL: [RecordFlag; 6] = [
+        RecordFlag::Red,
+        RecordFlag::Orange,
+        RecordFlag::Yellow,
+        RecordFlag::Green,
+        RecordFlag::Blue,
+        RecordFlag::Purple,
+    ];
+}
+
+/// The flag filter after `current`: no filter, then each flag in menu order, wrapping around
+pub fn next_flag_filter(current: Option<RecordFlag>) -> Option<RecordFlag> {
+    match current {
+        None => RecordFlag::ALL.first().copied(),
+        Some(flag) => {
+            let index = RecordFlag::ALL.iter().position(|f| *f == flag).unwrap_or(0);
+            RecordFlag::ALL.get(index + 1).copied()
+        }
+    }
+}
+
+/// Record types searched by a keyword search
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum SearchScope {
+    /// Only text records, the fastest option
+    TextOnly,
+    /// Text records plus image and file names, and SVG markup
+    #[default]
+    All,
+}
+
+/// Content type shown by the list's filter tabs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentFilter {
+    #[default]
+    All,
+    Text,
+    Image,
+    File,
+}
+
+impl ContentFilter {
+    /// Filters in tab order
+    pub const ALL: [ContentFilter; 4] = [
+        ContentFilter::All,
+        ContentFilter::Text,
+        ContentFilter::Image,
+        ContentFilter::File,
+    ];
+
+    /// Whether a record of this content type passes the filter
+    pub fn matches(self, content_type: &ContentType) -> bool {
+        match self {
+            ContentFilter::All => true,
+            ContentFilter::Text => *content_type == ContentType::Text,
+            ContentFilter::Image => {
+                matches!(content_type, ContentType::Image | ContentType::Svg)
+            }
+            ContentFilter::File => *content_type == ContentType::FilePath,
+        }
+    }
+}
+
+/// The filter after `current` in tab order, or before it when `forward` is false, wrapping around
+pub fn next_content_filter(current: ContentFilter, forward: bool) -> ContentFilter {
+    let filters = ContentFilter::ALL;
+    let index = filters.iter().position(|f| *f == current).unwrap_or(0);
+    let next = if forward {
+        (index + 1) % filters.len()
+    } else {
+        (index + filters.len() - 1) % filters.len()
+    };
+    filters[next]
+}
+
+/// Sort favorites by their manual order. Unordered favorites follow in their existing order.
+pub fn sort_favorites(records: &mut [ClipboardRecord]) {
+    records.sort_by_key(|record| record.favorite_order.unwrap_or(u32::MAX));
+}
+
+/// Move `id` to the position `target` holds in `ids`, returning the new order
+///
+/// Moving onto an earlier item places `id` before it, onto a later item after it.
+/// The order is unchanged when either ID is missing.
+pub fn move_favorite(ids: &[u64], id: u64, target: u64) -> Vec<u64> {
+    let mut order = ids.to_vec();
+    let (Some(from), Some(to)) = (
+        ids.iter().position(|i| *i == id),
+        ids.iter().position(|i| *i == target),
+    ) else {
+        return order;
+    };
+    let moved = order.remove(from);
+    order.insert(to, moved);
+    order
+}
+
+/// A deleted record kept in the trash until it is restored or purged
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TrashedRecord {
+    pub record: ClipboardRecord,
+    /// Time the record was moved to the trash
+    pub deleted_at: DateTime<Local>,
+}
+
+/// Trim a note, treating a blank one as no note
+pub fn clean_note(note: Option<String>) -> Option<String> {
+    note.map(|note| note.trim().to_string())
+        .filter(|note| !note.is_empty())
+}
+
+/// Tag as stored: lowercase, without a leading `#` and with inner whitespace turned into `-`
+///
+/// Returns `None` for names left empty.
+pub fn normalize_tag(name: &str) -> Option<String> {
+    let tag = name
+        .trim()
+        .trim_start_matches('#')
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Text with surrounding whitespace trimmed and inner runs of whitespace collapsed to one space
+pub fn whitespace_normalized(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Whether two texts only differ in surrounding whitespace or the width of inner whitespace
+pub fn texts_equivalent(a: &str, b: &str) -> bool {
+    a.split_whitespace().eq(b.split_whitespace())
+}
+
+/// Hash clipboard content the same way for text and image data
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
 }
 
 /// Content type enumeration
@@ -25,4 +317,234 @@ pub enum ContentType {
     Image,
     /// File path
     FilePath,
+    /// SVG image, stored as its markup
+    Svg,
+}
+
+#[cfg(test)]
+impl ClipboardRecord {
+    /// Text record captured now with nothing else set, for tests to adjust with struct update
+    pub fn test_record(id: u64, content: &str) -> Self {
+        Self {
+            id,
+            content: content.to_string(),
+            created_at: Local::now(),
+            content_type: ContentType::Text,
+            source_app: None,
+            group_id: None,
+            content_hash: None,
+            expires_at: None,
+            delete_after_paste: false,
+            lossy_encoding: false,
+            favorite: false,
+            favorite_order: None,
+            note: None,
+            flag: None,
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl ContentType {
+    /// Whether the content is the copied text itself, rather than a path
+    pub fn is_textual(&self) -> bool {
+        matches!(self, ContentType::Text | ContentType::Svg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_new_record() {
+        let text = ClipboardRecord::new(1, "hello".to_string(), ContentType::Text);
+        assert_eq!(text.content_hash, Some(content_hash(b"hello")));
+        assert!(!text.favorite && text.tags.is_empty());
+
+        let image = ClipboardRecord::new(2, "a.png".to_string(), ContentType::Image);
+        assert_eq!(image.content_hash, None);
+        assert_eq!(image.group_id, None);
+    }
+
+    #[test]
+    fn test_search_text() {
+        let mut record = ClipboardRecord {
+            content_type: ContentType::FilePath,
+            ..ClipboardRecord::test_record(1, "/home/user/report.pdf")
+        };
+        assert_eq!(record.search_text(SearchScope::All), Some("report.pdf"));
+        assert_eq!(record.search_text(SearchScope::TextOnly), None);
+
+        record.content_type = ContentType::Text;
+        assert_eq!(
+            record.search_text(SearchScope::TextOnly),
+            Some("/home/user/report.pdf")
+        );
+    }
+
+    #[test]
+    fn test_matches_keyword_in_note() {
+        let mut record = ClipboardRecord {
+            content_type: ContentType::Image,
+            ..ClipboardRecord::test_record(1, "/home/user/logo.png")
+        };
+        assert!(!record.matches_keyword("brand", SearchScope::TextOnly));
+
+        record.note = Some("Brand kit, Café".to_string());
+        assert!(record.matches_keyword("brand", SearchScope::TextOnly));
+        assert!(record.matches_keyword("cafe", SearchScope::All));
+        assert!(record.matches_keyword("logo", SearchScope::All));
+        assert!(!record.matches_keyword("logo", SearchScope::TextOnly));
+    }
+
+    #[test]
+    fn test_normalize_tag() {
+        assert_eq!(normalize_tag(" #Work ").as_deref(), Some("work"));
+        assert_eq!(normalize_tag("to  read").as_deref(), Some("to-read"));
+        assert_eq!(normalize_tag("##"), None);
+        assert_eq!(normalize_tag("  "), None);
+    }
+
+    #[test]
+    fn test_add_and_remove_tag() {
+        let mut record: ClipboardRecord = serde_json::from_str(
+            r#"{"id":1,"content":"a","created_at":"2024-01-01T00:00:00+00:00","content_type":"Text"}"#,
+        )
+        .unwrap();
+        assert!(record.tags.is_empty());
+        assert!(record.add_tag("work"));
+        assert!(record.add_tag("#Api"));
+        assert!(!record.add_tag("WORK"));
+        assert!(!record.add_tag(" "));
+        assert_eq!(record.tags, vec!["api", "work"]);
+
+        assert!(record.remove_tag("#work"));
+        assert!(!record.remove_tag("work"));
+        assert_eq!(record.tags, vec!["api"]);
+    }
+
+    #[test]
+    fn test_flag_serde() {
+        let mut record: ClipboardRecord = serde_json::from_str(
+            r#"{"id":1,"content":"a","created_at":"2024-01-01T00:00:00+00:00","content_type":"Text"}"#,
+        )
+        .unwrap();
+        assert_eq!(record.flag, None);
+        assert!(!serde_json::to_string(&record).unwrap().contains("flag"));
+
+        record.flag = Some(RecordFlag::Green);
+        let json = serde_json::to_string(&record).unwrap();
+        let parsed: ClipboardRecord = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.flag, Some(RecordFlag::Green));
+    }
+
+    #[test]
+    fn test_next_flag_filter() {
+        assert_eq!(next_flag_filter(None), Some(RecordFlag::Red));
+        assert_eq!(
+            next_flag_filter(Some(RecordFlag::Red)),
+            Some(RecordFlag::Orange)
+        );
+        assert_eq!(next_flag_filter(Some(RecordFlag::Purple)), None);
+    }
+
+    #[test]
+    fn test_clean_note() {
+        assert_eq!(clean_note(None), None);
+        assert_eq!(clean_note(Some("  \n ".to_string())), None);
+        assert_eq!(
+            clean_note(Some(" API key for staging ".to_string())),
+            Some("API key for staging".to_string())
+        );
+    }
+
+    #[test]
+    fn test_texts_equivalent() {
+        assert!(texts_equivalent("foo", "foo\n"));
+        assert!(texts_equivalent("  foo bar\r\n", "foo  bar"));
+        assert!(texts_equivalent("a\n\tb", "a b"));
+        assert!(!texts_equivalent("foo", "Foo"));
+        assert!(!texts_equivalent("foobar", "foo bar"));
+        assert_eq!(whitespace_normalized("  foo \n\t bar\n"), "foo bar");
+        assert_eq!(whitespace_normalized(" \n"), "");
+    }
+
+    #[test]
+    fn test_record_without_note_deserializes() {
+        let json = r#"{"id":1,"content":"hello","created_at":"2024-01-01T00:00:00+00:00","content_type":"Text"}"#;
+        let record: ClipboardRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.note, None);
+        assert!(!serde_json::to_string(&record).unwrap().contains("note"));
+    }
+
+    #[test]
+    fn test_next_content_filter() {
+        assert_eq!(
+            next_content_filter(ContentFilter::All, true),
+            ContentFilter::Text
+        );
+        assert_eq!(
+            next_content_filter(ContentFilter::File, true),
+            ContentFilter::All
+        );
+        assert_eq!(
+            next_content_filter(ContentFilter::All, false),
+            ContentFilter::File
+        );
+        assert_eq!(
+            next_content_filter(ContentFilter::Image, false),
+            ContentFilter::Text
+        );
+        assert!(ContentFilter::File.matches(&ContentType::FilePath));
+        assert!(ContentFilter::Image.matches(&ContentType::Svg));
+        assert!(!ContentFilter::Text.matches(&ContentType::Svg));
+        assert!(!ContentFilter::Text.matches(&ContentType::Image));
+        assert!(ContentFilter::All.matches(&ContentType::Image));
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let now = Local::now();
+        let mut record = ClipboardRecord {
+            created_at: now,
+            ..ClipboardRecord::test_record(1, "secret")
+        };
+        assert!(!record.is_expired(now));
+
+        record.expires_at = Some(now + Duration::minutes(5));
+        assert!(!record.is_expired(now));
+        assert!(record.is_expired(now + Duration::minutes(5)));
+        assert!(record.is_expired(now + Duration::minutes(6)));
+    }
+
+    #[test]
+    fn test_move_favorite() {
+        let ids = [1, 2, 3, 4];
+        assert_eq!(move_favorite(&ids, 3, 2), vec![1, 3, 2, 4]);
+        assert_eq!(move_favorite(&ids, 1, 2), vec![2, 1, 3, 4]);
+        assert_eq!(move_favorite(&ids, 1, 4), vec![2, 3, 4, 1]);
+        assert_eq!(move_favorite(&ids, 4, 1), vec![4, 1, 2, 3]);
+        assert_eq!(move_favorite(&ids, 2, 2), vec![1, 2, 3, 4]);
+        assert_eq!(move_favorite(&ids, 9, 1), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_sort_favorites() {
+        let record = |id, favorite_order| ClipboardRecord {
+            favorite: true,
+            favorite_order,
+            ..ClipboardRecord::test_record(id, "")
+        };
+        let mut records = vec![
+            record(5, None),
+            record(4, Some(1)),
+            record(3, None),
+            record(2, Some(0)),
+        ];
+        sort_favorites(&mut records);
+        let ids: Vec<u64> = records.iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![2, 4, 5, 3]);
+    }
 }
@@ -0,0 +1,59 @@
+//! Normalize text so searches ignore case, accents and compatibility variants
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+/// Fold `text` for case- and accent-insensitive matching.
+///
+/// Both record text and queries go through this before they are compared, so "CAFÉ" matches
+/// "café", "Straße" matches "strasse" and Turkish "İ"/"ı" match "i". Compatibility forms such
+/// as ligatures, fullwidth letters and no-break spaces become their plain equivalents.
+///
+/// Text is decomposed (NFKD), stripped of combining marks and lowercased, then composed again
+/// so scripts like Hangul keep one character per syllable.
+pub fn normalize_for_search(text: &str) -> String {
+    let mut folded = String::with_capacity(text.len());
+    for ch in text.nfkd().filter(|ch| !is_combining_mark(*ch)) {
+        for lower in ch.to_lowercase() {
+            match lower {
+                'ß' => folded.push_str("ss"),
+                // Turkish dotless i; the dotted `İ` loses its dot as a combining mark
+                'ı' => folded.push('i'),
+                'ς' => folded.push('σ'),
+                _ => folded.push(lower),
+            }
+        }
+    }
+    folded.nfc().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diacritics_and_case() {
+        assert_eq!(normalize_for_search("Café"), "cafe");
+        assert_eq!(normalize_for_search("CAFÉ"), normalize_for_search("cafe"));
+        assert_eq!(normalize_for_search("Crème Brûlée"), "creme brulee");
+        assert_eq!(normalize_for_search("Łódź"), "łodz");
+        // Already decomposed input: e followed by a combining acute accent
+        assert_eq!(normalize_for_search("cafe\u{301}"), "cafe");
+        assert_eq!(normalize_for_search("Όλα"), "ολα");
+    }
+
+    #[test]
+    fn test_special_cases() {
+        assert_eq!(normalize_for_search("Straße"), "strasse");
+        assert_eq!(normalize_for_search("STRASSE"), "strasse");
+        assert_eq!(normalize_for_search("İstanbul"), "istanbul");
+        assert_eq!(normalize_for_search("ılık"), "ilik");
+        assert_eq!(normalize_for_search("ΟΔΟΣ"), normalize_for_search("οδος"));
+        assert_eq!(normalize_for_search("ﬁle"), "file");
+        assert_eq!(normalize_for_search("ＲＯＰＹ！"), "ropy!");
+        assert_eq!(normalize_for_search("a\u{00A0}b"), "a b");
+        assert_eq!(normalize_for_search("日本語"), "日本語");
+        assert_eq!(normalize_for_search("한국어"), "한국어");
+        assert_eq!(normalize_for_search("Ⅻ ²"), "xii 2");
+    }
+}
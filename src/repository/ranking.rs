@@ -0,0 +1,152 @@
+//! Ordering of search results by match quality, favorites and recency
+
+use chrono::{DateTime, Local};
+
+use super::fuzzy::fuzzy_score;
+use super::models::{ClipboardRecord, SearchScope};
+use super::normalize::normalize_for_search;
+
+/// Score added to favorited records
+pub const FAVORITE_BOOST: i64 = 50;
+/// Score added when the whole searchable text equals the query
+pub const EXACT_MATCH_BOOST: i64 = 40;
+/// Score added when the searchable text starts with the query
+pub const PREFIX_MATCH_BOOST: i64 = 20;
+/// Score added when the query starts at a word boundary
+pub const WORD_MATCH_BOOST: i64 = 10;
+/// Highest score of a fuzzy match, kept below a word boundary match
+pub const FUZZY_MATCH_MAX: i64 = 5;
+/// Recency score of a record created just now
+pub const RECENCY_MAX: i64 = 30;
+/// Age at which a record loses one point of recency score
+pub const RECENCY_STEP_HOURS: i64 = 24;
+
+/// Score how well `query` matches `text`, ignoring case and accents
+///
+/// Only the best kind of match counts. Texts holding the query's characters only in
+/// order, or inside a word, get a fuzzy score. Texts that don't match at all score 0.
+pub fn match_quality(text: &str, query: &str) -> i64 {
+    let text = normalize_for_search(text.trim());
+    let query = normalize_for_search(query.trim());
+    if query.is_empty() {
+        return 0;
+    }
+    if text == query {
+        EXACT_MATCH_BOOST
+    } else if text.starts_with(&query) {
+        PREFIX_MATCH_BOOST
+    } else if text.match_indices(&query).any(|(index, _)| {
+        text[..index]
+            .chars()
+            .next_back()
+            .is_some_and(|c| !c.is_alphanumeric())
+    }) {
+        WORD_MATCH_BOOST
+    } else {
+        fuzzy_score(&query, &text).map_or(0, |score| score.clamp(1, FUZZY_MATCH_MAX))
+    }
+}
+
+/// Score of a record's age, from `RECENCY_MAX` when new down to 0
+pub fn recency_score(created_at: DateTime<Local>, now: DateTime<Local>) -> i64 {
+    let age_hours = (now - created_at).num_hours().max(0);
+    (RECENCY_MAX - age_hours / RECENCY_STEP_HOURS).max(0)
+}
+
+/// Combined ranking score of a search result, higher is better
+pub fn rank_score(
+    record: &ClipboardRecord,
+    query: &str,
+    scope: SearchScope,
+    now: DateTime<Local>,
+) -> i64 {
+    let quality = record
+        .search_text(scope)
+        .into_iter()
+        .chain(record.note.as_deref())
+        .map(|text| match_quality(text, query))
+        .max()
+        .unwrap_or(0);
+    let favorite = if record.favorite { FAVORITE_BOOST } else { 0 };
+    quality + favorite + recency_score(record.created_at, now)
+}
+
+/// Sort search results best first. Equal scores keep their chronological order.
+pub fn rank_records(
+    records: &mut [ClipboardRecord],
+    query: &str,
+    scope: SearchScope,
+    now: DateTime<Local>,
+) {
+    records.sort_by_cached_key(|record| std::cmp::Reverse(rank_score(record, query, scope, now)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn record(
+        id: u64,
+        content: &str,
+        created_at: DateTime<Local>,
+        favorite: bool,
+    ) -> ClipboardRecord {
+        ClipboardRecord {
+            created_at,
+            favorite,
+            ..ClipboardRecord::test_record(id, content)
+        }
+    }
+
+    #[test]
+    fn test_match_quality() {
+        assert_eq!(match_quality("Hello", "hello"), EXACT_MATCH_BOOST);
+        assert_eq!(match_quality("hello world", "hel"), PREFIX_MATCH_BOOST);
+        assert_eq!(match_quality("say hello", "hello"), WORD_MATCH_BOOST);
+        assert_eq!(match_quality("othello", "hello"), FUZZY_MATCH_MAX);
+        assert_eq!(match_quality("a long line holding its", "alh"), 1);
+        assert_eq!(match_quality("hello", "world"), 0);
+        assert_eq!(match_quality("othello hello", "hello"), WORD_MATCH_BOOST);
+        assert_eq!(match_quality("anything", ""), 0);
+    }
+
+    #[test]
+    fn test_recency_score() {
+        let now = Local::now();
+        assert_eq!(recency_score(now, now), RECENCY_MAX);
+        assert_eq!(recency_score(now - Duration::hours(23), now), RECENCY_MAX);
+        assert_eq!(recency_score(now - Duration::days(1), now), RECENCY_MAX - 1);
+        assert_eq!(recency_score(now - Duration::days(365), now), 0);
+        // Clock skew must not push the score above the maximum
+        assert_eq!(recency_score(now + Duration::days(1), now), RECENCY_MAX);
+    }
+
+    #[test]
+    fn test_rank_records() {
+        let now = Local::now();
+        let mut records = vec![
+            record(4, "a note about rust", now, false),
+            record(3, "rust", now - Duration::days(3), false),
+            record(2, "trusty", now - Duration::days(10), true),
+            record(1, "rusty nail", now - Duration::days(10), false),
+        ];
+        rank_records(&mut records, "rust", SearchScope::TextOnly, now);
+        let ids: Vec<u64> = records.iter().map(|r| r.id).collect();
+        // Scores: favorite 50 + fuzzy 5 + 20, exact 40 + 27, word 10 + 30, prefix 20 + 20
+        // (tie keeps order)
+        assert_eq!(ids, vec![2, 3, 4, 1]);
+    }
+
+    #[test]
+    fn test_rank_records_keeps_chronological_ties() {
+        let now = Local::now();
+        let mut records = vec![
+            record(2, "foo bar", now, false),
+            record(1, "foo baz", now, false),
+        ];
+        rank_records(&mut records, "foo", SearchScope::TextOnly, now);
+        assert_eq!(records[0].id, 2);
+        assert_eq!(records[1].id, 1);
+    }
+}
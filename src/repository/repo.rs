@@ -1,29 +1,84 @@
 //! Clipboard repository for storing and retrieving clipboard records.
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 use sled::{Db, Tree};
-use std::fs;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
 
+use crate::clipboard::{save_thumbnail, thumbnail_path};
+use crate::config::profile::{data_root, profile_dir};
+
+use super::budget::{RecordSize, records_to_evict};
 use super::errors::RepositoryError;
-use super::models::{ClipboardRecord, ContentType};
+use super::ids;
+use super::image_export::{
+    IMAGE_INDEX_FILE, ImageExportReport, ImageIndexEntry, image_export_name,
+};
+use super::index::{
+    INDEX_VERSION, PARTIAL_TOKEN, id_from_key, index_key, index_prefix, indexed_trigrams, trigrams,
+};
+use super::integrity::{IntegrityReport, image_file_names, is_orphan};
+use super::models::{
+    ClipboardRecord, ContentType, RecordFlag, SearchScope, TrashedRecord, clean_note, content_hash,
+    texts_equivalent, whitespace_normalized,
+};
+use super::normalize::normalize_for_search;
+use super::stats::{ItemUsage, TOP_ITEMS, UsageStats, day_bucket, day_key, week_start};
+use super::url::{canonicalize_url, is_url};
+
+/// Name of the record list inside an exported archive
+const ARCHIVE_RECORDS: &str = "records.json";
+/// Directory holding image files inside an exported archive
+const ARCHIVE_IMAGES_DIR: &str = "images";
+/// Key of the index layout version in the index metadata tree
+const INDEX_VERSION_KEY: &str = "version";
+/// Prefix of the index metadata keys marking a record write whose index update is unfinished
+const INDEX_PENDING_PREFIX: &[u8] = b"pending:";
 
 pub struct ClipboardRepository {
     db: Db,
     records_tree: Tree,
+    trash_tree: Tree,
+    /// Copies per day, keyed by ISO date
+    daily_usage_tree: Tree,
+    /// Copies per content, keyed by content hash
+    item_usage_tree: Tree,
+    /// Trigram search index, see `super::index`
+    index_tree: Tree,
+    /// Version of the search index layout, and records whose index update is unfinished
+    index_meta_tree: Tree,
+    /// Latest text record ID, keyed by the hash of its whitespace-normalized content
+    dedup_tree: Tree,
+    /// Unreadable values moved aside by `verify`, keyed by source tree name and record key
+    quarantine_tree: Tree,
     images_dir: PathBuf,
+    /// Number of records, kept up to date so trimming doesn't scan the tree on every save
+    record_count: AtomicUsize,
+    /// Bumped on every record write, so views can tell when the history changed
+    generation: AtomicU64,
+    /// Records per tag, counted on the first `all_tags` call and kept up to date after
+    tag_counts: Mutex<Option<BTreeMap<String, usize>>>,
+    /// Whether the search index covers every record; searches scan until it does
+    index_current: AtomicBool,
 }
 
 impl ClipboardRepository {
-    /// Create a new repository instance
-    pub fn new() -> Result<Self, RepositoryError> {
-        // The database file is stored in the user data directory at `ropy/clipboard.db`
-        let db_path = Self::get_db_path()?;
-        let images_dir = dirs::data_local_dir()
-            .ok_or(RepositoryError::DataDirNotFound)?
-            .join("ropy")
-            .join("images");
-        Self::init(db_path, images_dir)
+    /// Create the repository of a profile
+    ///
+    /// The default profile's database is stored in the user data directory at
+    /// `ropy/clipboard.db`, other profiles use `ropy/profiles/<name>/clipboard.db`.
+    pub fn new(profile: &str) -> Result<Self, RepositoryError> {
+        let data_dir = profile_dir(
+            &data_root().ok_or(RepositoryError::DataDirNotFound)?,
+            profile,
+        );
+        Self::init(data_dir.join("clipboard.db"), data_dir.join("images"))
     }
 
     /// Initialize repository with specific paths
@@ -32,21 +87,76 @@ impl ClipboardRepository {
         let records_tree = db
             .open_tree("clipboard_records")
             .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let trash_tree = db
+            .open_tree("deleted_records")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let daily_usage_tree = db
+            .open_tree("daily_usage")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let item_usage_tree = db
+            .open_tree("item_usage")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let index_tree = db
+            .open_tree("search_index")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let index_meta_tree = db
+            .open_tree("search_index_meta")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let dedup_tree = db
+            .open_tree("text_dedup")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
+        let quarantine_tree = db
+            .open_tree("quarantined_records")
+            .map_err(|e| RepositoryError::TreeOpen(e.to_string()))?;
 
-        Ok(Self {
+        let repo = Self {
             db,
             records_tree,
+            trash_tree,
+            daily_usage_tree,
+            item_usage_tree,
+            index_tree,
+            index_meta_tree,
+            dedup_tree,
+            quarantine_tree,
             images_dir,
-        })
+            record_count: AtomicUsize::new(0),
+            generation: AtomicU64::new(0),
+            tag_counts: Mutex::new(None),
+            index_current: AtomicBool::new(false),
+        };
+        // The only full count, everything after goes through `put_record` and `take_record`
+        repo.record_count
+            .store(repo.records_tree.len(), Ordering::Release);
+        ids::observe(repo.newest_id()?);
+        // Databases from older versions have no index yet, and a write interrupted between
+        // the record and its index entries leaves a pending marker behind
+        let version = repo
+            .index_meta_tree
+            .get(INDEX_VERSION_KEY)
+            .map_err(|e| RepositoryError::Query(e.to_string()))?;
+        let interrupted = repo
+            .index_meta_tree
+            .scan_prefix(INDEX_PENDING_PREFIX)
+            .next()
+            .is_some();
+        if version.as_deref() == Some(&[INDEX_VERSION][..]) && !interrupted {
+            repo.index_current.store(true, Ordering::Release);
+        } else if repo.records_tree.is_empty() {
+            // Nothing to read, so there is no reason to leave it for later
+            repo.reindex()?;
+        }
+        Ok(repo)
     }
 
-    /// Get the data directory path for storing the database file
-    fn get_db_path() -> Result<PathBuf, RepositoryError> {
-        let data_dir = dirs::data_local_dir()
-            .ok_or(RepositoryError::DataDirNotFound)?
-            .join("ropy")
-            .join("clipboard.db");
-        Ok(data_dir)
+    /// ID of the newest record, or 0 when there is none
+    fn newest_id(&self) -> Result<u64, RepositoryError> {
+        Ok(self
+            .records_tree
+            .last()
+            .map_err(|e| RepositoryError::Query(e.to_string()))?
+            .and_then(|(key, _)| key.as_ref().try_into().ok().map(u64::from_be_bytes))
+            .unwrap_or(0))
     }
 
     /// Save a clipboard record
@@ -57,23 +167,17 @@ impl ClipboardRepository {
         content: String,
         content_type: ContentType,
     ) -> Result<ClipboardRecord, RepositoryError> {
-        let now = Local::now();
-        let id = now.timestamp_nanos_opt().unwrap_or(0) as u64;
-
-        let record = ClipboardRecord {
-            id,
-            content,
-            created_at: now,
-            content_type,
-        };
+        let id = ids::next_id(Local::now(), 1);
+        let record = ClipboardRecord::new(id, content, content_type);
 
         let key = id.to_be_bytes();
         let value = serde_json::to_vec(&record)
             .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
 
-        self.records_tree
-            .insert(key, value)
-            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        self.indexed_write(id, || {
+            self.put_record(key, value)?;
+            self.index_record(&record)
+        })?;
 
         Ok(record)
     }
@@ -83,27 +187,51 @@ impl ClipboardRepository {
         &self,
         file_path: String,
     ) -> Result<ClipboardRecord, RepositoryError> {
-        let now = Local::now();
-        let id = now.timestamp_nanos_opt().unwrap_or(0) as u64;
-
-        let record = ClipboardRecord {
-            id,
-            content: file_path,
-            created_at: now,
-            content_type: ContentType::Image,
-        };
+        let id = ids::next_id(Local::now(), 1);
+        let record = ClipboardRecord::new(id, file_path, ContentType::Image);
 
         let key = id.to_be_bytes();
         let value = serde_json::to_vec(&record)
             .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
 
-        self.records_tree
-            .insert(key, value)
-            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        self.indexed_write(id, || {
+            self.put_record(key, value)?;
+            self.index_record(&record)
+        })?;
 
         Ok(record)
     }
 
+    /// Save several images captured from one clipboard change as a group
+    ///
+    /// Records share the first record's ID as `group_id` and are returned in save order
+    pub fn save_image_group(
+        &self,
+        file_paths: Vec<String>,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let group_id = ids::next_id(Local::now(), file_paths.len() as u64);
+
+        let mut records = Vec::with_capacity(file_paths.len());
+        for (offset, file_path) in file_paths.into_iter().enumerate() {
+            // The IDs after the group's first one are reserved for the rest of the group
+            let id = group_id + offset as u64;
+            let record = ClipboardRecord {
+                group_id: Some(group_id),
+                ..ClipboardRecord::new(id, file_path, ContentType::Image)
+            };
+
+            let value = serde_json::to_vec(&record)
+                .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+            self.indexed_write(id, || {
+                self.put_record(id.to_be_bytes(), value)?;
+                self.index_record(&record)
+            })?;
+            records.push(record);
+        }
+
+        Ok(records)
+    }
+
     /// Save text content (convenience method)
     pub fn save_text(&self, content: String) -> Result<ClipboardRecord, RepositoryError> {
         self.save(content, ContentType::Text)
@@ -136,49 +264,426 @@ impl ClipboardRepository {
         Ok(records)
     }
 
-    /// Search records by keyword
+    /// Search text records by keyword
     pub fn search(&self, keyword: &str) -> Result<Vec<ClipboardRecord>, RepositoryError> {
-        let keyword_lower = keyword.to_lowercase();
+        self.search_with_scope(keyword, SearchScope::TextOnly)
+    }
+
+    /// Search records by keyword, limited to the record types in `scope`
+    ///
+    /// Keywords of at least three characters are looked up in the search index; shorter
+    /// ones scan every record. A stale index is rebuilt and the search falls back to a scan.
+    pub fn search_with_scope(
+        &self,
+        keyword: &str,
+        scope: SearchScope,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let keyword = normalize_for_search(keyword);
+        let Some(candidates) = self.index_candidates(&keyword)? else {
+            return self.search_scan(&keyword, scope);
+        };
+
+        let mut records = Vec::new();
+        for id in candidates {
+            let Some(record) = self.get_by_id(id)? else {
+                log::warn!("Search index points at missing record {id}, rebuilding it");
+                self.reindex()?;
+                return self.search_scan(&keyword, scope);
+            };
+            if record.matches_keyword(&keyword, scope) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Search records by keyword by scanning every record, most recent first
+    pub fn search_scan(
+        &self,
+        keyword: &str,
+        scope: SearchScope,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let keyword = normalize_for_search(keyword);
         let mut records = Vec::new();
         for result in self.records_tree.iter().rev() {
             let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
             let record: ClipboardRecord = serde_json::from_slice(&value)
                 .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
-            // Only search in text records
-            if record.content_type == ContentType::Text
-                && record.content.to_lowercase().contains(&keyword_lower)
-            {
+            if record.matches_keyword(&keyword, scope) {
                 records.push(record);
             }
         }
         Ok(records)
     }
 
+    /// Overwrite an existing record with updated fields
+    pub fn update(&self, record: &ClipboardRecord) -> Result<(), RepositoryError> {
+        let value = serde_json::to_vec(record)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+        self.indexed_write(record.id, || {
+            let previous = self.put_record(record.id.to_be_bytes(), value)?;
+            if let Some(previous) = previous {
+                let previous: ClipboardRecord = serde_json::from_slice(&previous)
+                    .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+                self.unindex_record(&previous)?;
+            }
+            self.index_record(record)
+        })
+    }
+
+    /// Replace the content of a text record, returning the updated record
+    pub fn update_content(
+        &self,
+        id: u64,
+        content: String,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        record.content_hash =
+            (record.content_type == ContentType::Text).then(|| content_hash(content.as_bytes()));
+        record.content = content;
+        self.update(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Mark or unmark a record as a favorite, returning the updated record
+    pub fn set_favorite(
+        &self,
+        id: u64,
+        favorite: bool,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        record.favorite = favorite;
+        if !favorite {
+            record.favorite_order = None;
+        }
+        self.update(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Flag a record with a color, or clear its flag with `None`, returning the updated record
+    pub fn set_flag(
+        &self,
+        id: u64,
+        flag: Option<RecordFlag>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        record.flag = flag;
+        self.update(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Add a tag to a record, returning the updated record
+    pub fn add_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        if record.add_tag(tag) {
+            self.update(&record)?;
+        }
+        Ok(Some(record))
+    }
+
+    /// Remove a tag from a record, returning the updated record
+    pub fn remove_tag(
+        &self,
+        id: u64,
+        tag: &str,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        if record.remove_tag(tag) {
+            self.update(&record)?;
+        }
+        Ok(Some(record))
+    }
+
+    /// Every tag used in the history, sorted
+    pub fn all_tags(&self) -> Result<Vec<String>, RepositoryError> {
+        let mut guard = self.tag_counts.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            let mut counts = BTreeMap::new();
+            for tag in self
+                .get_recent(usize::MAX)?
+                .into_iter()
+                .flat_map(|record| record.tags)
+            {
+                *counts.entry(tag).or_default() += 1;
+            }
+            *guard = Some(counts);
+        }
+        Ok(guard
+            .iter()
+            .flat_map(|counts| counts.keys().cloned())
+            .collect())
+    }
+
+    /// Set or clear (with `None` or blank text) a record's note, returning the updated record
+    pub fn set_note(
+        &self,
+        id: u64,
+        note: Option<String>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        record.note = clean_note(note);
+        self.update(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Store the manual order of favorites, given as record IDs first to last
+    ///
+    /// Returns the updated records; IDs that no longer exist are skipped.
+    pub fn set_favorite_order(&self, ids: &[u64]) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        let mut updated = Vec::with_capacity(ids.len());
+        for (position, id) in ids.iter().enumerate() {
+            let Some(mut record) = self.get_by_id(*id)? else {
+                continue;
+            };
+            record.favorite_order = Some(position as u32);
+            self.update(&record)?;
+            updated.push(record);
+        }
+        Ok(updated)
+    }
+
+    /// Move a record to the top of the history by re-inserting it under a fresh timestamp ID
+    ///
+    /// Everything else about the record, including its image file, is kept. Returns the
+    /// moved record, or `None` when it doesn't exist.
+    pub fn touch(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(mut record) = self.get_by_id(id)? else {
+            return Ok(None);
+        };
+        let newest = self.newest_id()?;
+        let now = Local::now();
+        // IDs are keys, so the new one must sort after every existing record
+        let new_id = ids::next_id(now, 1).max(newest + 1);
+
+        self.indexed_write(id, || {
+            self.take_record(id.to_be_bytes())?;
+            self.unindex_record(&record)
+        })?;
+        record.id = new_id;
+        record.created_at = now;
+        self.update(&record)?;
+        Ok(Some(record))
+    }
+
+    /// Delete all records that have expired at the given time, returning their IDs
+    pub fn remove_expired(&self, now: DateTime<Local>) -> Result<Vec<u64>, RepositoryError> {
+        let mut expired = Vec::new();
+        for result in self.records_tree.iter() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let record: ClipboardRecord = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            if record.is_expired(now) {
+                expired.push(record.id);
+            }
+        }
+        for id in &expired {
+            self.delete(*id)?;
+        }
+        Ok(expired)
+    }
+
     /// Delete a record
     pub fn delete(&self, id: u64) -> Result<bool, RepositoryError> {
-        // If it's an image record, delete the associated image file
         let record = self.get_by_id(id)?;
+        let removed = self.indexed_write(id, || {
+            if let Some(ref rec) = record {
+                self.unindex_record(rec)?;
+            }
+            self.take_record(id.to_be_bytes())
+        })?;
+        // If it's an image record, delete the associated image file
         if let Some(rec) = record
             && rec.content_type == ContentType::Image
         {
             // Delete original image file and thumbnail
             let _ = fs::remove_file(&rec.content);
-            let thumb_path = rec.content.replace(".png", "_thumb.png");
-            let _ = fs::remove_file(thumb_path);
+            let _ = fs::remove_file(thumbnail_path(Path::new(&rec.content)));
         }
-        let key = id.to_be_bytes();
-        let removed = self
-            .records_tree
-            .remove(key)
-            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
         Ok(removed.is_some())
     }
 
+    /// Move a record to the trash, keeping its image files until it is purged
+    pub fn trash(&self, id: u64) -> Result<bool, RepositoryError> {
+        let Some(record) = self.get_by_id(id)? else {
+            return Ok(false);
+        };
+        let trashed = TrashedRecord {
+            record,
+            deleted_at: Local::now(),
+        };
+        let value = serde_json::to_vec(&trashed)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+        self.indexed_write(id, || {
+            self.unindex_record(&trashed.record)?;
+            self.trash_tree
+                .insert(id.to_be_bytes(), value)
+                .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+            self.take_record(id.to_be_bytes())
+        })?;
+        Ok(true)
+    }
+
+    /// List trashed records, most recently deleted first
+    pub fn list_trash(&self) -> Result<Vec<TrashedRecord>, RepositoryError> {
+        let mut trashed = Vec::new();
+        for result in self.trash_tree.iter() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let entry: TrashedRecord = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            trashed.push(entry);
+        }
+        trashed.sort_by_key(|record| std::cmp::Reverse(record.deleted_at));
+        Ok(trashed)
+    }
+
+    /// Move a trashed record back into the history, returning it
+    pub fn restore(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(value) = self
+            .trash_tree
+            .remove(id.to_be_bytes())
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let trashed: TrashedRecord = serde_json::from_slice(&value)
+            .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+        self.update(&trashed.record)?;
+        Ok(Some(trashed.record))
+    }
+
+    /// Permanently delete trashed records deleted before `cutoff`, returning how many were purged
+    pub fn purge_trash(&self, cutoff: DateTime<Local>) -> Result<usize, RepositoryError> {
+        let mut purged = 0;
+        for entry in self.list_trash()? {
+            if entry.deleted_at >= cutoff {
+                continue;
+            }
+            if entry.record.content_type == ContentType::Image {
+                let _ = fs::remove_file(&entry.record.content);
+                let _ = fs::remove_file(thumbnail_path(Path::new(&entry.record.content)));
+            }
+            self.trash_tree
+                .remove(entry.record.id.to_be_bytes())
+                .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+            purged += 1;
+        }
+        Ok(purged)
+    }
+
+    /// Count a copy of `record` from the history at `at`
+    pub fn record_use(
+        &self,
+        record: &ClipboardRecord,
+        at: DateTime<Local>,
+    ) -> Result<(), RepositoryError> {
+        self.daily_usage_tree
+            .update_and_fetch(day_key(day_bucket(at)), |old| {
+                let count = old.map_or(0, decode_count);
+                Some((count + 1).to_be_bytes().to_vec())
+            })
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+
+        // Records are recreated when re-copied, so items are tracked by content
+        let hash = record
+            .content_hash
+            .unwrap_or_else(|| content_hash(record.content.as_bytes()));
+        let use_count = match self
+            .item_usage_tree
+            .get(hash.to_be_bytes())
+            .map_err(|e| RepositoryError::Query(e.to_string()))?
+        {
+            Some(value) => {
+                let usage: ItemUsage = serde_json::from_slice(&value)
+                    .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+                usage.use_count
+            }
+            None => 0,
+        };
+        let usage = ItemUsage {
+            content: record.content.clone(),
+            content_type: record.content_type.clone(),
+            use_count: use_count + 1,
+        };
+        let value = serde_json::to_vec(&usage)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+        self.item_usage_tree
+            .insert(hash.to_be_bytes(), value)
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Copies made today and this week, and the most-copied items
+    pub fn usage_stats(&self, now: DateTime<Local>) -> Result<UsageStats, RepositoryError> {
+        let today = day_bucket(now);
+        let mut stats = UsageStats::default();
+        for result in self
+            .daily_usage_tree
+            .range(day_key(week_start(today))..=day_key(today))
+        {
+            let (key, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let count = decode_count(&value);
+            if key == day_key(today).as_bytes() {
+                stats.today = count;
+            }
+            stats.this_week += count;
+        }
+
+        for result in self.item_usage_tree.iter() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let usage: ItemUsage = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            stats.top.push(usage);
+        }
+        stats
+            .top
+            .sort_by_key(|item| std::cmp::Reverse(item.use_count));
+        stats.top.truncate(TOP_ITEMS);
+        Ok(stats)
+    }
+
+    /// Forget all copy statistics
+    pub fn reset_usage(&self) -> Result<(), RepositoryError> {
+        self.daily_usage_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        self.item_usage_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        Ok(())
+    }
+
     /// Clear all records
     pub fn clear(&self) -> Result<(), RepositoryError> {
         self.records_tree
             .clear()
             .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        self.record_count.store(0, Ordering::Release);
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        *self.tag_counts.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        self.index_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        self.dedup_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        // Trashed images are removed below, so the trash can't be restored either
+        self.trash_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        // The usage statistics keep copied content, which must not outlive the history
+        self.reset_usage()?;
         // Clear all image files
         if self.images_dir.exists() {
             fs::remove_dir_all(&self.images_dir).ok();
@@ -186,9 +691,205 @@ impl ClipboardRepository {
         Ok(())
     }
 
+    /// Write the missing thumbnail of every image record, so the list doesn't fall back to
+    /// the full image
+    ///
+    /// Records whose image is gone or can't be decoded are skipped. Returns the number of
+    /// thumbnails written.
+    pub fn regenerate_thumbnails(&self) -> Result<usize, RepositoryError> {
+        let mut regenerated = 0;
+        for record in self.get_recent(usize::MAX)? {
+            let path = Path::new(&record.content);
+            if record.content_type != ContentType::Image
+                || !path.is_file()
+                || thumbnail_path(path).exists()
+            {
+                continue;
+            }
+            match save_thumbnail(path) {
+                Ok(()) => regenerated += 1,
+                Err(e) => log::warn!(
+                    "Failed to regenerate the thumbnail of {}: {e}",
+                    record.content
+                ),
+            }
+        }
+        Ok(regenerated)
+    }
+
+    /// Check the history against the images directory, optionally repairing what is found
+    ///
+    /// Looks for image records whose file is gone, files no record refers to and values that
+    /// can't be read. Repairing deletes the first two and moves unreadable values to a
+    /// quarantine tree, so they can still be recovered by hand. Files in `keep` are never
+    /// treated as orphans, e.g. images of records only held in memory.
+    pub fn verify(
+        &self,
+        repair: bool,
+        keep: &HashSet<PathBuf>,
+    ) -> Result<IntegrityReport, RepositoryError> {
+        let mut report = IntegrityReport {
+            repaired: repair,
+            ..Default::default()
+        };
+        let mut referenced = HashSet::new();
+        for path in keep {
+            referenced.extend(image_file_names(path));
+        }
+        // (in trash, record ID) of image records without their file
+        let mut missing: Vec<(bool, u64)> = Vec::new();
+        let mut unreadable: Vec<(&Tree, &str, sled::IVec, sled::IVec)> = Vec::new();
+
+        for (tree, name, in_trash) in [
+            (&self.records_tree, "clipboard_records", false),
+            (&self.trash_tree, "deleted_records", true),
+        ] {
+            for result in tree.iter() {
+                let (key, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+                let record = if in_trash {
+                    serde_json::from_slice::<TrashedRecord>(&value).map(|entry| entry.record)
+                } else {
+                    serde_json::from_slice::<ClipboardRecord>(&value)
+                };
+                let Ok(record) = record else {
+                    unreadable.push((tree, name, key, value));
+                    continue;
+                };
+                if record.content_type != ContentType::Image {
+                    continue;
+                }
+                let path = Path::new(&record.content);
+                referenced.extend(image_file_names(path));
+                if !path.exists() {
+                    missing.push((in_trash, record.id));
+                }
+            }
+        }
+        report.missing_images = missing.len();
+        report.bad_records = unreadable.len();
+
+        let now = std::time::SystemTime::now();
+        let mut orphans = Vec::new();
+        if let Ok(entries) = fs::read_dir(&self.images_dir) {
+            for entry in entries.flatten() {
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                let path = entry.path();
+                if metadata.is_file()
+                    && is_orphan(&path, metadata.modified().ok(), &referenced, now)
+                {
+                    orphans.push(path);
+                }
+            }
+        }
+        report.orphan_files = orphans.len();
+
+        if !repair {
+            return Ok(report);
+        }
+        for (in_trash, id) in missing {
+            if in_trash {
+                self.trash_tree
+                    .remove(id.to_be_bytes())
+                    .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+            } else {
+                self.delete(id)?;
+            }
+        }
+        // Unreadable records can't say what they were indexed under, so go by their IDs
+        let unreadable_ids: HashSet<u64> = unreadable
+            .iter()
+            .filter(|(tree, ..)| std::ptr::eq(*tree, &self.records_tree))
+            .filter_map(|(_, _, key, _)| id_from_key(key))
+            .collect();
+        self.unindex_ids(&unreadable_ids)?;
+        for (tree, name, key, value) in unreadable {
+            let quarantine_key = [name.as_bytes(), b":", &key].concat();
+            self.quarantine_tree
+                .insert(quarantine_key, value)
+                .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+            if std::ptr::eq(tree, &self.records_tree) {
+                self.take_record(key)?;
+            } else {
+                tree.remove(key)
+                    .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+            }
+        }
+        for path in orphans {
+            if let Err(e) = fs::remove_file(&path) {
+                log::warn!("Failed to remove orphan image {}: {e}", path.display());
+            }
+        }
+        Ok(report)
+    }
+
     /// Get the total number of records
     pub fn count(&self) -> usize {
-        self.records_tree.len()
+        self.record_count.load(Ordering::Acquire)
+    }
+
+    /// Counter that changes whenever a record is saved, changed or removed
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    /// Store a record's value under its key, counting it if the key is new
+    fn put_record(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: Vec<u8>,
+    ) -> Result<Option<sled::IVec>, RepositoryError> {
+        let value = sled::IVec::from(value);
+        let previous = self
+            .records_tree
+            .insert(key, value.clone())
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        if previous.is_none() {
+            self.record_count.fetch_add(1, Ordering::AcqRel);
+        }
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        self.count_tags(previous.as_deref(), Some(&value));
+        Ok(previous)
+    }
+
+    /// Remove a record's value, uncounting it if it was there
+    fn take_record(&self, key: impl AsRef<[u8]>) -> Result<Option<sled::IVec>, RepositoryError> {
+        let removed = self
+            .records_tree
+            .remove(key)
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        if removed.is_some() {
+            self.record_count.fetch_sub(1, Ordering::AcqRel);
+            self.generation.fetch_add(1, Ordering::AcqRel);
+        }
+        self.count_tags(removed.as_deref(), None);
+        Ok(removed)
+    }
+
+    /// Move the tag counts from a record value that was replaced or removed to its new value
+    fn count_tags(&self, removed: Option<&[u8]>, added: Option<&[u8]>) {
+        let mut guard = self.tag_counts.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(counts) = guard.as_mut() else {
+            return;
+        };
+        let tags_of = |value: Option<&[u8]>| value.map_or(Some(Vec::new()), stored_tags);
+        let (Some(removed), Some(added)) = (tags_of(removed), tags_of(added)) else {
+            // Counted again on the next `all_tags` call
+            *guard = None;
+            return;
+        };
+        for tag in removed {
+            if let Some(count) = counts.get_mut(&tag) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&tag);
+                }
+            }
+        }
+        for tag in added {
+            *counts.entry(tag).or_default() += 1;
+        }
     }
 
     /// Flush data to disk
@@ -199,42 +900,601 @@ impl ClipboardRepository {
         Ok(())
     }
 
-    /// Clean up old records, keeping the most recent N records
-    pub fn cleanup_old_records(&self, keep_count: usize) -> Result<usize, RepositoryError> {
-        let total = self.count();
-        if total <= keep_count {
-            return Ok(0);
+    /// Export all records and their image files into a single zip archive
+    ///
+    /// Image records are rewritten to point inside the archive's `images/` directory.
+    /// Returns the number of exported records.
+    pub fn export_archive(&self, path: &Path) -> Result<usize, RepositoryError> {
+        let file = File::create(path).map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+
+        let mut records = self.get_recent(usize::MAX)?;
+        for record in records.iter_mut() {
+            if record.content_type != ContentType::Image {
+                continue;
+            }
+            let source = PathBuf::from(&record.content);
+            let Some(file_name) = source.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let archive_path = format!("{ARCHIVE_IMAGES_DIR}/{file_name}");
+            let thumb_source = thumbnail_path(&source);
+            let thumb_name = thumb_source
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            for (source, archive_path) in [
+                (source.clone(), archive_path.clone()),
+                (thumb_source, format!("{ARCHIVE_IMAGES_DIR}/{thumb_name}")),
+            ] {
+                // Missing files are skipped; the record still round-trips
+                let Ok(bytes) = fs::read(&source) else {
+                    continue;
+                };
+                zip.start_file(archive_path, options)
+                    .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+                zip.write_all(&bytes)
+                    .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+            }
+            record.content = archive_path;
         }
 
-        let to_remove = total - keep_count;
-        let mut removed = 0;
+        let json = serde_json::to_vec_pretty(&records)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+        zip.start_file(ARCHIVE_RECORDS, options)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        zip.write_all(&json)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        zip.finish()
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        Ok(records.len())
+    }
 
-        for result in self.records_tree.iter().take(to_remove) {
-            let (key, _) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
-            self.records_tree
-                .remove(key)
-                .map_err(|e| RepositoryError::Delete(e.to_string()))?;
-            removed += 1;
+    /// Copy the file of every image record into `dest`, named by capture time, and write an
+    /// `index.json` describing them, oldest first
+    ///
+    /// Records whose image file is missing are skipped and reported. `dest` must be empty
+    /// or not exist yet, so no file already there is overwritten.
+    pub fn export_images(&self, dest: &Path) -> Result<ImageExportReport, RepositoryError> {
+        fs::create_dir_all(dest).map_err(|e| RepositoryError::Export(e.to_string()))?;
+        let mut entries = fs::read_dir(dest).map_err(|e| RepositoryError::Export(e.to_string()))?;
+        if entries.next().is_some() {
+            return Err(RepositoryError::Export(format!(
+                "{} is not empty",
+                dest.display()
+            )));
         }
+        let mut records = self.get_recent(usize::MAX)?;
+        records.retain(|record| record.content_type == ContentType::Image);
+        records.reverse();
 
-        Ok(removed)
+        let mut report = ImageExportReport::default();
+        let mut taken = HashSet::from([IMAGE_INDEX_FILE.to_string()]);
+        let mut index = Vec::new();
+        for record in records {
+            let source = Path::new(&record.content);
+            if !source.is_file() {
+                report.missing.push(record.id);
+                continue;
+            }
+            let file = image_export_name(&record.created_at, source, &mut taken);
+            fs::copy(source, dest.join(&file))
+                .map_err(|e| RepositoryError::Export(e.to_string()))?;
+            index.push(ImageIndexEntry {
+                file,
+                id: record.id,
+                created_at: record.created_at,
+                note: record.note,
+            });
+            report.exported += 1;
+        }
+
+        let json = serde_json::to_vec_pretty(&index)
+            .map_err(|e| RepositoryError::Serialization(e.to_string()))?;
+        fs::write(dest.join(IMAGE_INDEX_FILE), json)
+            .map_err(|e| RepositoryError::Export(e.to_string()))?;
+        Ok(report)
     }
-}
 
-impl Drop for ClipboardRepository {
-    fn drop(&mut self) {
-        self.flush().ok();
+    /// Save a single record to `dest`, see [`ClipboardRecord::write_to_file`]
+    ///
+    /// Returns `false` when the record doesn't exist.
+    pub fn export_single(&self, id: u64, dest: &Path) -> Result<bool, RepositoryError> {
+        let Some(record) = self.get_by_id(id)? else {
+            return Ok(false);
+        };
+        record
+            .write_to_file(dest)
+            .map_err(|e| RepositoryError::Export(e.to_string()))?;
+        Ok(true)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::thread;
-    use std::time::Duration;
-    use tempfile::tempdir;
+    /// Import records and image files from an archive written by `export_archive`
+    ///
+    /// Imported records are added to the history under fresh IDs, keeping their order, so
+    /// they never replace local records. Image files are extracted into this repository's
+    /// image directory under fresh names and their records rewritten to the new location.
+    /// Returns the number of imported records.
+    pub fn import_archive(&self, path: &Path) -> Result<usize, RepositoryError> {
+        let file = File::open(path).map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        let mut archive =
+            ZipArchive::new(file).map_err(|e| RepositoryError::Archive(e.to_string()))?;
 
-    fn create_test_repo() -> ClipboardRepository {
+        let mut json = Vec::new();
+        archive
+            .by_name(ARCHIVE_RECORDS)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?
+            .read_to_end(&mut json)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        let mut records: Vec<ClipboardRecord> = serde_json::from_slice(&json)
+            .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+
+        // Archives list records newest first, and newer records need higher IDs
+        records.sort_by_key(|record| record.id);
+        let first_id = ids::next_id(Local::now(), records.len() as u64);
+        let new_ids: HashMap<u64, u64> = records
+            .iter()
+            .enumerate()
+            .map(|(offset, record)| (record.id, first_id + offset as u64))
+            .collect();
+        for record in records.iter_mut() {
+            record.id = new_ids[&record.id];
+            record.group_id = record
+                .group_id
+                .and_then(|group_id| new_ids.get(&group_id).copied());
+        }
+
+        fs::create_dir_all(&self.images_dir)
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        for record in records.iter_mut() {
+            if record.content_type != ContentType::Image {
+                continue;
+            }
+            // Only trust the file name so a crafted archive cannot write outside the image dir
+            let Some(file_name) = Path::new(&record.content)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(str::to_string)
+            else {
+                continue;
+            };
+            // Named after the new ID, so local image files are never replaced
+            let extension = Path::new(&file_name)
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .unwrap_or("png");
+            let target = self.images_dir.join(format!("{}.{extension}", record.id));
+            let thumb_name = thumbnail_path(Path::new(&file_name))
+                .to_string_lossy()
+                .to_string();
+            for (name, target) in [
+                (file_name, target.clone()),
+                (thumb_name, thumbnail_path(&target)),
+            ] {
+                let Ok(mut entry) = archive.by_name(&format!("{ARCHIVE_IMAGES_DIR}/{name}")) else {
+                    continue;
+                };
+                let mut bytes = Vec::new();
+                entry
+                    .read_to_end(&mut bytes)
+                    .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+                fs::write(&target, bytes).map_err(|e| RepositoryError::Archive(e.to_string()))?;
+            }
+            record.content = target.to_string_lossy().to_string();
+        }
+
+        for record in &records {
+            self.update(record)?;
+        }
+        Ok(records.len())
+    }
+
+    /// Stream all records as JSON Lines, newest first, without collecting them
+    ///
+    /// Image records keep their file paths; use `export_archive` to carry the files along.
+    /// Returns the number of exported records.
+    pub fn export_jsonl<W: Write>(&self, mut writer: W) -> Result<usize, RepositoryError> {
+        let mut exported = 0;
+        for result in self.records_tree.iter().rev() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            // Stored values are already serialized records; check them before writing
+            serde_json::from_slice::<ClipboardRecord>(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            writer
+                .write_all(&value)
+                .and_then(|_| writer.write_all(b"\n"))
+                .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+            exported += 1;
+        }
+        writer
+            .flush()
+            .map_err(|e| RepositoryError::Archive(e.to_string()))?;
+        Ok(exported)
+    }
+
+    /// Import records written by `export_jsonl`, one line at a time
+    ///
+    /// Blank lines are skipped and records with an existing ID are overwritten.
+    /// Returns the number of imported records.
+    pub fn import_jsonl<R: BufRead>(&self, reader: R) -> Result<usize, RepositoryError> {
+        let mut imported = 0;
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|e| RepositoryError::Archive(e.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: ClipboardRecord = serde_json::from_str(&line).map_err(|e| {
+                RepositoryError::Deserialization(format!("line {}: {e}", index + 1))
+            })?;
+            self.update(&record)?;
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// Clean up old records, keeping the most recent N records
+    pub fn cleanup_old_records(&self, keep_count: usize) -> Result<usize, RepositoryError> {
+        let total = self.count();
+        if total <= keep_count {
+            return Ok(0);
+        }
+
+        let to_remove = total - keep_count;
+        let mut removed = 0;
+
+        for result in self.records_tree.iter().take(to_remove) {
+            let (key, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let record: ClipboardRecord = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            self.indexed_write(record.id, || {
+                self.unindex_record(&record)?;
+                self.take_record(key)
+            })?;
+            removed += 1;
+        }
+
+        Ok(removed)
+    }
+
+    /// Apply the storage limits: keep the newest `max_records` records, then delete
+    /// records until the history fits in the optional `(max_bytes, images_first)` budget
+    ///
+    /// Returns the IDs evicted by the size budget. Records beyond `max_records` are the
+    /// oldest ones, so callers only need to truncate their loaded page to drop those.
+    pub fn trim_history(
+        &self,
+        max_records: usize,
+        size_budget: Option<(u64, bool)>,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        self.cleanup_old_records(max_records)?;
+        match size_budget {
+            Some((max_bytes, images_first)) => self.enforce_size_budget(max_bytes, images_first),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Delete records until the history fits in `max_bytes`, returning the deleted IDs
+    ///
+    /// A record's size is its stored entry plus its image files. See [`records_to_evict`]
+    /// for the eviction order.
+    pub fn enforce_size_budget(
+        &self,
+        max_bytes: u64,
+        images_first: bool,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        let mut sizes = Vec::new();
+        for result in self.records_tree.iter() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let record: ClipboardRecord = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            let is_image = record.content_type == ContentType::Image;
+            let file_bytes = if is_image {
+                [
+                    PathBuf::from(&record.content),
+                    thumbnail_path(Path::new(&record.content)),
+                ]
+                .iter()
+                .filter_map(|path| fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum()
+            } else {
+                0
+            };
+            sizes.push(RecordSize {
+                id: record.id,
+                is_image,
+                bytes: value.len() as u64 + file_bytes,
+            });
+        }
+
+        let evicted = records_to_evict(&sizes, max_bytes, images_first);
+        for id in &evicted {
+            self.delete(*id)?;
+        }
+        Ok(evicted)
+    }
+
+    /// Whether the search index is missing, outdated or was left behind by an interrupted
+    /// write, so searches scan until [`Self::reindex`] has run
+    pub fn index_is_stale(&self) -> bool {
+        !self.index_current.load(Ordering::Acquire)
+    }
+
+    /// Rebuild the search index if [`Self::index_is_stale`], returning whether it did
+    ///
+    /// This reads every record, so callers with a UI run it in the background.
+    pub fn reindex_if_stale(&self) -> Result<bool, RepositoryError> {
+        if !self.index_is_stale() {
+            return Ok(false);
+        }
+        self.reindex()?;
+        Ok(true)
+    }
+
+    /// Rebuild the search index from the stored records
+    pub fn reindex(&self) -> Result<(), RepositoryError> {
+        self.index_current.store(false, Ordering::Release);
+        // Markers of writes still in flight are theirs to remove
+        let pending: Vec<sled::IVec> = self
+            .index_meta_tree
+            .scan_prefix(INDEX_PENDING_PREFIX)
+            .keys()
+            .collect::<Result<_, _>>()
+            .map_err(|e| RepositoryError::Query(e.to_string()))?;
+        self.index_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        self.dedup_tree
+            .clear()
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        for result in self.records_tree.iter() {
+            let (_, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            let record: ClipboardRecord = serde_json::from_slice(&value)
+                .map_err(|e| RepositoryError::Deserialization(e.to_string()))?;
+            self.index_record(&record)?;
+        }
+        self.index_meta_tree
+            .insert(INDEX_VERSION_KEY, &[INDEX_VERSION])
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        for key in pending {
+            self.index_meta_tree
+                .remove(key)
+                .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        }
+        self.index_current.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Run a write to record `id` and its index entries, marked as pending in the index
+    /// metadata until it is done
+    ///
+    /// A marker still there on the next start means the record and the index may disagree,
+    /// so the index is rebuilt. Markers of failed writes are kept for the same reason.
+    fn indexed_write<T>(
+        &self,
+        id: u64,
+        write: impl FnOnce() -> Result<T, RepositoryError>,
+    ) -> Result<T, RepositoryError> {
+        let marker = [INDEX_PENDING_PREFIX, &id.to_be_bytes()].concat();
+        self.index_meta_tree
+            .insert(&marker, &[])
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        let result = write()?;
+        self.index_meta_tree
+            .remove(&marker)
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        Ok(result)
+    }
+
+    /// Trigrams under which a record is indexed
+    fn record_trigrams(record: &ClipboardRecord) -> HashSet<String> {
+        // Index everything any scope can search; results are checked against the scope
+        record
+            .search_text(SearchScope::All)
+            .into_iter()
+            .chain(record.note.as_deref())
+            .flat_map(|text| indexed_trigrams(&normalize_for_search(text)))
+            .collect()
+    }
+
+    /// Key of a text record in the duplicate lookup
+    fn dedup_key(record: &ClipboardRecord) -> Option<[u8; 8]> {
+        (record.content_type == ContentType::Text)
+            .then(|| content_hash(whitespace_normalized(&record.content).as_bytes()).to_be_bytes())
+    }
+
+    /// Key of a URL in the duplicate lookup, prefixed to keep it apart from plain text keys
+    fn url_dedup_key(url: &str) -> [u8; 8] {
+        content_hash(format!("url:{}", canonicalize_url(url)).as_bytes()).to_be_bytes()
+    }
+
+    /// Keys of a record in the duplicate lookup
+    fn dedup_keys(record: &ClipboardRecord) -> impl Iterator<Item = [u8; 8]> {
+        let url_key = (record.content_type == ContentType::Text && is_url(&record.content))
+            .then(|| Self::url_dedup_key(&record.content));
+        Self::dedup_key(record).into_iter().chain(url_key)
+    }
+
+    fn index_record(&self, record: &ClipboardRecord) -> Result<(), RepositoryError> {
+        let mut batch = sled::Batch::default();
+        for trigram in Self::record_trigrams(record) {
+            batch.insert(index_key(&trigram, record.id), &[]);
+        }
+        self.index_tree
+            .apply_batch(batch)
+            .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        for key in Self::dedup_keys(record) {
+            self.dedup_tree
+                .insert(key, &record.id.to_be_bytes())
+                .map_err(|e| RepositoryError::Insert(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    fn unindex_record(&self, record: &ClipboardRecord) -> Result<(), RepositoryError> {
+        let mut batch = sled::Batch::default();
+        for trigram in Self::record_trigrams(record) {
+            batch.remove(index_key(&trigram, record.id));
+        }
+        self.index_tree
+            .apply_batch(batch)
+            .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        for key in Self::dedup_keys(record) {
+            // Only drop the entry if no newer equivalent record took it over
+            self.dedup_tree
+                .compare_and_swap(key, Some(&record.id.to_be_bytes()), None::<&[u8]>)
+                .map_err(|e| RepositoryError::Delete(e.to_string()))?
+                .ok();
+        }
+        Ok(())
+    }
+
+    /// Remove the index and dedup entries of records by ID alone, scanning both trees
+    fn unindex_ids(&self, ids: &HashSet<u64>) -> Result<(), RepositoryError> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+        for tree in [&self.index_tree, &self.dedup_tree] {
+            let mut batch = sled::Batch::default();
+            for result in tree.iter() {
+                let (key, value) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+                // Index keys end with the record ID, dedup entries hold it as their value
+                let id = if std::ptr::eq(tree, &self.index_tree) {
+                    id_from_key(&key)
+                } else {
+                    id_from_key(&value)
+                };
+                if id.is_some_and(|id| ids.contains(&id)) {
+                    batch.remove(key);
+                }
+            }
+            tree.apply_batch(batch)
+                .map_err(|e| RepositoryError::Delete(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Find a text record whose content equals `text` up to surrounding and repeated whitespace
+    pub fn find_equivalent_text(
+        &self,
+        text: &str,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let key = content_hash(whitespace_normalized(text).as_bytes()).to_be_bytes();
+        // The hash may collide, so compare the texts themselves
+        Ok(self.find_dedup_entry(key)?.filter(|record| {
+            record.content_type == ContentType::Text && texts_equivalent(&record.content, text)
+        }))
+    }
+
+    /// Find a record holding the same URL as `url` once both are canonicalized, see
+    /// [`canonicalize_url`]
+    pub fn find_equivalent_url(
+        &self,
+        url: &str,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        if !is_url(url) {
+            return Ok(None);
+        }
+        let canonical = canonicalize_url(url);
+        Ok(self
+            .find_dedup_entry(Self::url_dedup_key(url))?
+            .filter(|record| {
+                record.content_type == ContentType::Text
+                    && is_url(&record.content)
+                    && canonicalize_url(&record.content) == canonical
+            }))
+    }
+
+    /// Record stored under a duplicate lookup key
+    fn find_dedup_entry(&self, key: [u8; 8]) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        let Some(value) = self
+            .dedup_tree
+            .get(key)
+            .map_err(|e| RepositoryError::Query(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let Ok(id) = value.as_ref().try_into().map(u64::from_be_bytes) else {
+            return Ok(None);
+        };
+        self.get_by_id(id)
+    }
+
+    /// IDs of the records indexed under `token`
+    fn index_ids(&self, token: &str) -> Result<HashSet<u64>, RepositoryError> {
+        let mut ids = HashSet::new();
+        for result in self.index_tree.scan_prefix(index_prefix(token)) {
+            let (key, _) = result.map_err(|e| RepositoryError::Query(e.to_string()))?;
+            ids.extend(id_from_key(&key));
+        }
+        Ok(ids)
+    }
+
+    /// IDs of records that may contain the normalized keyword, most recent first
+    ///
+    /// Returns `None` when the keyword is too short to use the index, or while the index is
+    /// stale.
+    fn index_candidates(&self, keyword: &str) -> Result<Option<Vec<u64>>, RepositoryError> {
+        let grams = trigrams(keyword);
+        if grams.is_empty() || self.index_is_stale() {
+            return Ok(None);
+        }
+        let mut candidates: Option<HashSet<u64>> = None;
+        for trigram in grams {
+            let ids = self.index_ids(&trigram)?;
+            let narrowed: HashSet<u64> = match candidates {
+                Some(previous) => previous.intersection(&ids).copied().collect(),
+                None => ids,
+            };
+            let done = narrowed.is_empty();
+            candidates = Some(narrowed);
+            if done {
+                break;
+            }
+        }
+        // The keyword may sit past the indexed start of a long record
+        let mut candidates = candidates.unwrap_or_default();
+        candidates.extend(self.index_ids(PARTIAL_TOKEN)?);
+        let mut ids: Vec<u64> = candidates.into_iter().collect();
+        ids.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(Some(ids))
+    }
+}
+
+/// Decode a big-endian day counter
+fn decode_count(value: &[u8]) -> u64 {
+    value.try_into().map(u64::from_be_bytes).unwrap_or(0)
+}
+
+/// Tags of a stored record, without decoding the rest of it
+fn stored_tags(value: &[u8]) -> Option<Vec<String>> {
+    #[derive(serde::Deserialize)]
+    struct StoredTags {
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+    serde_json::from_slice::<StoredTags>(value)
+        .ok()
+        .map(|stored| stored.tags)
+}
+
+impl Drop for ClipboardRepository {
+    fn drop(&mut self) {
+        self.flush().ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn create_test_repo() -> ClipboardRepository {
         let temp_dir = tempdir().expect("Failed to create temp dir");
         let db_path = temp_dir.path().join("test.db");
         ClipboardRepository::init(db_path, temp_dir.path().join("images"))
@@ -250,6 +1510,10 @@ mod tests {
             .expect("Failed to save");
         assert_eq!(record.content, "Hello, World!");
         assert_eq!(record.content_type, ContentType::Text);
+        assert_eq!(
+            record.content_hash,
+            Some(content_hash("Hello, World!".as_bytes()))
+        );
 
         let retrieved = repo
             .get_by_id(record.id)
@@ -275,6 +1539,42 @@ mod tests {
         assert_eq!(recent[2].content, "Record 3");
     }
 
+    #[test]
+    fn test_save_image_group() {
+        let repo = create_test_repo();
+
+        let records = repo
+            .save_image_group(vec!["a.png".to_string(), "b.png".to_string()])
+            .expect("Failed to save group");
+        assert_eq!(records.len(), 2);
+        assert_ne!(records[0].id, records[1].id);
+        assert_eq!(records[0].group_id, Some(records[0].id));
+        assert_eq!(records[1].group_id, Some(records[0].id));
+        assert_eq!(repo.count(), 2);
+    }
+
+    #[test]
+    fn test_rapid_saves_keep_every_record() {
+        let repo = create_test_repo();
+
+        let mut ids = Vec::new();
+        for i in 0..1000 {
+            ids.push(repo.save_text(format!("Record {i}")).unwrap().id);
+        }
+        let group = repo
+            .save_image_group(vec!["a.png".to_string(), "b.png".to_string()])
+            .unwrap();
+        ids.extend(group.iter().map(|record| record.id));
+        ids.push(repo.save_text("last".to_string()).unwrap().id);
+
+        // Strictly increasing, so no key was reused and save order is kept
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(repo.count(), 1003);
+        let recent = repo.get_recent(2).unwrap();
+        assert_eq!(recent[0].content, "last");
+        assert_eq!(recent[1].content, "b.png");
+    }
+
     #[test]
     fn test_search() {
         let repo = create_test_repo();
@@ -296,6 +1596,281 @@ mod tests {
         assert_eq!(results.len(), 1);
     }
 
+    #[test]
+    fn test_search_with_scope() {
+        let repo = create_test_repo();
+
+        repo.save_text("holiday plans".to_string())
+            .expect("Failed to save");
+        repo.save_image_from_path("/images/holiday.png".to_string())
+            .expect("Failed to save");
+
+        let results = repo
+            .search_with_scope("holiday", SearchScope::TextOnly)
+            .expect("Failed to search");
+        assert_eq!(results.len(), 1);
+
+        let results = repo
+            .search_with_scope("holiday", SearchScope::All)
+            .expect("Failed to search");
+        assert_eq!(results.len(), 2);
+
+        // Directory names are not matched
+        let results = repo
+            .search_with_scope("images", SearchScope::All)
+            .expect("Failed to search");
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_search_index_follows_changes() {
+        let repo = create_test_repo();
+
+        let record = repo
+            .save_text("Quarterly Report".to_string())
+            .expect("Failed to save");
+        repo.save_text("report card".to_string())
+            .expect("Failed to save");
+
+        // Substrings spanning words and short keywords both match
+        assert_eq!(repo.search("rly rep").expect("Failed to search").len(), 1);
+        assert_eq!(repo.search("report").expect("Failed to search").len(), 2);
+        assert_eq!(repo.search("rd").expect("Failed to search").len(), 1);
+
+        repo.update_content(record.id, "Annual summary".to_string())
+            .expect("Failed to update");
+        assert_eq!(repo.search("report").expect("Failed to search").len(), 1);
+        assert_eq!(repo.search("annual").expect("Failed to search").len(), 1);
+
+        repo.delete(record.id).expect("Failed to delete");
+        assert!(repo.search("annual").expect("Failed to search").is_empty());
+    }
+
+    #[test]
+    fn test_search_finds_text_past_the_indexed_start() {
+        let repo = create_test_repo();
+
+        let long = format!(
+            "{} needle",
+            "a".repeat(crate::repository::index::MAX_INDEXED_CHARS)
+        );
+        let record = repo.save_text(long).expect("Failed to save");
+        repo.save_text("haystack".to_string())
+            .expect("Failed to save");
+
+        let results = repo.search("needle").expect("Failed to search");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, record.id);
+
+        repo.delete(record.id).expect("Failed to delete");
+        assert!(repo.search("needle").expect("Failed to search").is_empty());
+    }
+
+    #[test]
+    fn test_search_ignores_case_and_accents() {
+        let repo = create_test_repo();
+
+        repo.save_text("Meet at the Café".to_string())
+            .expect("Failed to save");
+        repo.save_text("Große Straße 5".to_string())
+            .expect("Failed to save");
+
+        assert_eq!(repo.search("CAFE").expect("Failed to search").len(), 1);
+        assert_eq!(repo.search("café").expect("Failed to search").len(), 1);
+        assert_eq!(repo.search("strasse").expect("Failed to search").len(), 1);
+        assert_eq!(
+            repo.search_scan("grosse", SearchScope::TextOnly)
+                .expect("Failed to search")
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_set_note() {
+        let repo = create_test_repo();
+
+        let record = repo
+            .save_text("sk-test-1234".to_string())
+            .expect("Failed to save");
+        assert!(repo.search("staging").expect("Failed to search").is_empty());
+
+        let updated = repo
+            .set_note(record.id, Some(" Staging API key ".to_string()))
+            .expect("Failed to set note")
+            .expect("Record not found");
+        assert_eq!(updated.note.as_deref(), Some("Staging API key"));
+        let found = repo.search("staging").expect("Failed to search");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].note, updated.note);
+
+        repo.set_note(record.id, Some("   ".to_string()))
+            .expect("Failed to clear note");
+        assert!(repo.search("staging").expect("Failed to search").is_empty());
+        let cleared = repo.get_by_id(record.id).expect("Failed to get").unwrap();
+        assert_eq!(cleared.note, None);
+    }
+
+    #[test]
+    fn test_search_rebuilds_stale_index() {
+        let repo = create_test_repo();
+
+        let record = repo
+            .save_text("stale entry".to_string())
+            .expect("Failed to save");
+        repo.save_text("fresh entry".to_string())
+            .expect("Failed to save");
+        // Remove the record behind the index's back
+        repo.records_tree
+            .remove(record.id.to_be_bytes())
+            .expect("Failed to remove");
+
+        let results = repo.search("entry").expect("Failed to search");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "fresh entry");
+        assert!(repo.search("stale").expect("Failed to search").is_empty());
+    }
+
+    #[test]
+    fn test_interrupted_write_marks_index_stale() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let open = || {
+            ClipboardRepository::init(
+                temp_dir.path().join("test.db"),
+                temp_dir.path().join("images"),
+            )
+            .expect("Failed to open test repository")
+        };
+        {
+            let repo = open();
+            assert!(!repo.index_is_stale());
+            let record = ClipboardRecord::test_record(7, "unindexed entry");
+            let value = serde_json::to_vec(&record).unwrap();
+            // Stops after the record, before its index entries
+            let interrupted: Result<(), RepositoryError> = repo.indexed_write(record.id, || {
+                repo.put_record(record.id.to_be_bytes(), value)?;
+                Err(RepositoryError::Insert("interrupted".to_string()))
+            });
+            assert!(interrupted.is_err());
+            repo.flush().expect("Failed to flush");
+        }
+
+        let repo = open();
+        assert!(repo.index_is_stale());
+        // Searches scan meanwhile, so the record is still found
+        assert_eq!(repo.search("unindexed").unwrap().len(), 1);
+        assert!(repo.reindex_if_stale().unwrap());
+        assert!(!repo.index_is_stale());
+        assert!(!repo.reindex_if_stale().unwrap());
+        assert_eq!(repo.search("unindexed").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_update_content() {
+        let repo = create_test_repo();
+        let record = repo.save_text("draft".to_string()).expect("Failed to save");
+
+        let updated = repo
+            .update_content(record.id, "final".to_string())
+            .expect("Failed to update")
+            .expect("Record not found");
+        assert_eq!(updated.content, "final");
+        assert_eq!(updated.content_hash, Some(content_hash(b"final")));
+        assert_eq!(repo.get_by_id(record.id).unwrap(), Some(updated));
+        assert!(
+            repo.update_content(0, "missing".to_string())
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_set_favorite() {
+        let repo = create_test_repo();
+        let record = repo
+            .save_text("snippet".to_string())
+            .expect("Failed to save");
+        assert!(!record.favorite);
+
+        let updated = repo.set_favorite(record.id, true).unwrap().unwrap();
+        assert!(updated.favorite);
+        assert!(repo.get_by_id(record.id).unwrap().unwrap().favorite);
+        assert!(repo.set_favorite(0, true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_set_flag() {
+        let repo = create_test_repo();
+        let record = repo.save_text("todo".to_string()).unwrap();
+        assert_eq!(record.flag, None);
+
+        let updated = repo.set_flag(record.id, Some(RecordFlag::Red)).unwrap();
+        assert_eq!(updated.unwrap().flag, Some(RecordFlag::Red));
+        assert_eq!(
+            repo.get_by_id(record.id).unwrap().unwrap().flag,
+            Some(RecordFlag::Red)
+        );
+
+        repo.set_flag(record.id, None).unwrap();
+        assert_eq!(repo.get_by_id(record.id).unwrap().unwrap().flag, None);
+        assert!(repo.set_flag(0, Some(RecordFlag::Blue)).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_tags() {
+        let repo = create_test_repo();
+        let first = repo.save_text("first".to_string()).unwrap();
+        let second = repo.save_text("second".to_string()).unwrap();
+        assert!(repo.all_tags().unwrap().is_empty());
+
+        let updated = repo.add_tag(first.id, "#Work").unwrap().unwrap();
+        assert_eq!(updated.tags, vec!["work"]);
+        repo.add_tag(second.id, "api").unwrap();
+        repo.add_tag(second.id, "work").unwrap();
+        assert_eq!(
+            repo.get_by_id(second.id).unwrap().unwrap().tags,
+            vec!["api", "work"]
+        );
+        assert_eq!(repo.all_tags().unwrap(), vec!["api", "work"]);
+
+        repo.remove_tag(second.id, "api").unwrap();
+        assert_eq!(repo.all_tags().unwrap(), vec!["work"]);
+        assert!(repo.add_tag(0, "work").unwrap().is_none());
+
+        // Counted per record, so a tag stays until its last record is gone
+        repo.delete(first.id).unwrap();
+        assert_eq!(repo.all_tags().unwrap(), vec!["work"]);
+        repo.trash(second.id).unwrap();
+        assert!(repo.all_tags().unwrap().is_empty());
+        repo.restore(second.id).unwrap();
+        assert_eq!(repo.all_tags().unwrap(), vec!["work"]);
+    }
+
+    #[test]
+    fn test_set_favorite_order() {
+        let repo = create_test_repo();
+        let first = repo.save_text("first".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let second = repo.save_text("second".to_string()).unwrap();
+        repo.set_favorite(first.id, true).unwrap();
+        repo.set_favorite(second.id, true).unwrap();
+
+        let updated = repo.set_favorite_order(&[second.id, first.id, 42]).unwrap();
+        assert_eq!(updated.len(), 2);
+        assert_eq!(
+            repo.get_by_id(second.id).unwrap().unwrap().favorite_order,
+            Some(0)
+        );
+        assert_eq!(
+            repo.get_by_id(first.id).unwrap().unwrap().favorite_order,
+            Some(1)
+        );
+
+        // Unfavoriting forgets the position
+        let record = repo.set_favorite(second.id, false).unwrap().unwrap();
+        assert_eq!(record.favorite_order, None);
+    }
+
     #[test]
     fn test_delete() {
         let repo = create_test_repo();
@@ -313,6 +1888,500 @@ mod tests {
         assert!(!deleted_again);
     }
 
+    #[test]
+    fn test_remove_expired() {
+        let repo = create_test_repo();
+
+        let mut expiring = repo
+            .save_text("Secret".to_string())
+            .expect("Failed to save");
+        let kept = repo.save_text("Kept".to_string()).expect("Failed to save");
+        expiring.expires_at = Some(Local::now() - chrono::Duration::seconds(1));
+        repo.update(&expiring).expect("Failed to update");
+
+        let removed = repo.remove_expired(Local::now()).expect("Failed to remove");
+        assert_eq!(removed, vec![expiring.id]);
+        assert_eq!(repo.count(), 1);
+        assert!(repo.get_by_id(kept.id).expect("Failed to get").is_some());
+    }
+
+    #[test]
+    fn test_archive_round_trip() {
+        let source_dir = tempdir().expect("Failed to create temp dir");
+        let source = ClipboardRepository::init(
+            source_dir.path().join("source.db"),
+            source_dir.path().join("images"),
+        )
+        .expect("Failed to create source repository");
+        fs::create_dir_all(source_dir.path().join("images")).unwrap();
+        let image_path = source_dir.path().join("images").join("1.png");
+        fs::write(&image_path, b"image bytes").unwrap();
+
+        let text = source.save_text("archived text".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let image = source
+            .save_image_from_path(image_path.to_string_lossy().to_string())
+            .unwrap();
+
+        let archive_path = source_dir.path().join("history.zip");
+        assert_eq!(source.export_archive(&archive_path).unwrap(), 2);
+
+        let target_dir = tempdir().expect("Failed to create temp dir");
+        let target_images = target_dir.path().join("images");
+        let target =
+            ClipboardRepository::init(target_dir.path().join("target.db"), target_images.clone())
+                .expect("Failed to create target repository");
+        assert_eq!(target.import_archive(&archive_path).unwrap(), 2);
+
+        let imported = target.get_recent(10).unwrap();
+        assert_eq!(imported[1].content, "archived text");
+        assert_eq!(imported[1].created_at, text.created_at);
+
+        let imported_image = &imported[0];
+        let imported_path = target_images.join(format!("{}.png", imported_image.id));
+        assert_eq!(imported_image.content, imported_path.to_string_lossy());
+        assert_eq!(fs::read(imported_path).unwrap(), b"image bytes");
+
+        // Importing into the source adds copies instead of replacing its records and files
+        assert_eq!(source.import_archive(&archive_path).unwrap(), 2);
+        assert_eq!(source.count(), 4);
+        assert_eq!(source.get_by_id(image.id).unwrap().unwrap(), image);
+        assert_eq!(fs::read(&image_path).unwrap(), b"image bytes");
+        let copies = source.get_recent(2).unwrap();
+        assert!(copies.iter().all(|record| record.id > image.id));
+        assert_ne!(copies[0].content, image.content);
+    }
+
+    /// Write an image file old enough to count as an orphan when no record refers to it
+    fn write_old_image(path: &Path) {
+        fs::write(path, b"image bytes").unwrap();
+        let old = std::time::SystemTime::now() - crate::repository::integrity::ORPHAN_GRACE * 2;
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_regenerate_thumbnails() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let images = dir.path().join("images");
+        let repo = ClipboardRepository::init(dir.path().join("test.db"), images.clone())
+            .expect("Failed to create test repository");
+        fs::create_dir_all(&images).unwrap();
+
+        let mut paths = Vec::new();
+        for name in ["1.png", "2.png"] {
+            let path = images.join(name);
+            image::RgbaImage::new(600, 400).save(&path).unwrap();
+            save_thumbnail(&path).unwrap();
+            repo.save_image_from_path(path.to_string_lossy().to_string())
+                .unwrap();
+            thread::sleep(Duration::from_millis(10));
+            paths.push(path);
+        }
+        repo.save_image_from_path(images.join("gone.png").to_string_lossy().to_string())
+            .unwrap();
+        fs::remove_file(thumbnail_path(&paths[1])).unwrap();
+
+        assert_eq!(repo.regenerate_thumbnails().unwrap(), 1);
+        let thumb = image::open(thumbnail_path(&paths[1])).unwrap();
+        assert_eq!((thumb.width(), thumb.height()), (300, 200));
+        // Nothing left to do
+        assert_eq!(repo.regenerate_thumbnails().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_verify_and_repair() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let images = dir.path().join("images");
+        let repo = ClipboardRepository::init(dir.path().join("test.db"), images.clone())
+            .expect("Failed to create test repository");
+        fs::create_dir_all(&images).unwrap();
+
+        // A healthy image with its thumbnail, and a trashed one still owning its file
+        let kept = images.join("1.png");
+        write_old_image(&kept);
+        write_old_image(&images.join("1_thumb.png"));
+        let kept_record = repo
+            .save_image_from_path(kept.to_string_lossy().to_string())
+            .unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let trashed = images.join("2.png");
+        write_old_image(&trashed);
+        let trashed_record = repo
+            .save_image_from_path(trashed.to_string_lossy().to_string())
+            .unwrap();
+        repo.trash(trashed_record.id).unwrap();
+
+        // An image record whose file was deleted by hand
+        thread::sleep(Duration::from_millis(10));
+        let missing_record = repo
+            .save_image_from_path(images.join("3.png").to_string_lossy().to_string())
+            .unwrap();
+
+        // Files no record refers to, one of them kept for an in-memory record
+        let orphan = images.join("4.png");
+        write_old_image(&orphan);
+        let private = images.join("5.png");
+        write_old_image(&private);
+        let keep = HashSet::from([private.clone()]);
+
+        // A value that isn't a record
+        repo.put_record(7u64.to_be_bytes(), b"not json".to_vec())
+            .unwrap();
+
+        let report = repo.verify(false, &keep).unwrap();
+        assert_eq!(
+            report,
+            IntegrityReport {
+                missing_images: 1,
+                orphan_files: 1,
+                bad_records: 1,
+                repaired: false,
+            }
+        );
+        // Checking alone changes nothing
+        assert!(orphan.exists());
+        assert_eq!(repo.count(), 3);
+
+        let report = repo.verify(true, &keep).unwrap();
+        assert!(report.repaired);
+        assert_eq!(report.orphan_files, 1);
+        assert!(!orphan.exists());
+        assert!(private.exists());
+        assert!(kept.exists() && trashed.exists());
+        assert!(repo.get_by_id(missing_record.id).unwrap().is_none());
+        assert!(repo.get_by_id(kept_record.id).unwrap().is_some());
+        assert_eq!(repo.quarantine_tree.len(), 1);
+
+        assert!(repo.verify(false, &keep).unwrap().is_clean());
+    }
+
+    #[test]
+    fn test_verify_unindexes_unreadable_records() {
+        let repo = create_test_repo();
+        let damaged = repo.save_text("stale words".to_string()).unwrap();
+        let intact = repo.save_text("stale bread".to_string()).unwrap();
+        repo.records_tree
+            .insert(damaged.id.to_be_bytes(), b"not json".to_vec())
+            .unwrap();
+
+        repo.verify(true, &HashSet::new()).unwrap();
+        assert_eq!(repo.index_ids("sta").unwrap(), HashSet::from([intact.id]));
+        assert!(
+            repo.dedup_tree
+                .iter()
+                .values()
+                .all(|id| id_from_key(&id.unwrap()) != Some(damaged.id))
+        );
+        // The same text can be captured again as a new record
+        let again = repo.save_text("stale words".to_string()).unwrap();
+        assert_ne!(again.id, damaged.id);
+    }
+
+    #[test]
+    fn test_export_single() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let repo = ClipboardRepository::init(dir.path().join("test.db"), dir.path().join("images"))
+            .expect("Failed to create test repository");
+        let image_path = dir.path().join("1.png");
+        fs::write(&image_path, b"image bytes").unwrap();
+
+        let text = repo.save_text("shared text".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let image = repo
+            .save_image_from_path(image_path.to_string_lossy().to_string())
+            .unwrap();
+
+        let text_dest = dir.path().join("note.txt");
+        assert!(repo.export_single(text.id, &text_dest).unwrap());
+        assert_eq!(fs::read_to_string(&text_dest).unwrap(), "shared text");
+
+        let image_dest = dir.path().join("copy.png");
+        assert!(repo.export_single(image.id, &image_dest).unwrap());
+        assert_eq!(fs::read(&image_dest).unwrap(), b"image bytes");
+
+        assert!(!repo.export_single(42, &text_dest).unwrap());
+        assert!(matches!(
+            repo.export_single(text.id, dir.path()),
+            Err(RepositoryError::Export(_))
+        ));
+        assert!(matches!(
+            repo.export_single(text.id, &dir.path().join("missing").join("a.txt")),
+            Err(RepositoryError::Export(_))
+        ));
+    }
+
+    #[test]
+    fn test_export_images() {
+        let dir = tempdir().expect("Failed to create temp dir");
+        let repo = ClipboardRepository::init(dir.path().join("test.db"), dir.path().join("images"))
+            .expect("Failed to create test repository");
+        let mut images = Vec::new();
+        for (name, bytes) in [("1.png", b"first"), ("2.png", b"other")] {
+            let path = dir.path().join(name);
+            fs::write(&path, bytes).unwrap();
+            images.push(
+                repo.save_image_from_path(path.to_string_lossy().to_string())
+                    .unwrap(),
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+        repo.save_text("not an image".to_string()).unwrap();
+        let gone = repo
+            .save_image_from_path(dir.path().join("gone.png").to_string_lossy().to_string())
+            .unwrap();
+        repo.set_note(images[1].id, Some("logo draft".to_string()))
+            .unwrap();
+
+        let dest = dir.path().join("export");
+        let report = repo.export_images(&dest).unwrap();
+        assert_eq!(report.exported, 2);
+        assert_eq!(report.missing, vec![gone.id]);
+
+        let index: serde_json::Value =
+            serde_json::from_slice(&fs::read(dest.join("index.json")).unwrap()).unwrap();
+        let entries = index.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        // Oldest first, each pointing at a copy of its record's file
+        for (entry, (record, bytes)) in entries.iter().zip(images.iter().zip([b"first", b"other"]))
+        {
+            assert_eq!(entry["id"], record.id);
+            let file = entry["file"].as_str().unwrap();
+            assert!(file.ends_with(".png"));
+            assert_eq!(fs::read(dest.join(file)).unwrap(), bytes);
+        }
+        assert!(entries[0].get("note").is_none());
+        assert_eq!(entries[1]["note"], "logo draft");
+        assert_ne!(entries[0]["file"], entries[1]["file"]);
+
+        // A second export into the same folder would overwrite the first
+        let first_index = fs::read(dest.join("index.json")).unwrap();
+        assert!(repo.export_images(&dest).is_err());
+        assert_eq!(fs::read(dest.join("index.json")).unwrap(), first_index);
+    }
+
+    #[test]
+    fn test_find_equivalent_text() {
+        let repo = create_test_repo();
+
+        let record = repo.save_text("foo  bar\n".to_string()).unwrap();
+        let found = repo.find_equivalent_text(" foo bar").unwrap();
+        assert_eq!(found.map(|r| r.id), Some(record.id));
+        assert!(repo.find_equivalent_text("foobar").unwrap().is_none());
+
+        repo.delete(record.id).unwrap();
+        assert!(repo.find_equivalent_text("foo bar").unwrap().is_none());
+
+        // Rebuilding the index restores lookups for existing records
+        let record = repo.save_text("baz\n".to_string()).unwrap();
+        repo.dedup_tree.clear().unwrap();
+        repo.reindex().unwrap();
+        let found = repo.find_equivalent_text("baz").unwrap();
+        assert_eq!(found.map(|r| r.id), Some(record.id));
+    }
+
+    #[test]
+    fn test_find_equivalent_url() {
+        let repo = create_test_repo();
+
+        let record = repo
+            .save_text("https://example.com/docs/?utm_source=news".to_string())
+            .unwrap();
+        let found = repo
+            .find_equivalent_url("https://Example.com/docs")
+            .unwrap();
+        assert_eq!(found.map(|r| r.id), Some(record.id));
+        assert!(
+            repo.find_equivalent_url("https://example.com/docs?page=2")
+                .unwrap()
+                .is_none()
+        );
+        // Only URLs are canonicalized
+        assert!(
+            repo.find_equivalent_url("example.com/docs")
+                .unwrap()
+                .is_none()
+        );
+
+        repo.delete(record.id).unwrap();
+        assert!(
+            repo.find_equivalent_url("https://example.com/docs")
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_touch_moves_record_to_top() {
+        let repo = create_test_repo();
+
+        let old = repo.save_text("old snippet".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        repo.save_text("newer".to_string()).unwrap();
+
+        let mut old = repo.set_favorite(old.id, true).unwrap().unwrap();
+        old.note = Some("keep me".to_string());
+        old.source_app = Some("Terminal".to_string());
+        old.favorite_order = Some(2);
+        old.lossy_encoding = true;
+        old.delete_after_paste = true;
+        repo.update(&old).unwrap();
+
+        let touched = repo.touch(old.id).unwrap().expect("Record not found");
+        assert!(touched.id > old.id);
+        assert!(touched.created_at >= old.created_at);
+        assert_eq!(
+            ClipboardRecord {
+                id: old.id,
+                created_at: old.created_at,
+                ..touched.clone()
+            },
+            old
+        );
+
+        let recent = repo.get_recent(10).unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0], touched);
+        assert!(repo.get_by_id(old.id).unwrap().is_none());
+        assert_eq!(repo.search("snippet").unwrap(), vec![touched]);
+        assert!(repo.touch(old.id).unwrap().is_none());
+    }
+
+    /// Writer that only remembers how much was written, and in how large pieces
+    #[derive(Default)]
+    struct CountingWriter {
+        total: usize,
+        largest_write: usize,
+        lines: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.total += buf.len();
+            self.largest_write = self.largest_write.max(buf.len());
+            self.lines += buf.iter().filter(|&&b| b == b'\n').count();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_export_jsonl_streams_records() {
+        let repo = create_test_repo();
+        for i in 0..500 {
+            repo.save_text(format!("record number {i}")).unwrap();
+        }
+        let count = repo.count();
+
+        let mut writer = CountingWriter::default();
+        assert_eq!(repo.export_jsonl(&mut writer).unwrap(), count);
+        assert_eq!(writer.lines, count);
+        // Records are written one at a time, never as one large buffer
+        assert!(writer.largest_write < 1024);
+        assert!(writer.total > 100 * writer.largest_write);
+    }
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let source = create_test_repo();
+        let first = source.save_text("first".to_string()).unwrap();
+        thread::sleep(Duration::from_millis(10));
+        let second = source.save_text("second\nline".to_string()).unwrap();
+
+        let mut exported = Vec::new();
+        assert_eq!(source.export_jsonl(&mut exported).unwrap(), 2);
+        let text = String::from_utf8(exported.clone()).unwrap();
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.lines().next().unwrap().contains("second"));
+
+        let target = create_test_repo();
+        let input = [exported.as_slice(), b"\n"].concat();
+        assert_eq!(target.import_jsonl(input.as_slice()).unwrap(), 2);
+        assert_eq!(
+            target.get_by_id(first.id).unwrap().unwrap().content,
+            "first"
+        );
+        assert_eq!(
+            target.get_by_id(second.id).unwrap().unwrap().content,
+            "second\nline"
+        );
+
+        assert!(matches!(
+            target.import_jsonl(&b"not json\n"[..]),
+            Err(RepositoryError::Deserialization(_))
+        ));
+    }
+
+    #[test]
+    fn test_trash_restore_and_purge() {
+        let repo = create_test_repo();
+
+        let kept = repo.save_text("kept".to_string()).expect("Failed to save");
+        let trashed = repo
+            .save_text("trashed".to_string())
+            .expect("Failed to save");
+
+        assert!(repo.trash(trashed.id).expect("Failed to trash"));
+        assert!(repo.get_by_id(trashed.id).unwrap().is_none());
+        let trash = repo.list_trash().expect("Failed to list trash");
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].record, trashed);
+
+        let restored = repo.restore(trashed.id).expect("Failed to restore");
+        assert_eq!(restored, Some(trashed.clone()));
+        assert!(repo.get_by_id(trashed.id).unwrap().is_some());
+        assert!(repo.list_trash().unwrap().is_empty());
+
+        repo.trash(kept.id).expect("Failed to trash");
+        let deleted_at = repo.list_trash().unwrap()[0].deleted_at;
+        assert_eq!(repo.purge_trash(deleted_at).unwrap(), 0);
+        assert_eq!(
+            repo.purge_trash(deleted_at + chrono::Duration::seconds(1))
+                .unwrap(),
+            1
+        );
+        assert!(repo.restore(kept.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_usage_stats() {
+        let repo = create_test_repo();
+        let often = repo.save_text("often".to_string()).expect("Failed to save");
+        let once = repo.save_text("once".to_string()).expect("Failed to save");
+
+        let monday = Local.with_ymd_and_hms(2025, 6, 16, 10, 0, 0).unwrap();
+        let sunday_night = Local.with_ymd_and_hms(2025, 6, 15, 23, 59, 59).unwrap();
+        repo.record_use(&often, sunday_night).unwrap();
+        repo.record_use(&often, monday).unwrap();
+        repo.record_use(&often, monday).unwrap();
+        repo.record_use(&once, monday - chrono::Duration::hours(10))
+            .unwrap();
+
+        let stats = repo.usage_stats(monday).expect("Failed to read stats");
+        assert_eq!(stats.today, 3);
+        // Sunday belongs to the previous week
+        assert_eq!(stats.this_week, 3);
+        assert_eq!(stats.top.len(), 2);
+        assert_eq!(stats.top[0].content, "often");
+        assert_eq!(stats.top[0].use_count, 3);
+
+        let stats = repo
+            .usage_stats(monday + chrono::Duration::days(1))
+            .unwrap();
+        assert_eq!(stats.today, 0);
+        assert_eq!(stats.this_week, 3);
+
+        repo.reset_usage().unwrap();
+        assert_eq!(repo.usage_stats(monday).unwrap(), UsageStats::default());
+    }
+
     #[test]
     fn test_clear() {
         let repo = create_test_repo();
@@ -326,6 +2395,61 @@ mod tests {
         assert_eq!(repo.count(), 0);
     }
 
+    #[test]
+    fn test_clear_removes_usage_stats() {
+        let repo = create_test_repo();
+        let secret = repo.save_text("hunter2".to_string()).unwrap();
+        let now = Local::now();
+        repo.record_use(&secret, now).unwrap();
+        assert_eq!(repo.usage_stats(now).unwrap().top.len(), 1);
+
+        repo.clear().unwrap();
+        assert_eq!(repo.usage_stats(now).unwrap(), UsageStats::default());
+    }
+
+    #[test]
+    fn test_enforce_size_budget() {
+        let temp_dir = tempdir().expect("Failed to create temp dir");
+        let repo = ClipboardRepository::init(
+            temp_dir.path().join("test.db"),
+            temp_dir.path().join("images"),
+        )
+        .expect("Failed to create test repository");
+
+        let image_path = temp_dir.path().join("large.png");
+        fs::write(&image_path, vec![0u8; 4096]).expect("Failed to write image");
+        let image = repo
+            .save_image_from_path(image_path.to_string_lossy().to_string())
+            .expect("Failed to save");
+        for i in 0..5 {
+            repo.save_text(format!("Record {i}"))
+                .expect("Failed to save");
+        }
+
+        assert!(
+            repo.enforce_size_budget(u64::MAX, true)
+                .expect("Failed to enforce")
+                .is_empty()
+        );
+
+        // The image alone is over budget, so it is evicted before any text
+        let evicted = repo
+            .enforce_size_budget(2048, true)
+            .expect("Failed to enforce");
+        assert_eq!(evicted, vec![image.id]);
+        assert!(!image_path.exists());
+        assert_eq!(repo.count(), 5);
+
+        // Text records are evicted oldest first until under budget
+        let evicted = repo
+            .enforce_size_budget(1, false)
+            .expect("Failed to enforce");
+        assert_eq!(evicted.len(), 4);
+        let remaining = repo.get_recent(10).expect("Failed to get recent");
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].content, "Record 4");
+    }
+
     #[test]
     fn test_cleanup_old_records() {
         let repo = create_test_repo();
@@ -346,4 +2470,60 @@ mod tests {
         assert_eq!(recent[0].content, "Record 10");
         assert_eq!(recent[4].content, "Record 6");
     }
+
+    #[test]
+    fn test_trim_history() {
+        let repo = create_test_repo();
+        for i in 1..=4 {
+            repo.save_text(format!("Record {}", i))
+                .expect("Failed to save");
+        }
+
+        assert!(repo.trim_history(3, None).unwrap().is_empty());
+        assert_eq!(repo.count(), 3);
+
+        // The newest record is kept even when it alone is over budget
+        let evicted = repo.trim_history(3, Some((1, false))).unwrap();
+        assert_eq!(evicted.len(), 2);
+        assert_eq!(repo.get_recent(10).unwrap()[0].content, "Record 4");
+    }
+
+    #[test]
+    fn test_count_follows_changes() {
+        let repo = create_test_repo();
+        let first = repo.save_text("one".to_string()).unwrap();
+        let second = repo.save_text("two".to_string()).unwrap();
+        repo.save_text("three".to_string()).unwrap();
+        assert_eq!(repo.count(), 3);
+
+        repo.touch(first.id).unwrap();
+        repo.update(&second).unwrap();
+        assert_eq!(repo.count(), 3);
+        repo.trash(second.id).unwrap();
+        assert_eq!(repo.count(), 2);
+        repo.restore(second.id).unwrap();
+        assert_eq!(repo.count(), 3);
+        assert_eq!(repo.count(), repo.records_tree.len());
+
+        repo.clear().unwrap();
+        assert_eq!(repo.count(), 0);
+    }
+
+    #[test]
+    fn test_generation_follows_writes() {
+        let repo = create_test_repo();
+        let start = repo.generation();
+        let record = repo.save_text("one".to_string()).unwrap();
+        let saved = repo.generation();
+        assert_ne!(saved, start);
+
+        // Reads and writes that change nothing leave it alone
+        repo.get_recent(10).unwrap();
+        repo.search("one").unwrap();
+        assert!(!repo.delete(record.id + 1).unwrap());
+        assert_eq!(repo.generation(), saved);
+
+        repo.set_favorite(record.id, true).unwrap();
+        assert_ne!(repo.generation(), saved);
+    }
 }
@@ -0,0 +1,92 @@
+//! Local copy statistics, bucketed by calendar day.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use super::models::ContentType;
+
+/// Number of most-copied items reported in [`UsageStats`]
+pub const TOP_ITEMS: usize = 5;
+
+/// How often a piece of content has been copied from the history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemUsage {
+    /// Content of the most recent record copied with this content
+    pub content: String,
+    pub content_type: ContentType,
+    pub use_count: u64,
+}
+
+/// Summary shown in the settings stats section
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageStats {
+    pub today: u64,
+    pub this_week: u64,
+    /// Most-copied items, highest count first
+    pub top: Vec<ItemUsage>,
+}
+
+/// Calendar day a copy at `at` is counted towards, in local time
+pub fn day_bucket(at: DateTime<Local>) -> NaiveDate {
+    at.date_naive()
+}
+
+/// Key of a day in the counter tree. ISO dates sort chronologically as bytes.
+pub fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+/// First day (Monday) of the week containing `day`
+pub fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn local(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(y, m, d, h, min, s)
+            .earliest()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_day_bucket_across_midnight() {
+        let before = day_bucket(local(2025, 6, 10, 23, 59, 59));
+        let after = day_bucket(local(2025, 6, 11, 0, 0, 0));
+        assert_ne!(before, after);
+        assert_eq!(day_key(before), "2025-06-10");
+        assert_eq!(day_key(after), "2025-06-11");
+        assert_eq!(day_bucket(local(2025, 6, 11, 23, 59, 59)), after);
+
+        // New year's midnight
+        assert_eq!(
+            day_key(day_bucket(local(2024, 12, 31, 23, 59, 59))),
+            "2024-12-31"
+        );
+        assert_eq!(
+            day_key(day_bucket(local(2025, 1, 1, 0, 0, 0))),
+            "2025-01-01"
+        );
+        assert!(day_key(before) < day_key(after));
+    }
+
+    #[test]
+    fn test_week_start() {
+        // 2025-06-15 is a Sunday, 2025-06-16 a Monday
+        let sunday_night = day_bucket(local(2025, 6, 15, 23, 59, 59));
+        let monday_morning = day_bucket(local(2025, 6, 16, 0, 0, 0));
+        assert_eq!(
+            week_start(sunday_night),
+            NaiveDate::from_ymd_opt(2025, 6, 9).unwrap()
+        );
+        assert_eq!(week_start(monday_morning), monday_morning);
+        assert_eq!(
+            week_start(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+            NaiveDate::from_ymd_opt(2024, 12, 30).unwrap()
+        );
+    }
+}
@@ -0,0 +1,316 @@
+//! Storage abstraction used by the UI, so it can run against sled or memory.
+
+use chrono::{DateTime, Local};
+use std::path::Path;
+
+use super::errors::RepositoryError;
+use super::models::{ClipboardRecord, ContentType, RecordFlag, SearchScope, TrashedRecord};
+use super::repo::ClipboardRepository;
+use super::stats::UsageStats;
+
+/// Clipboard history operations needed by the board
+///
+/// Implemented by the sled-backed [`ClipboardRepository`] and, with the `test-util`
+/// feature, the in-memory `MemStore`.
+pub trait ClipboardStore: Send + Sync {
+    /// Save a record with a new timestamp ID
+    fn save(
+        &self,
+        content: String,
+        content_type: ContentType,
+    ) -> Result<ClipboardRecord, RepositoryError>;
+
+    /// Save an image record for an image file already in the images directory
+    fn save_image_from_path(&self, file_path: String) -> Result<ClipboardRecord, RepositoryError>;
+
+    /// Save several images as one group, returned in save order
+    fn save_image_group(
+        &self,
+        file_paths: Vec<String>,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError>;
+
+    fn get_by_id(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Most recent records first
+    fn get_recent(&self, limit: usize) -> Result<Vec<ClipboardRecord>, RepositoryError>;
+
+    /// Case-insensitive keyword search, most recent first
+    fn search_with_scope(
+        &self,
+        keyword: &str,
+        scope: SearchScope,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError>;
+
+    /// Insert or overwrite a record
+    fn update(&self, record: &ClipboardRecord) -> Result<(), RepositoryError>;
+
+    fn update_content(
+        &self,
+        id: u64,
+        content: String,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    fn set_favorite(
+        &self,
+        id: u64,
+        favorite: bool,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Flag a record with a color, or clear its flag with `None`
+    fn set_flag(
+        &self,
+        id: u64,
+        flag: Option<RecordFlag>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Add a tag to a record
+    fn add_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Remove a tag from a record
+    fn remove_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Every tag used in the history, sorted
+    fn all_tags(&self) -> Result<Vec<String>, RepositoryError>;
+
+    /// Set or clear (with `None` or blank text) a record's note
+    fn set_note(
+        &self,
+        id: u64,
+        note: Option<String>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Store the manual order of favorites, given as record IDs first to last
+    fn set_favorite_order(&self, ids: &[u64]) -> Result<Vec<ClipboardRecord>, RepositoryError>;
+
+    /// Move a record to the top under a fresh ID, keeping everything else
+    fn touch(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    /// Delete records expired at `now`, returning their IDs
+    fn remove_expired(&self, now: DateTime<Local>) -> Result<Vec<u64>, RepositoryError>;
+
+    fn delete(&self, id: u64) -> Result<bool, RepositoryError>;
+
+    fn trash(&self, id: u64) -> Result<bool, RepositoryError>;
+
+    /// Most recently deleted first
+    fn list_trash(&self) -> Result<Vec<TrashedRecord>, RepositoryError>;
+
+    fn restore(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError>;
+
+    fn purge_trash(&self, cutoff: DateTime<Local>) -> Result<usize, RepositoryError>;
+
+    fn clear(&self) -> Result<(), RepositoryError>;
+
+    fn count(&self) -> usize;
+
+    /// Counter that changes whenever a record is saved, changed or removed
+    fn generation(&self) -> u64;
+
+    /// Remove the oldest records beyond `keep_count`
+    fn cleanup_old_records(&self, keep_count: usize) -> Result<usize, RepositoryError>;
+
+    /// Delete records until the history fits in `max_bytes`, returning the deleted IDs
+    fn enforce_size_budget(
+        &self,
+        max_bytes: u64,
+        images_first: bool,
+    ) -> Result<Vec<u64>, RepositoryError>;
+
+    /// Keep the newest `max_records` records and fit the optional size budget, returning
+    /// the IDs evicted by the budget
+    fn trim_history(
+        &self,
+        max_records: usize,
+        size_budget: Option<(u64, bool)>,
+    ) -> Result<Vec<u64>, RepositoryError>;
+
+    /// Save a single record to a file, returning `false` when it doesn't exist
+    fn export_single(&self, id: u64, dest: &Path) -> Result<bool, RepositoryError>;
+
+    fn record_use(
+        &self,
+        record: &ClipboardRecord,
+        at: DateTime<Local>,
+    ) -> Result<(), RepositoryError>;
+
+    fn usage_stats(&self, now: DateTime<Local>) -> Result<UsageStats, RepositoryError>;
+
+    fn reset_usage(&self) -> Result<(), RepositoryError>;
+
+    fn save_text(&self, content: String) -> Result<ClipboardRecord, RepositoryError> {
+        self.save(content, ContentType::Text)
+    }
+
+    fn search(&self, keyword: &str) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        self.search_with_scope(keyword, SearchScope::TextOnly)
+    }
+}
+
+impl ClipboardStore for ClipboardRepository {
+    fn save(
+        &self,
+        content: String,
+        content_type: ContentType,
+    ) -> Result<ClipboardRecord, RepositoryError> {
+        ClipboardRepository::save(self, content, content_type)
+    }
+
+    fn save_image_from_path(&self, file_path: String) -> Result<ClipboardRecord, RepositoryError> {
+        ClipboardRepository::save_image_from_path(self, file_path)
+    }
+
+    fn save_image_group(
+        &self,
+        file_paths: Vec<String>,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::save_image_group(self, file_paths)
+    }
+
+    fn get_by_id(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::get_by_id(self, id)
+    }
+
+    fn get_recent(&self, limit: usize) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::get_recent(self, limit)
+    }
+
+    fn search_with_scope(
+        &self,
+        keyword: &str,
+        scope: SearchScope,
+    ) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::search_with_scope(self, keyword, scope)
+    }
+
+    fn update(&self, record: &ClipboardRecord) -> Result<(), RepositoryError> {
+        ClipboardRepository::update(self, record)
+    }
+
+    fn update_content(
+        &self,
+        id: u64,
+        content: String,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::update_content(self, id, content)
+    }
+
+    fn set_favorite(
+        &self,
+        id: u64,
+        favorite: bool,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::set_favorite(self, id, favorite)
+    }
+
+    fn set_flag(
+        &self,
+        id: u64,
+        flag: Option<RecordFlag>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::set_flag(self, id, flag)
+    }
+
+    fn add_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::add_tag(self, id, tag)
+    }
+
+    fn remove_tag(&self, id: u64, tag: &str) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::remove_tag(self, id, tag)
+    }
+
+    fn all_tags(&self) -> Result<Vec<String>, RepositoryError> {
+        ClipboardRepository::all_tags(self)
+    }
+
+    fn set_note(
+        &self,
+        id: u64,
+        note: Option<String>,
+    ) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::set_note(self, id, note)
+    }
+
+    fn set_favorite_order(&self, ids: &[u64]) -> Result<Vec<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::set_favorite_order(self, ids)
+    }
+
+    fn touch(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::touch(self, id)
+    }
+
+    fn remove_expired(&self, now: DateTime<Local>) -> Result<Vec<u64>, RepositoryError> {
+        ClipboardRepository::remove_expired(self, now)
+    }
+
+    fn delete(&self, id: u64) -> Result<bool, RepositoryError> {
+        ClipboardRepository::delete(self, id)
+    }
+
+    fn trash(&self, id: u64) -> Result<bool, RepositoryError> {
+        ClipboardRepository::trash(self, id)
+    }
+
+    fn list_trash(&self) -> Result<Vec<TrashedRecord>, RepositoryError> {
+        ClipboardRepository::list_trash(self)
+    }
+
+    fn restore(&self, id: u64) -> Result<Option<ClipboardRecord>, RepositoryError> {
+        ClipboardRepository::restore(self, id)
+    }
+
+    fn purge_trash(&self, cutoff: DateTime<Local>) -> Result<usize, RepositoryError> {
+        ClipboardRepository::purge_trash(self, cutoff)
+    }
+
+    fn clear(&self) -> Result<(), RepositoryError> {
+        ClipboardRepository::clear(self)
+    }
+
+    fn count(&self) -> usize {
+        ClipboardRepository::count(self)
+    }
+
+    fn generation(&self) -> u64 {
+        ClipboardRepository::generation(self)
+    }
+
+    fn cleanup_old_records(&self, keep_count: usize) -> Result<usize, RepositoryError> {
+        ClipboardRepository::cleanup_old_records(self, keep_count)
+    }
+
+    fn enforce_size_budget(
+        &self,
+        max_bytes: u64,
+        images_first: bool,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        ClipboardRepository::enforce_size_budget(self, max_bytes, images_first)
+    }
+
+    fn trim_history(
+        &self,
+        max_records: usize,
+        size_budget: Option<(u64, bool)>,
+    ) -> Result<Vec<u64>, RepositoryError> {
+        ClipboardRepository::trim_history(self, max_records, size_budget)
+    }
+
+    fn export_single(&self, id: u64, dest: &Path) -> Result<bool, RepositoryError> {
+        ClipboardRepository::export_single(self, id, dest)
+    }
+
+    fn record_use(
+        &self,
+        record: &ClipboardRecord,
+        at: DateTime<Local>,
+    ) -> Result<(), RepositoryError> {
+        ClipboardRepository::record_use(self, record, at)
+    }
+
+    fn usage_stats(&self, now: DateTime<Local>) -> Result<UsageStats, RepositoryError> {
+        ClipboardRepository::usage_stats(self, now)
+    }
+
+    fn reset_usage(&self) -> Result<(), RepositoryError> {
+        ClipboardRepository::reset_usage(self)
+    }
+}
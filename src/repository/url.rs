@@ -0,0 +1,120 @@
+//! Recognizing URLs and reducing trivially different forms of one URL to the same text
+
+/// Query parameters that only track where a link was shared, matched case-insensitively.
+/// Entries ending in `_` match any parameter with that prefix.
+const TRACKING_PARAMS: &[&str] = &[
+    "utm_", "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "mc_cid", "mc_eid",
+    "igshid", "_hsenc", "_hsmi", "mkt_tok",
+];
+
+/// Whether the text is a single http(s) URL
+pub fn is_url(content: &str) -> bool {
+    let content = content.trim();
+    (content.starts_with("http://") || content.starts_with("https://"))
+        && !content.contains(char::is_whitespace)
+}
+
+fn is_tracking_param(pair: &str) -> bool {
+    let name = pair
+        .split('=')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    TRACKING_PARAMS
+        .iter()
+        .any(|param| match param.strip_suffix('_') {
+            Some(_) => name.starts_with(param),
+            None => name == *param,
+        })
+}
+
+/// Form of a URL used to detect duplicates
+///
+/// The scheme and host are lowercased, trailing slashes of the path and tracking parameters
+/// such as `utm_source` are dropped, and other parameters and the fragment are kept in order.
+/// Text that isn't a URL is returned trimmed but otherwise unchanged.
+pub fn canonicalize_url(url: &str) -> String {
+    let url = url.trim();
+    if !is_url(url) {
+        return url.to_string();
+    }
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let (rest, query) = match rest.split_once('?') {
+        Some((rest, query)) => (rest, Some(query)),
+        None => (rest, None),
+    };
+    // `is_url` guarantees the scheme separator
+    let (scheme, rest) = rest.split_once("://").unwrap_or(("", rest));
+    let (host, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+
+    let mut canonical = format!(
+        "{}://{}{}",
+        scheme.to_ascii_lowercase(),
+        host.to_ascii_lowercase(),
+        path.trim_end_matches('/')
+    );
+    let params: Vec<&str> = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter(|pair| !pair.is_empty() && !is_tracking_param(pair))
+        .collect();
+    if !params.is_empty() {
+        canonical.push('?');
+        canonical.push_str(&params.join("&"));
+    }
+    if let Some(fragment) = fragment.filter(|fragment| !fragment.is_empty()) {
+        canonical.push('#');
+        canonical.push_str(fragment);
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/a?b=c"));
+        assert!(is_url("  http://example.com\n"));
+        assert!(!is_url("see https://example.com"));
+        assert!(!is_url("ftp://example.com"));
+    }
+
+    #[test]
+    fn test_canonicalize_url() {
+        assert_eq!(
+            canonicalize_url("https://example.com/docs/"),
+            "https://example.com/docs"
+        );
+        assert_eq!(
+            canonicalize_url("https://Example.COM/"),
+            "https://example.com"
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/a?utm_source=x&id=3&UTM_Medium=y&fbclid=z"),
+            "https://example.com/a?id=3"
+        );
+        assert_eq!(
+            canonicalize_url("https://example.com/a/?utm_campaign=x#top"),
+            "https://example.com/a#top"
+        );
+        // The path keeps its case and other parameters keep their order
+        assert_eq!(
+            canonicalize_url("https://example.com/Page?b=2&a=1"),
+            "https://example.com/Page?b=2&a=1"
+        );
+        // Parameters that merely contain a tracking name are kept
+        assert_eq!(
+            canonicalize_url("https://example.com/?my_utm_source=1&gclidx=2"),
+            "https://example.com?my_utm_source=1&gclidx=2"
+        );
+        assert_eq!(canonicalize_url(" not a url "), "not a url");
+    }
+}
@@ -1,4 +1,4 @@
-use windows_sys::Win32::Foundation::{ERROR_ALREADY_EXISTS, GetLastError};
+use windows_sys::Win32::Foundation::{CloseHandle, ERROR_ALREADY_EXISTS, GetLastError, HANDLE};
 use windows_sys::Win32::System::Threading::CreateMutexW;
 use windows_sys::Win32::UI::WindowsAndMessaging::{
     FindWindowW, SW_RESTORE, SetForegroundWindow, ShowWindow,
@@ -7,7 +7,19 @@ use windows_sys::Win32::UI::WindowsAndMessaging::{
 use std::ffi::OsStr;
 use std::os::windows::ffi::OsStrExt;
 
-pub fn ensure_single_instance() -> bool {
+/// Named mutex held while this instance runs, released when dropped
+pub struct InstanceGuard(HANDLE);
+
+impl Drop for InstanceGuard {
+    fn drop(&mut self) {
+        unsafe {
+            CloseHandle(self.0);
+        }
+    }
+}
+
+/// Claim the single-instance mutex, activating the running instance if there is one
+pub fn ensure_single_instance() -> Option<InstanceGuard> {
     let mutex_name = "RopySingleInstanceMutex";
     let wide_name: Vec<u16> = OsStr::new(mutex_name)
         .encode_wide()
@@ -17,8 +29,9 @@ pub fn ensure_single_instance() -> bool {
     unsafe {
         let mutex = CreateMutexW(std::ptr::null(), 0, wide_name.as_ptr());
         if mutex.is_null() {
-            return false;
+            return None;
         }
+        let guard = InstanceGuard(mutex);
 
         if GetLastError() == ERROR_ALREADY_EXISTS {
             // Try to activate existing window
@@ -31,8 +44,8 @@ pub fn ensure_single_instance() -> bool {
                 ShowWindow(hwnd, SW_RESTORE);
                 SetForegroundWindow(hwnd);
             }
-            return false;
+            return None;
         }
-        true
+        Some(guard)
     }
 }